@@ -4,7 +4,7 @@ use crate::selection::Selection;
 use crate::ui_jumpto::JumpTo;
 use crate::ui_popup::Popup;
 use crate::ui_search::Search;
-use intelhexlib::IntelHex;
+use intelhexlib::{Endian, IntelHex};
 use std::cell::RefCell;
 use std::ops::RangeInclusive;
 use std::rc::Rc;
@@ -20,12 +20,6 @@ pub mod colors {
     pub const SHADOW: Color32 = Color32::from_black_alpha(150);
 }
 
-#[derive(PartialEq, Eq)]
-pub enum Endianness {
-    Little,
-    Big,
-}
-
 pub struct HexSession {
     /// Name of the session (aka filename)
     pub name: String,
@@ -34,7 +28,7 @@ pub struct HexSession {
     /// Address range of the hex data
     pub addr: RangeInclusive<usize>,
     /// Endianness of the hex data
-    pub endianness: Endianness,
+    pub endianness: Endian,
     /// Handler for bytes editing
     pub editor: ByteEdit,
     /// Handler for GUI feature of bytes selection
@@ -76,7 +70,7 @@ impl Default for HexSession {
             name: "Untitled".to_string(),
             ih: IntelHex::default(),
             addr: 0..=0,
-            endianness: Endianness::Little,
+            endianness: Endian::Little,
             editor: ByteEdit::default(),
             selection: Selection::default(),
             search: Search::default(),
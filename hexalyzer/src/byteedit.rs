@@ -1,5 +1,7 @@
 use crate::app::HexSession;
+use intelhexlib::{IntelHex, IntelHexError};
 use std::collections::HashMap;
+use std::ops::Range;
 
 #[derive(Default)]
 pub struct ByteEdit {
@@ -81,6 +83,37 @@ impl HexSession {
         }
     }
 
+    /// Apply the transform (e.g. byte swap) to the selected bytes. The original values
+    /// of the changed bytes are tracked, so that they can be restored.
+    pub(crate) fn transform_selection(
+        &mut self,
+        transform: impl FnOnce(&mut IntelHex, Range<usize>) -> Result<(), IntelHexError>,
+    ) -> Result<(), IntelHexError> {
+        let Some([start, end]) = self.selection.range else {
+            return Ok(());
+        };
+
+        // Handle reversed range
+        let range = start.min(end)..start.max(end) + 1;
+        let prev_values = self.ih.read_range_safe(range.start, range.len());
+
+        transform(&mut self.ih, range.clone())?;
+
+        for (addr, prev_value) in range.zip(prev_values) {
+            if let Some(prev) = prev_value
+                && self.ih.read_byte(addr) != Some(prev)
+            {
+                self.editor.modified.entry(addr).or_insert(prev);
+            }
+        }
+
+        // If there are search results - redo it
+        if !self.search.results.is_empty() {
+            self.search.redo();
+        }
+        Ok(())
+    }
+
     /// Restore all modified bytes to their original values
     pub(crate) fn restore(&mut self) {
         for (&addr, &orig_value) in &self.editor.modified {
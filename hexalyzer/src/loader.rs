@@ -1,5 +1,5 @@
 use crate::app::{HexSession, HexViewerApp};
-use intelhexlib::IntelHex;
+use intelhexlib::{ElfAddress, IntelHex};
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
@@ -7,6 +7,8 @@ use std::path::PathBuf;
 #[derive(Debug)]
 pub enum FileKind {
     Hex,
+    TiTxt,
+    TekHex,
     Bin,
     Elf,
     Unknown,
@@ -35,6 +37,30 @@ fn detect_file_kind(path: &PathBuf) -> std::io::Result<FileKind> {
         return Ok(FileKind::Hex);
     }
 
+    // TI-TXT address line check: '@' followed by hex digits up to the end of the line
+    // (only if the line ends within the read bytes)
+    let first_line = buf
+        .iter()
+        .position(|&b| b == b'\n')
+        .or_else(|| (n < 32).then_some(n))
+        .map(|end| buf[..end].trim_ascii_end());
+    if let Some([b'@', digits @ ..]) = first_line
+        && !digits.is_empty()
+        && digits.iter().all(u8::is_ascii_hexdigit)
+    {
+        return Ok(FileKind::TiTxt);
+    }
+
+    // Tektronix record check: standard ('/' followed by 8+ hex digits)
+    // or extended ('%' followed by 2 hex digits of length and the type digit)
+    let is_standard_tek =
+        buf.len() >= 9 && buf[0] == b'/' && buf[1..9].iter().all(u8::is_ascii_hexdigit);
+    let is_extended_tek = matches!(buf, [b'%', len_hi, len_lo, rtype, ..]
+        if len_hi.is_ascii_hexdigit() && len_lo.is_ascii_hexdigit() && rtype.is_ascii_digit());
+    if is_standard_tek || is_extended_tek {
+        return Ok(FileKind::TekHex);
+    }
+
     // Otherwise consider the file as raw binary
     Ok(FileKind::Bin)
 }
@@ -70,12 +96,15 @@ impl HexViewerApp {
 
         let res = match file_type {
             FileKind::Hex => ih.load_hex(path),
+            FileKind::TiTxt => ih.load_titxt(path),
+            FileKind::TekHex => ih.load_tekhex(path),
             FileKind::Bin => {
                 // Set base addr to 0 to avoid complex logic around waiting
                 // to fill the pop-up. Can re-addr later.
                 ih.load_bin(path, 0)
             }
-            FileKind::Elf => Err("ELF files are not yet supported".into()),
+            // Place the segments at their load (flash) addresses, the same way objcopy does
+            FileKind::Elf => ih.load_elf(path, ElfAddress::Physical),
             FileKind::Unknown => Err("Could not determine the file type".into()),
         };
 
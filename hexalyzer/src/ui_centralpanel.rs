@@ -2,8 +2,12 @@ use crate::app::{HexSession, colors};
 use crate::events::collect_ui_events;
 use crate::ui_button::light_mono_button;
 use eframe::egui;
+use intelhexlib::Chunk;
 use std::ops::Range;
 
+/// Largest number of bytes per row selectable in the View menu
+const MAX_BYTES_PER_ROW: usize = 32;
+
 impl HexSession {
     /// Displays the central panel of the UI for rendering the hex editor content.
     /// This function draws the main content area of the application. It uses the `egui::CentralPanel`
@@ -104,6 +108,19 @@ impl HexSession {
             let start = self.addr.start() + row * bytes_per_row;
             let end = start + bytes_per_row;
 
+            // Fetch the bytes of the row once (borrowed slices, no per-byte lookups)
+            let mut row_bytes = [None; MAX_BYTES_PER_ROW];
+            for chunk in self.ih.chunks_in(start..end) {
+                if let Chunk::Data(addr, data) = chunk {
+                    for (i, &b) in data.iter().enumerate() {
+                        if let Some(slot) = row_bytes.get_mut(addr - start + i) {
+                            *slot = Some(b);
+                        }
+                    }
+                }
+            }
+            let read_byte = |addr: usize| row_bytes.get(addr - start).copied().flatten();
+
             // Display address (fixed width, monospaced)
             ui.monospace(format!("{start:08X}"));
 
@@ -116,7 +133,7 @@ impl HexSession {
                 ui.spacing_mut().item_spacing.x = 0.0;
 
                 // Determine is the current byte selected
-                let byte = self.ih.read_byte(addr);
+                let byte = read_byte(addr);
                 let is_selected = byte.is_some() && self.selection.is_addr_within_range(addr);
 
                 // Change color of every other byte for better readability
@@ -176,7 +193,7 @@ impl HexSession {
                 ui.spacing_mut().item_spacing.x = 1.0;
 
                 // Determine display char
-                let byte = self.ih.read_byte(addr);
+                let byte = read_byte(addr);
                 let ch = byte.map_or(' ', |b| if b.is_ascii_graphic() { b as char } else { '.' });
 
                 // Determine is char selected
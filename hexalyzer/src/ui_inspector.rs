@@ -1,6 +1,7 @@
-use crate::app::{Endianness, HexSession};
+use crate::app::HexSession;
 use eframe::egui;
 use eframe::egui::Ui;
+use intelhexlib::Endian;
 
 #[allow(clippy::needless_pass_by_value)]
 /// Format the number so that it has separators (for readability)
@@ -54,8 +55,8 @@ impl HexSession {
     #[allow(clippy::similar_names, clippy::too_many_lines)]
     /// Displays the inspector panel for the selected data.
     pub(crate) fn show_data_inspector_contents(&mut self, ui: &mut Ui) {
-        ui.radio_value(&mut self.endianness, Endianness::Little, "Little Endian");
-        ui.radio_value(&mut self.endianness, Endianness::Big, "Big Endian");
+        ui.radio_value(&mut self.endianness, Endian::Little, "Little Endian");
+        ui.radio_value(&mut self.endianness, Endian::Big, "Big Endian");
 
         ui.add_space(5.0);
         ui.separator();
@@ -82,24 +83,15 @@ impl HexSession {
                     return;
                 };
 
-                let mut bytes: Vec<u8> = Vec::new();
-                for addr in min..=max {
-                    if let Some(b) = self.ih.read_byte(addr) {
-                        bytes.push(b);
-                    }
-                }
-
-                if self.endianness == Endianness::Big && bytes.len() > 1 {
-                    bytes.reverse();
-                }
+                let ih = &self.ih;
+                let endian = self.endianness;
 
-                match bytes.len() {
-                    1 => {
-                        let val_u8 = u8::from_le_bytes([bytes[0]]);
+                match (max - min + 1, ih.read_byte(min)) {
+                    (1, Some(val_u8)) => {
                         ui.label("u8");
                         ui.label(val_u8.to_string());
                         ui.end_row();
-                        let val_i8 = i8::from_le_bytes([bytes[0]]);
+                        let val_i8 = i8::from_le_bytes([val_u8]);
                         ui.label("i8");
                         ui.label(val_i8.to_string());
                         ui.end_row();
@@ -107,14 +99,13 @@ impl HexSession {
                         ui.label("bin");
                         ui.label(val_bin);
                     }
-                    2 => {
-                        let val_u16 =
-                            u16::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
+                    (2, _)
+                        if let (Ok(val_u16), Ok(val_i16)) =
+                            (ih.read_u16(min, endian), ih.read_i16(min, endian)) =>
+                    {
                         ui.label("u16");
                         ui.label(format_with_separators(val_u16));
                         ui.end_row();
-                        let val_i16 =
-                            i16::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
                         ui.label("i16");
                         ui.label(format_with_separators(val_i16));
                         ui.end_row();
@@ -122,19 +113,19 @@ impl HexSession {
                         ui.label("bin");
                         ui.label(val_bin);
                     }
-                    4 => {
-                        let val_u32 =
-                            u32::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
+                    (4, _)
+                        if let (Ok(val_u32), Ok(val_i32), Ok(val_f32)) = (
+                            ih.read_u32(min, endian),
+                            ih.read_i32(min, endian),
+                            ih.read_f32(min, endian),
+                        ) =>
+                    {
                         ui.label("u32");
                         ui.label(format_with_separators(val_u32));
                         ui.end_row();
-                        let val_i32 =
-                            i32::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
                         ui.label("i32");
                         ui.label(format_with_separators(val_i32));
                         ui.end_row();
-                        let val_f32 =
-                            f32::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
                         ui.label("f32");
                         ui.label(format_float(val_f32));
                         ui.end_row();
@@ -145,19 +136,19 @@ impl HexSession {
                         });
                         ui.label(multiline);
                     }
-                    8 => {
-                        let val_u64 =
-                            u64::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
+                    (8, _)
+                        if let (Ok(val_u64), Ok(val_i64), Ok(val_f64)) = (
+                            ih.read_u64(min, endian),
+                            ih.read_i64(min, endian),
+                            ih.read_f64(min, endian),
+                        ) =>
+                    {
                         ui.label("u64");
                         ui.label(format_with_separators(val_u64));
                         ui.end_row();
-                        let val_i64 =
-                            i64::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
                         ui.label("i64");
                         ui.label(format_with_separators(val_i64));
                         ui.end_row();
-                        let val_f64 =
-                            f64::from_le_bytes(bytes.as_slice().try_into().unwrap_or_default());
                         ui.label("f64");
                         ui.label(format_float(val_f64));
                         ui.end_row();
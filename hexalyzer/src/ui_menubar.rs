@@ -1,11 +1,15 @@
 use crate::HexViewerApp;
 use crate::ui_popup::PopupType;
 use eframe::egui;
+use intelhexlib::{ExportScope, IntelHex, SourceFormat, TekHexFormat};
 use std::error::Error;
 
 enum SaveFormat {
     Bin,
     Hex,
+    TiTxt,
+    TekHex,
+    Source(SourceFormat),
 }
 
 fn format_from_extension(path: &std::path::Path) -> Option<SaveFormat> {
@@ -17,10 +21,48 @@ fn format_from_extension(path: &std::path::Path) -> Option<SaveFormat> {
     {
         "bin" => Some(SaveFormat::Bin),
         "hex" => Some(SaveFormat::Hex),
+        "txt" => Some(SaveFormat::TiTxt),
+        "tek" => Some(SaveFormat::TekHex),
+        "h" => Some(SaveFormat::Source(SourceFormat::CHeader)),
+        "rs" => Some(SaveFormat::Source(SourceFormat::RustArray)),
+        "s" | "asm" => Some(SaveFormat::Source(SourceFormat::GnuAsm)),
         _ => None,
     }
 }
 
+/// Derive a valid identifier for the exported arrays from the file name.
+fn array_name(path: &std::path::Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let mut name: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Save the data into the file in the format given by its extension (binary by default).
+fn export_to_file(ih: &mut IntelHex, mut path: std::path::PathBuf) -> Result<(), Box<dyn Error>> {
+    if path.extension().is_none() {
+        path.set_extension("bin");
+    }
+
+    match format_from_extension(&path).unwrap_or(SaveFormat::Bin) {
+        SaveFormat::Bin => ih.write_bin(path, 0x00),
+        SaveFormat::Hex => ih.write_hex(path),
+        SaveFormat::TiTxt => ih.write_titxt(path),
+        SaveFormat::TekHex => ih.write_tekhex(path, TekHexFormat::Extended),
+        SaveFormat::Source(format) => {
+            ih.write_source(&path, format, &array_name(&path), ExportScope::AllSegments)
+        }
+    }
+}
+
 impl HexViewerApp {
     /// Displays the top menu bar with File, Edit, View, and About buttons
     pub(crate) fn show_menu_bar(&mut self, ctx: &egui::Context) {
@@ -43,21 +85,12 @@ impl HexViewerApp {
                         if ui.button("Export file...").clicked()
                             && let Some(curr_session) = self.get_curr_session_mut()
                             && curr_session.ih.size != 0
-                            && let Some(mut path) = rfd::FileDialog::new()
+                            && let Some(path) = rfd::FileDialog::new()
                                 .set_title("Save As")
                                 .set_file_name(curr_session.name.clone())
                                 .save_file()
                         {
-                            if path.extension().is_none() {
-                                path.set_extension("bin");
-                            }
-
-                            let format = format_from_extension(&path).unwrap_or(SaveFormat::Bin);
-
-                            let res: Result<(), Box<dyn Error>> = match format {
-                                SaveFormat::Bin => curr_session.ih.write_bin(path, 0x00),
-                                SaveFormat::Hex => curr_session.ih.write_hex(path),
-                            };
+                            let res = export_to_file(&mut curr_session.ih, path);
                             if let Err(msg) = res {
                                 self.error.borrow_mut().replace(msg.to_string());
                             }
@@ -90,6 +123,36 @@ impl HexViewerApp {
                         {
                             curr_session.restore();
                         }
+
+                        ui.separator();
+
+                        // TRANSFORM SELECTION SUBMENU
+                        ui.menu_button("Transform selection", |ui| {
+                            for (label, word_size) in [
+                                ("Swap bytes (16-bit)", 2),
+                                ("Swap bytes (32-bit)", 4),
+                                ("Swap bytes (64-bit)", 8),
+                            ] {
+                                if ui.button(label).clicked()
+                                    && let Some(curr_session) = self.get_curr_session_mut()
+                                    && let Err(err) =
+                                        curr_session.transform_selection(|ih, range| {
+                                            ih.swap_bytes(range, word_size)
+                                        })
+                                {
+                                    self.error.borrow_mut().replace(err.to_string());
+                                }
+                            }
+
+                            if ui.button("Swap 16-bit halves (32-bit)").clicked()
+                                && let Some(curr_session) = self.get_curr_session_mut()
+                                && let Err(err) = curr_session.transform_selection(|ih, range| {
+                                    ih.reorder(range, &[2, 3, 0, 1])
+                                })
+                            {
+                                self.error.borrow_mut().replace(err.to_string());
+                            }
+                        });
                     });
 
                     // VIEW BUTTON
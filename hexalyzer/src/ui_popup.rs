@@ -187,8 +187,8 @@ impl HexViewerApp {
                 self.popup.text_input.clear();
 
                 if let Some(curr_session) = self.get_curr_session_mut() {
-                    // Re-address the IntelHex
-                    match curr_session.ih.relocate(addr) {
+                    // Re-address the IntelHex (keep the entry point pointing to the same code)
+                    match curr_session.ih.relocate_with_start_address(addr) {
                         Ok(()) => {}
                         Err(err) => {
                             self.popup.clear();
@@ -1,5 +1,6 @@
 use crate::app::HexSession;
 use eframe::egui;
+use intelhexlib::Pattern;
 
 #[derive(Default, PartialEq, Clone)]
 struct SearchState {
@@ -108,8 +109,8 @@ impl HexSession {
                 let is_ascii = self.search.current.is_ascii;
 
                 // If pattern valid -> search, otherwise -> clear results
-                if let Some(pattern) = parse_str_into_bytes(input, is_ascii) {
-                    self.search.results = search_bmh(self.ih.bytes(), &pattern);
+                if let Some(pattern) = parse_str_into_pattern(input, is_ascii) {
+                    self.search.results = self.ih.find_all(&pattern);
                     self.search.length = pattern.len();
                 } else {
                     self.search.results.clear();
@@ -145,74 +146,14 @@ impl HexSession {
     }
 }
 
-// TODO: 1) add SIMD acceleration; 2) Replace with KMP search?
-
-#[allow(
-    clippy::cast_possible_truncation,
-    clippy::cast_sign_loss,
-    clippy::cast_possible_wrap
-)]
-/// Boyer–Moore–Horspool algorithm for `BTreeMap<usize, u8>`.
-/// Returns the starting addresses of all matches.
-fn search_bmh(map_iter: impl Iterator<Item = (usize, u8)>, pattern: &[u8]) -> Vec<usize> {
-    let m = pattern.len();
-    if m == 0 || m > u8::MAX as usize {
-        return vec![];
-    }
-
-    // Consume the iterator once into an indexable representation.
-    // This does not clone the BTreeMap, only copies (usize, u8) pairs.
-    let haystack: Vec<(usize, u8)> = map_iter.collect();
-
-    // Check if length of address is less than the pattern
-    let n = haystack.len();
-    if n < m {
-        return vec![];
-    }
-
-    // Build bad match table
-    let mut bad_match = [m as u8; 256];
-    for i in 0..m - 1 {
-        bad_match[pattern[i] as usize] = (m - 1 - i) as u8;
-    }
-
-    // Prepare result collection
-    let mut results = Vec::new();
-
-    // Main BMH loop
-    let mut i = 0; // index into addrs[]
-    while i <= n - m {
-        // Compare pattern from right to left
-        let mut j = (m - 1) as isize;
-        while j >= 0 && haystack[i + j as usize].1 == pattern[j as usize] {
-            j -= 1;
-        }
-
-        if j < 0 {
-            // Match found
-            results.push(haystack[i].0);
-            i += 1; // advance minimally
-        } else {
-            // Mismatch -> skip using last byte of window
-            let last_byte = haystack[i + m - 1].1;
-            i += bad_match[last_byte as usize] as usize;
-        }
-    }
-
-    results
-}
-
-fn parse_str_into_bytes(s: &str, is_ascii_repr: bool) -> Option<Vec<u8>> {
-    if is_ascii_repr {
-        return Some(s.as_bytes().to_vec());
-    }
-
-    if s.len().is_multiple_of(2) {
-        (0..s.len())
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
-            .collect()
+/// Parse the user input into a search pattern.
+/// Hex input may contain `?` nibble wildcards (e.g. `DE ?? BE EF`).
+/// Matches may span the gaps between segments, as the data is searched as a whole.
+fn parse_str_into_pattern(s: &str, is_ascii_repr: bool) -> Option<Pattern> {
+    let pattern = if is_ascii_repr {
+        Pattern::new(s.as_bytes())
     } else {
-        None
-    }
+        s.parse().ok()?
+    };
+    Some(pattern.across_gaps())
 }
@@ -56,6 +56,18 @@ impl HexViewerApp {
                                 let size = format_with_separators(curr_session.ih.size);
                                 ui.label(format!("{size} bytes"));
                                 ui.end_row();
+
+                                ui.with_layout(
+                                    egui::Layout::left_to_right(egui::Align::LEFT),
+                                    |ui| {
+                                        ui.label("Data Size");
+                                    },
+                                );
+                                let data_len =
+                                    format_with_separators(curr_session.ih.total_data_len());
+                                let segments = curr_session.ih.segments().count();
+                                ui.label(format!("{data_len} bytes in {segments} segment(s)"));
+                                ui.end_row();
                             });
 
                         ui.add_space(5.0);
@@ -0,0 +1,242 @@
+use crate::files::{load, save, stream_to_bin};
+use crate::{Command, EXIT_DIFFERENT};
+use intelhexlib::{IntelHex, StartAddress};
+use std::error::Error;
+use std::ops::Range;
+use std::process::ExitCode;
+
+/// Number of bytes per row of the hexdump
+const DUMP_ROW_LEN: usize = 16;
+
+/// Execute the subcommand. Raw binary inputs are placed at the `base` address.
+pub fn run(command: Command, base: usize) -> Result<ExitCode, Box<dyn Error>> {
+    match command {
+        Command::Info { input } => {
+            let ih = load(&input, base)?;
+            print_info(&ih);
+        }
+        Command::Convert {
+            input,
+            output,
+            gap_fill,
+            stream,
+        } => {
+            if stream {
+                stream_to_bin(&input, &output, gap_fill)?;
+            } else {
+                let mut ih = load(&input, base)?;
+                save(&mut ih, &output, gap_fill)?;
+            }
+        }
+        Command::Merge {
+            inputs,
+            output,
+            policy,
+        } => {
+            let mut ih = IntelHex::new();
+            for input in &inputs {
+                ih.merge(&load(input, base)?, policy.into())?;
+            }
+            save(&mut ih, &output, 0xFF)?;
+        }
+        Command::Diff { first, second } => {
+            let diffs = load(&first, base)?.diff(&load(&second, base)?);
+            for diff in &diffs {
+                println!("{diff}");
+            }
+            if !diffs.is_empty() {
+                return Ok(ExitCode::from(EXIT_DIFFERENT));
+            }
+        }
+        Command::Relocate {
+            input,
+            output,
+            to,
+            adjust_start,
+        } => {
+            let mut ih = load(&input, base)?;
+            if adjust_start {
+                ih.relocate_with_start_address(to)?;
+            } else {
+                ih.relocate(to)?;
+            }
+            save(&mut ih, &output, 0xFF)?;
+        }
+        Command::Fill {
+            input,
+            output,
+            range,
+            pattern,
+        } => {
+            let mut ih = load(&input, base)?;
+            ih.fill(range, &pattern)?;
+            save(&mut ih, &output, 0xFF)?;
+        }
+        Command::Crc {
+            input,
+            range,
+            algorithm,
+            gap_fill,
+        } => {
+            let ih = load(&input, base)?;
+            let range = range.unwrap_or_else(|| data_range(&ih));
+            let checksum = algorithm.into();
+            let value = ih.checksum(range, checksum, gap_fill);
+            println!("0x{value:0width$X}", width = checksum.size() * 2);
+        }
+        Command::Dump { input, range } => {
+            let ih = load(&input, base)?;
+            let range = range.unwrap_or_else(|| data_range(&ih));
+            print_dump(&ih, range);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Address range from the lowest to the highest data address.
+fn data_range(ih: &IntelHex) -> Range<usize> {
+    match (ih.get_min_addr(), ih.get_max_addr()) {
+        (Some(min_addr), Some(max_addr)) => min_addr..max_addr + 1,
+        _ => 0..0,
+    }
+}
+
+/// Print the file summary and its data segments.
+fn print_info(ih: &IntelHex) {
+    let segments: Vec<Range<usize>> = ih.segments().collect();
+
+    println!("File size:     {} bytes", ih.size);
+    println!(
+        "Data size:     {} bytes in {} segment(s)",
+        ih.total_data_len(),
+        segments.len()
+    );
+    if let (Some(min_addr), Some(max_addr)) = (ih.get_min_addr(), ih.get_max_addr()) {
+        println!("Address range: 0x{min_addr:08X}..0x{:08X}", max_addr + 1);
+    }
+    match ih.start_address() {
+        Some(StartAddress::Linear(eip)) => println!("Start address: 0x{eip:08X} (linear)"),
+        Some(StartAddress::Segment { cs, ip }) => {
+            println!("Start address: {cs:04X}:{ip:04X} (segment)");
+        }
+        None => println!("Start address: none"),
+    }
+
+    println!("Segments:");
+    for segment in segments {
+        println!(
+            "  0x{:08X}..0x{:08X} ({} bytes)",
+            segment.start,
+            segment.end,
+            segment.len()
+        );
+    }
+}
+
+/// Format a row of the hexdump starting at `addr`; gaps are shown as `--`.
+fn format_dump_row(addr: usize, bytes: &[Option<u8>]) -> String {
+    let hex: Vec<String> = bytes
+        .iter()
+        .map(|byte| byte.map_or_else(|| "--".to_string(), |b| format!("{b:02X}")))
+        .collect();
+    let ascii: String = bytes
+        .iter()
+        .map(|byte| match byte {
+            Some(b) if b.is_ascii_graphic() || *b == b' ' => char::from(*b),
+            _ => '.',
+        })
+        .collect();
+
+    format!(
+        "{addr:08X}  {:<width$}  |{ascii}|",
+        hex.join(" "),
+        width = DUMP_ROW_LEN * 3 - 1
+    )
+}
+
+/// Print the hexdump of the range. Rows without any data are collapsed into a single `*`.
+fn print_dump(ih: &IntelHex, range: Range<usize>) {
+    for line in dump_lines(ih, range) {
+        println!("{line}");
+    }
+}
+
+/// Lines of the hexdump of the range. Only the rows of the data segments are read,
+/// the gaps between them are skipped directly.
+fn dump_lines(ih: &IntelHex, range: Range<usize>) -> Vec<String> {
+    let mut lines = Vec::new();
+    // Start of the next row, all rows below are already dumped (or skipped)
+    let mut next_row = range.start;
+
+    for segment in ih
+        .segments()
+        .take_while(|segment| segment.start < range.end)
+    {
+        let start = segment.start.max(next_row);
+        let end = segment.end.min(range.end);
+        if start >= end {
+            continue;
+        }
+
+        // Rows are aligned to the row length
+        let mut addr = (start / DUMP_ROW_LEN * DUMP_ROW_LEN).max(next_row);
+        if addr > next_row {
+            lines.push("*".to_string());
+        }
+        while addr < end {
+            let row_end = ((addr / DUMP_ROW_LEN + 1) * DUMP_ROW_LEN).min(range.end);
+            let bytes = ih.read_range_safe(addr, row_end - addr);
+            lines.push(format_dump_row(addr, &bytes));
+            addr = row_end;
+        }
+        next_row = addr;
+    }
+
+    if next_row < range.end {
+        lines.push("*".to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_dump_row() {
+        // Act
+        let row = format_dump_row(0x10, &[Some(0x41), None, Some(0x00)]);
+
+        // Assert
+        assert_eq!(row, format!("00000010  41 -- 00{}  |A..|", " ".repeat(39)));
+    }
+
+    #[test]
+    fn test_dump_lines_skips_gaps() {
+        // Arrange: data in the first row, a row shared by two segments and a large gap
+        let mut ih = IntelHex::new();
+        ih.load_bin_bytes(&[0x41], 0x1004);
+        let _ = ih.insert_bytes(0x1025, &[0x42]);
+        let _ = ih.insert_bytes(0x1028, &[0x43]);
+        let _ = ih.insert_bytes(0x0100_0000, &[0x44]);
+
+        // Act
+        let lines = dump_lines(&ih, 0x1002..0x2000);
+
+        // Assert
+        let mut shared = vec![None; DUMP_ROW_LEN];
+        shared[5] = Some(0x42);
+        shared[8] = Some(0x43);
+        let mut first = vec![None; 14];
+        first[2] = Some(0x41);
+        assert_eq!(
+            lines,
+            [
+                format_dump_row(0x1002, &first),
+                "*".to_string(),
+                format_dump_row(0x1020, &shared),
+                "*".to_string(),
+            ]
+        );
+    }
+}
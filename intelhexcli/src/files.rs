@@ -0,0 +1,206 @@
+use intelhexlib::{ElfAddress, IntelHex, IntelHexError, IntelHexErrorKind, TekHexFormat};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read};
+use std::path::Path;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    Hex,
+    Srec,
+    TiTxt,
+    TekHex,
+    Elf,
+    Bin,
+}
+
+/// Determine the output format from the file extension.
+pub fn format_from_extension(path: &Path) -> Option<FileFormat> {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)?
+        .as_str()
+    {
+        "hex" | "ihex" | "ihx" => Some(FileFormat::Hex),
+        "srec" | "s19" | "s28" | "s37" | "mot" => Some(FileFormat::Srec),
+        "txt" => Some(FileFormat::TiTxt),
+        "tek" | "tex" => Some(FileFormat::TekHex),
+        "elf" | "axf" => Some(FileFormat::Elf),
+        "bin" => Some(FileFormat::Bin),
+        _ => None,
+    }
+}
+
+/// Determine the input format from the first bytes of the file contents.
+fn detect_format(path: &Path) -> io::Result<FileFormat> {
+    let mut f = File::open(path)?;
+
+    // Read the first 32 bytes (OK if file has less)
+    let mut buf = [0u8; 32];
+    let n = f.read(&mut buf)?;
+
+    Ok(format_from_contents(&buf[..n], n < buf.len()))
+}
+
+/// Determine the format from the first bytes of the file (`is_complete` if these are
+/// the whole file). Text formats are recognized by the shape of their first line,
+/// everything else is considered raw binary.
+fn format_from_contents(head: &[u8], is_complete: bool) -> FileFormat {
+    // First line (only if it ends within the read bytes)
+    let first_line = head
+        .iter()
+        .position(|&b| b == b'\n')
+        .or_else(|| is_complete.then_some(head.len()))
+        .map(|end| head[..end].trim_ascii_end());
+    let is_hex_digits = |digits: &[u8]| digits.iter().all(u8::is_ascii_hexdigit);
+
+    match head {
+        [0x7F, b'E', b'L', b'F', ..] => FileFormat::Elf,
+        [b':', ..] => FileFormat::Hex,
+        [b'S', b'0'..=b'9', ..] => FileFormat::Srec,
+        // Standard Tektronix: '/' followed by 8+ hex digits (address, length, checksum)
+        [b'/', rest @ ..] if rest.len() >= 8 && is_hex_digits(&rest[..8]) => FileFormat::TekHex,
+        // Extended Tektronix: '%' followed by 2 hex digits of length and the type digit
+        [b'%', len_hi, len_lo, rtype, ..]
+            if is_hex_digits(&[*len_hi, *len_lo]) && rtype.is_ascii_digit() =>
+        {
+            FileFormat::TekHex
+        }
+        // TI-TXT: '@' followed by hex digits up to the end of the line
+        _ => match first_line {
+            Some([b'@', digits @ ..]) if !digits.is_empty() && is_hex_digits(digits) => {
+                FileFormat::TiTxt
+            }
+            _ => FileFormat::Bin,
+        },
+    }
+}
+
+/// Load the input file. Raw binary files are placed at the base address,
+/// ELF segments at their load (physical) addresses.
+pub fn load(path: &Path, base: usize) -> Result<IntelHex, Box<dyn Error>> {
+    let format = detect_format(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+
+    match format {
+        FileFormat::Hex => IntelHex::from_hex(path),
+        FileFormat::Srec => IntelHex::from_srec(path),
+        FileFormat::TiTxt => IntelHex::from_titxt(path),
+        FileFormat::TekHex => IntelHex::from_tekhex(path),
+        FileFormat::Elf => IntelHex::from_elf(path, ElfAddress::Physical),
+        FileFormat::Bin => IntelHex::from_bin(path, base),
+    }
+}
+
+/// Write the output file in the format given by its extension.
+/// Gaps of a binary file are filled with the `gap_fill` byte.
+pub fn save(ih: &mut IntelHex, path: &Path, gap_fill: u8) -> Result<(), Box<dyn Error>> {
+    match format_from_extension(path) {
+        Some(FileFormat::Hex) => ih.write_hex(path),
+        Some(FileFormat::Srec) => ih.write_srec(path),
+        Some(FileFormat::TiTxt) => ih.write_titxt(path),
+        Some(FileFormat::TekHex) => ih.write_tekhex(path, TekHexFormat::Extended),
+        Some(FileFormat::Bin) => ih.write_bin(path, gap_fill),
+        Some(FileFormat::Elf) => Err("ELF files can only be read".into()),
+        None => Err(format!(
+            "Unknown output format of '{}' (expected .hex, .srec / .s19 / .s28 / .s37, .txt, .tek or .bin)",
+            path.display()
+        )
+        .into()),
+    }
+}
+
+/// Convert a hex file into a binary file one record at a time, so that the memory use
+/// does not depend on the file size. Gaps are filled with the `gap_fill` byte.
+/// Files with records out of address order are converted in memory instead.
+pub fn stream_to_bin(input: &Path, output: &Path, gap_fill: u8) -> Result<(), Box<dyn Error>> {
+    let with_path = |path: &Path, err: io::Error| {
+        io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+    };
+
+    if detect_format(input).map_err(|err| with_path(input, err))? != FileFormat::Hex {
+        return Err(format!(
+            "Streaming conversion expects a hex input file, found '{}'",
+            input.display()
+        )
+        .into());
+    }
+    if format_from_extension(output) != Some(FileFormat::Bin) {
+        return Err(format!(
+            "Streaming conversion writes .bin files only, found '{}'",
+            output.display()
+        )
+        .into());
+    }
+
+    let reader = BufReader::new(File::open(input).map_err(|err| with_path(input, err))?);
+    let writer = BufWriter::new(File::create(output).map_err(|err| with_path(output, err))?);
+    match IntelHex::hex_to_bin(reader, writer, gap_fill) {
+        Err(err)
+            if matches!(
+                err.downcast_ref::<IntelHexError>(),
+                Some(IntelHexError::ParseRecordError(
+                    IntelHexErrorKind::RecordAddressNotAscending(_),
+                    _
+                ))
+            ) =>
+        {
+            // The partly written output is replaced
+            let mut ih = IntelHex::from_hex(input)?;
+            save(&mut ih, output, gap_fill)
+        }
+        res => res.map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            format_from_extension(Path::new("fw.HEX")),
+            Some(FileFormat::Hex)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("fw.s19")),
+            Some(FileFormat::Srec)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("fw.tek")),
+            Some(FileFormat::TekHex)
+        );
+        assert_eq!(format_from_extension(Path::new("fw")), None);
+    }
+
+    #[test]
+    fn test_format_from_contents() {
+        assert_eq!(
+            format_from_contents(b"@F000\r\n31 40", false),
+            FileFormat::TiTxt
+        );
+        assert_eq!(format_from_contents(b"@F000", true), FileFormat::TiTxt);
+        assert_eq!(format_from_contents(b"/00000000", true), FileFormat::TekHex);
+        assert_eq!(
+            format_from_contents(b"%0E81F800000100", true),
+            FileFormat::TekHex
+        );
+
+        // Raw binary and other text files starting with the same characters
+        assert_eq!(
+            format_from_contents(b"@\x00\x10\xFF", true),
+            FileFormat::Bin
+        );
+        assert_eq!(format_from_contents(b"@F000", false), FileFormat::Bin);
+        assert_eq!(
+            format_from_contents(b"// Generated by", false),
+            FileFormat::Bin
+        );
+        assert_eq!(
+            format_from_contents(b"%\x01\x02\x03", true),
+            FileFormat::Bin
+        );
+    }
+}
@@ -0,0 +1,315 @@
+//! # `intelhex`
+//!
+//! Command-line tool built on `intelhexlib` for inspecting and converting
+//! Intel HEX, Motorola S-record, ELF and raw binary files, e.g. on build servers.
+//!
+//! The exit code tells the error category apart (see [`exit_code`]), so that
+//! scripts can react to e.g. a corrupted input file differently than to a missing one.
+
+mod commands;
+mod files;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use intelhexlib::{Checksum, Crc, IntelHexError, OverlapPolicy};
+use std::error::Error;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Exit code: `diff` found differences
+const EXIT_DIFFERENT: u8 = 1;
+/// Exit code: invalid arguments or unsupported file format (same as clap usage errors)
+const EXIT_USAGE: u8 = 2;
+/// Exit code: file cannot be read or written
+const EXIT_IO: u8 = 3;
+/// Exit code: `IntelHexError::ParseRecordError`
+const EXIT_PARSE_RECORD: u8 = 10;
+/// Exit code: `IntelHexError::CreateRecordError`
+const EXIT_CREATE_RECORD: u8 = 11;
+/// Exit code: `IntelHexError::UpdateError`
+const EXIT_UPDATE: u8 = 12;
+/// Exit code: `IntelHexError::ParseElfError`
+const EXIT_PARSE_ELF: u8 = 13;
+/// Exit code: `IntelHexError::ReadError`
+const EXIT_READ: u8 = 14;
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    /// Base address of raw binary input files
+    #[arg(long, global = true, default_value = "0", value_parser = parse_number)]
+    base: usize,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the segments, size and start address of a file
+    Info {
+        /// Input file (hex, srec, ti-txt, tekhex, elf or bin)
+        input: PathBuf,
+    },
+    /// Convert a file into another format (chosen by the output file extension)
+    Convert {
+        /// Input file (hex, srec, ti-txt, tekhex, elf or bin)
+        input: PathBuf,
+        /// Output file (.hex, .srec / .s19 / .s28 / .s37, .txt, .tek or .bin)
+        output: PathBuf,
+        /// Byte written into the gaps of a binary output file
+        #[arg(long, default_value = "0xFF", value_parser = parse_byte)]
+        gap_fill: u8,
+        /// Convert hex to bin one record at a time without loading the whole file
+        /// (files with records out of address order are loaded as a whole)
+        #[arg(long)]
+        stream: bool,
+    },
+    /// Merge several files into one
+    Merge {
+        /// Input files, merged in the given order
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+        /// Output file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Handling of overlapping data
+        #[arg(long, value_enum, default_value_t = Policy::Error)]
+        policy: Policy,
+    },
+    /// Print the differences between two files (exit code 1 if they differ)
+    Diff {
+        /// First file
+        first: PathBuf,
+        /// Second file
+        second: PathBuf,
+    },
+    /// Move the data to a new start address
+    Relocate {
+        /// Input file
+        input: PathBuf,
+        /// Output file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// New lowest data address
+        #[arg(long, value_parser = parse_number)]
+        to: usize,
+        /// Shift the start address (entry point) by the same offset
+        #[arg(long)]
+        adjust_start: bool,
+    },
+    /// Fill the gaps in an address range with a repeated pattern
+    Fill {
+        /// Input file
+        input: PathBuf,
+        /// Output file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Address range, e.g. 0x0000..0x8000
+        #[arg(long, value_parser = parse_range)]
+        range: Range<usize>,
+        /// Fill pattern in hex digits, e.g. FF or DEADBEEF
+        #[arg(long, default_value = "FF", value_parser = parse_hex_bytes)]
+        pattern: Vec<u8>,
+    },
+    /// Compute a CRC or checksum over an address range
+    Crc {
+        /// Input file
+        input: PathBuf,
+        /// Address range (default: from the lowest to the highest data address)
+        #[arg(long, value_parser = parse_range)]
+        range: Option<Range<usize>>,
+        /// Checksum algorithm
+        #[arg(long, value_enum, default_value_t = Algorithm::Crc32)]
+        algorithm: Algorithm,
+        /// Byte used in place of the gaps
+        #[arg(long, default_value = "0xFF", value_parser = parse_byte)]
+        gap_fill: u8,
+    },
+    /// Print a hexdump of an address range
+    Dump {
+        /// Input file
+        input: PathBuf,
+        /// Address range (default: from the lowest to the highest data address)
+        #[arg(long, value_parser = parse_range)]
+        range: Option<Range<usize>>,
+    },
+}
+
+/// Overlap handling of `merge`, see [`OverlapPolicy`].
+#[derive(Copy, Clone, ValueEnum)]
+enum Policy {
+    Error,
+    KeepExisting,
+    Overwrite,
+    ErrorIfDifferent,
+}
+
+impl From<Policy> for OverlapPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Error => Self::Error,
+            Policy::KeepExisting => Self::KeepExisting,
+            Policy::Overwrite => Self::Overwrite,
+            Policy::ErrorIfDifferent => Self::ErrorIfDifferent,
+        }
+    }
+}
+
+/// Checksum algorithm of `crc`, see [`Checksum`].
+#[derive(Copy, Clone, ValueEnum)]
+enum Algorithm {
+    Crc8,
+    Crc16Ccitt,
+    Crc16Modbus,
+    Crc32,
+    Crc32c,
+    Sum8,
+    Sum16,
+    Sum32,
+}
+
+impl From<Algorithm> for Checksum {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Crc8 => Self::Crc(Crc::CRC_8),
+            Algorithm::Crc16Ccitt => Self::Crc(Crc::CRC_16_CCITT),
+            Algorithm::Crc16Modbus => Self::Crc(Crc::CRC_16_MODBUS),
+            Algorithm::Crc32 => Self::Crc(Crc::CRC_32),
+            Algorithm::Crc32c => Self::Crc(Crc::CRC_32C),
+            Algorithm::Sum8 => Self::Sum8,
+            Algorithm::Sum16 => Self::Sum16,
+            Algorithm::Sum32 => Self::Sum32,
+        }
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal number.
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .map_or_else(
+            || s.replace('_', "").parse(),
+            |hex| usize::from_str_radix(&hex.replace('_', ""), 16),
+        )
+        .map_err(|err| format!("invalid number '{s}': {err}"))
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal byte value.
+fn parse_byte(s: &str) -> Result<u8, String> {
+    let value = parse_number(s)?;
+    u8::try_from(value).map_err(|_| format!("value '{s}' does not fit into a byte"))
+}
+
+/// Parse an address range `START..END` (end exclusive) or `START+LEN`.
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let range = if let Some((start, end)) = s.split_once("..") {
+        parse_number(start)?..parse_number(end)?
+    } else if let Some((start, len)) = s.split_once('+') {
+        let start = parse_number(start)?;
+        start..start.saturating_add(parse_number(len)?)
+    } else {
+        return Err(format!(
+            "invalid range '{s}': expected START..END or START+LEN"
+        ));
+    };
+
+    if range.is_empty() {
+        return Err(format!("invalid range '{s}': range is empty"));
+    }
+    Ok(range)
+}
+
+/// Parse a byte sequence from hex digits, e.g. `DEADBEEF` or `DE AD BE EF`.
+fn parse_hex_bytes(s: &str) -> Result<Vec<u8>, String> {
+    let digits: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    // Only ASCII hex digits, so that the pairs can be sliced at byte offsets
+    if digits.is_empty()
+        || !digits.len().is_multiple_of(2)
+        || !digits.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(format!(
+            "invalid pattern '{s}': expected pairs of hex digits"
+        ));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid pattern '{s}': expected pairs of hex digits"))
+        })
+        .collect()
+}
+
+/// Map the error to the exit code of its category.
+fn exit_code(err: &(dyn Error + 'static)) -> u8 {
+    if let Some(err) = err.downcast_ref::<IntelHexError>() {
+        return match err {
+            IntelHexError::ParseRecordError(..) => EXIT_PARSE_RECORD,
+            IntelHexError::CreateRecordError(_) => EXIT_CREATE_RECORD,
+            IntelHexError::UpdateError(_) => EXIT_UPDATE,
+            IntelHexError::ParseElfError(_) => EXIT_PARSE_ELF,
+            IntelHexError::ReadError(_) => EXIT_READ,
+        };
+    }
+    if err.downcast_ref::<std::io::Error>().is_some() {
+        return EXIT_IO;
+    }
+    EXIT_USAGE
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match commands::run(cli.command, cli.base) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(exit_code(err.as_ref()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intelhexlib::IntelHexErrorKind;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("0x1_0000"), Ok(0x1_0000));
+        assert_eq!(parse_number("4096"), Ok(4096));
+        assert!(parse_number("0xZZ").is_err());
+        assert!(parse_byte("0x100").is_err());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("0x100..0x200"), Ok(0x100..0x200));
+        assert_eq!(parse_range("0x100+16"), Ok(0x100..0x110));
+        assert!(parse_range("0x200..0x100").is_err());
+        assert!(parse_range("0x100").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_bytes() {
+        assert_eq!(parse_hex_bytes("DE AD"), Ok(vec![0xDE, 0xAD]));
+        assert!(parse_hex_bytes("DEA").is_err());
+        assert!(parse_hex_bytes("").is_err());
+        assert!(parse_hex_bytes("a\u{e9}1").is_err());
+        assert!(parse_hex_bytes("+F").is_err());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        // Arrange
+        let parse_err = IntelHexError::ParseRecordError(IntelHexErrorKind::MissingStartCode, 1);
+        let io_err = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let other_err: Box<dyn Error> = "unknown format".into();
+
+        // Act + Assert
+        assert_eq!(exit_code(&parse_err), EXIT_PARSE_RECORD);
+        assert_eq!(exit_code(&io_err), EXIT_IO);
+        assert_eq!(exit_code(other_err.as_ref()), EXIT_USAGE);
+    }
+}
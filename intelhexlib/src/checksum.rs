@@ -0,0 +1,333 @@
+//! The `checksum` module defines the [`Checksum`] algorithms (CRCs and additive sums)
+//! which can be computed over address ranges of an `IntelHex` image via
+//! [`IntelHex::checksum()`](crate::IntelHex::checksum).
+
+/// Parameterized CRC definition following the Rocksoft model
+/// (as used by the "Catalogue of parametrised CRC algorithms").
+///
+/// Predefined constants cover the common variants, vendor variants can be defined
+/// by filling in the fields directly.
+///
+/// # Example
+/// ```
+/// use intelhexlib::Crc;
+///
+/// // CRC-16/XMODEM
+/// let crc = Crc { width: 16, poly: 0x1021, init: 0x0000, refin: false, refout: false, xorout: 0x0000 };
+///
+/// assert_eq!(crc.compute([b"123456789".as_slice()]), 0x31C3);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Crc {
+    /// Width of the CRC in bits (1..=64, other values are clamped)
+    pub width: u8,
+    /// Generator polynomial (without the leading bit)
+    pub poly: u64,
+    /// Initial value of the register
+    pub init: u64,
+    /// Reflect (bit-reverse) each input byte
+    pub refin: bool,
+    /// Reflect the final register value
+    pub refout: bool,
+    /// Value the final register value is XOR-ed with
+    pub xorout: u64,
+}
+
+impl Crc {
+    /// CRC-8 (aka CRC-8/SMBUS)
+    pub const CRC_8: Self = Self {
+        width: 8,
+        poly: 0x07,
+        init: 0x00,
+        refin: false,
+        refout: false,
+        xorout: 0x00,
+    };
+
+    /// CRC-16/CCITT-FALSE (aka CRC-16/IBM-3740)
+    pub const CRC_16_CCITT: Self = Self {
+        width: 16,
+        poly: 0x1021,
+        init: 0xFFFF,
+        refin: false,
+        refout: false,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/MODBUS
+    pub const CRC_16_MODBUS: Self = Self {
+        width: 16,
+        poly: 0x8005,
+        init: 0xFFFF,
+        refin: true,
+        refout: true,
+        xorout: 0x0000,
+    };
+
+    /// CRC-32 (aka CRC-32/ISO-HDLC, as used by zlib and Ethernet)
+    pub const CRC_32: Self = Self {
+        width: 32,
+        poly: 0x04C1_1DB7,
+        init: 0xFFFF_FFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF_FFFF,
+    };
+
+    /// CRC-32C (aka CRC-32/ISCSI, Castagnoli)
+    pub const CRC_32C: Self = Self {
+        width: 32,
+        poly: 0x1EDC_6F41,
+        init: 0xFFFF_FFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF_FFFF,
+    };
+
+    /// Width of the CRC in bits clamped to 1..=64.
+    ///
+    const fn clamped_width(&self) -> u32 {
+        match self.width {
+            0 => 1,
+            w @ 1..=64 => w as u32,
+            _ => 64,
+        }
+    }
+
+    /// Mask covering `width` lower bits.
+    ///
+    const fn mask(width: u32) -> u64 {
+        if width == 64 {
+            u64::MAX
+        } else {
+            (1 << width) - 1
+        }
+    }
+
+    /// Reverse the order of `width` lower bits.
+    ///
+    const fn reflect(value: u64, width: u32) -> u64 {
+        value.reverse_bits() >> (64 - width)
+    }
+
+    /// Computes the CRC over the concatenation of the slices.
+    ///
+    #[must_use]
+    pub fn compute<'a>(&self, slices: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+        let width = self.clamped_width();
+        let mask = Self::mask(width);
+
+        let mut table = [0u64; 256];
+
+        let crc = if self.refin {
+            // Register holds the reflected CRC in its lower bits
+            let poly = Self::reflect(self.poly & mask, width);
+            for (i, entry) in (0u64..).zip(table.iter_mut()) {
+                let mut c = i;
+                for _ in 0..8 {
+                    c = if c & 1 == 1 { (c >> 1) ^ poly } else { c >> 1 };
+                }
+                *entry = c;
+            }
+
+            let mut crc = Self::reflect(self.init & mask, width);
+            for slice in slices {
+                for &b in slice {
+                    crc = table[((crc ^ u64::from(b)) & 0xFF) as usize] ^ (crc >> 8);
+                }
+            }
+
+            // Register is reflected already
+            if self.refout {
+                crc
+            } else {
+                Self::reflect(crc, width)
+            }
+        } else {
+            // Register holds the CRC in its upper bits
+            let shift = 64 - width;
+            let poly = (self.poly & mask) << shift;
+            for (i, entry) in (0u64..).zip(table.iter_mut()) {
+                let mut c = i << 56;
+                for _ in 0..8 {
+                    c = if c >> 63 == 1 {
+                        (c << 1) ^ poly
+                    } else {
+                        c << 1
+                    };
+                }
+                *entry = c;
+            }
+
+            let mut crc = (self.init & mask) << shift;
+            for slice in slices {
+                for &b in slice {
+                    crc = table[(((crc >> 56) ^ u64::from(b)) & 0xFF) as usize] ^ (crc << 8);
+                }
+            }
+
+            let crc = crc >> shift;
+            if self.refout {
+                Self::reflect(crc, width)
+            } else {
+                crc
+            }
+        };
+
+        (crc ^ self.xorout) & mask
+    }
+}
+
+/// Checksum algorithm computed over an address range of an `IntelHex` image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Checksum {
+    /// Cyclic redundancy check with the given parameters
+    Crc(Crc),
+    /// Sum of all bytes truncated to 8 bits
+    Sum8,
+    /// Sum of all bytes truncated to 16 bits
+    Sum16,
+    /// Sum of all bytes truncated to 32 bits
+    Sum32,
+}
+
+impl Checksum {
+    /// Size of the checksum in bytes (e.g. when embedded into the image).
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Checksum, Crc};
+    ///
+    /// assert_eq!(Checksum::Crc(Crc::CRC_16_MODBUS).size(), 2);
+    /// assert_eq!(Checksum::Sum32.size(), 4);
+    /// ```
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        match self {
+            Self::Crc(crc) => crc.clamped_width().div_ceil(8) as usize,
+            Self::Sum8 => 1,
+            Self::Sum16 => 2,
+            Self::Sum32 => 4,
+        }
+    }
+
+    /// Computes the checksum over the concatenation of the slices.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Checksum, Crc};
+    ///
+    /// let data: [&[u8]; 2] = [b"1234", b"56789"];
+    ///
+    /// assert_eq!(Checksum::Crc(Crc::CRC_32).compute(data), 0xCBF4_3926);
+    /// assert_eq!(Checksum::Sum8.compute(data), 0xDD);
+    /// ```
+    #[must_use]
+    pub fn compute<'a>(&self, slices: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+        let Self::Crc(crc) = self else {
+            let sum = slices
+                .into_iter()
+                .flatten()
+                .fold(0u64, |acc, &b| acc.wrapping_add(u64::from(b)));
+            return sum & Self::mask(self.size());
+        };
+        crc.compute(slices)
+    }
+
+    /// Mask covering `size` lower bytes.
+    ///
+    const fn mask(size: usize) -> u64 {
+        if size >= 8 {
+            u64::MAX
+        } else {
+            (1 << (size * 8)) - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn test_predefined_crc_check_values() {
+        assert_eq!(Crc::CRC_8.compute([CHECK]), 0xF4);
+        assert_eq!(Crc::CRC_16_CCITT.compute([CHECK]), 0x29B1);
+        assert_eq!(Crc::CRC_16_MODBUS.compute([CHECK]), 0x4B37);
+        assert_eq!(Crc::CRC_32.compute([CHECK]), 0xCBF4_3926);
+        assert_eq!(Crc::CRC_32C.compute([CHECK]), 0xE306_9283);
+    }
+
+    #[test]
+    fn test_parameterized_crc_check_values() {
+        // CRC-3/GSM (non-reflected, shorter than a byte)
+        let crc = Crc {
+            width: 3,
+            poly: 0x3,
+            init: 0x0,
+            refin: false,
+            refout: false,
+            xorout: 0x7,
+        };
+        assert_eq!(crc.compute([CHECK]), 0x4);
+
+        // CRC-5/USB (reflected, shorter than a byte)
+        let crc = Crc {
+            width: 5,
+            poly: 0x05,
+            init: 0x1F,
+            refin: true,
+            refout: true,
+            xorout: 0x1F,
+        };
+        assert_eq!(crc.compute([CHECK]), 0x19);
+
+        // CRC-64/XZ (full register width)
+        let crc = Crc {
+            width: 64,
+            poly: 0x42F0_E1EB_A9EA_3693,
+            init: u64::MAX,
+            refin: true,
+            refout: true,
+            xorout: u64::MAX,
+        };
+        assert_eq!(crc.compute([CHECK]), 0x995D_C9BB_DF19_39FA);
+
+        // CRC-12/UMTS (input not reflected, output reflected)
+        let crc = Crc {
+            width: 12,
+            poly: 0x80F,
+            init: 0x000,
+            refin: false,
+            refout: true,
+            xorout: 0x000,
+        };
+        assert_eq!(crc.compute([CHECK]), 0xDAF);
+    }
+
+    #[test]
+    fn test_crc_over_split_slices() {
+        // Arrange
+        let (a, b) = CHECK.split_at(4);
+
+        // Act
+        let whole = Crc::CRC_32C.compute([CHECK]);
+        let split = Crc::CRC_32C.compute([a, &[], b]);
+
+        // Assert
+        assert_eq!(whole, split);
+    }
+
+    #[test]
+    fn test_sums() {
+        // Arrange
+        let data = vec![0xFFu8; 0x1_0001];
+
+        // Act + Assert
+        assert_eq!(Checksum::Sum8.compute([data.as_slice()]), 0xFF);
+        assert_eq!(Checksum::Sum16.compute([data.as_slice()]), 0x00FF);
+        assert_eq!(Checksum::Sum32.compute([data.as_slice()]), 0x00FF_00FF);
+    }
+}
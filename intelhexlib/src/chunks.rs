@@ -0,0 +1,232 @@
+//! The `chunks` module defines the borrowing iterators over the data of an address window
+//! returned by [`IntelHex::chunks_in()`](crate::IntelHex::chunks_in) and
+//! [`IntelHex::chunks_in_mut()`](crate::IntelHex::chunks_in_mut).
+//! They yield slices of the internal buffer (no copies) and mark the gaps between them.
+
+use std::collections::btree_map;
+use std::ops::Range;
+
+/// Piece of an address window yielded by [`ChunksIn`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Chunk<'a> {
+    /// Contiguous data starting at the address
+    Data(usize, &'a [u8]),
+    /// Address range without data
+    Gap(Range<usize>),
+}
+
+/// Piece of an address window yielded by [`ChunksInMut`].
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum ChunkMut<'a> {
+    /// Contiguous data starting at the address
+    Data(usize, &'a mut [u8]),
+    /// Address range without data
+    Gap(Range<usize>),
+}
+
+/// Locate the part of the chunk which lies inside the window `addr..end`.
+/// Returns the gap preceding the part (if any) and the range of the part within the chunk,
+/// or `None` if the chunk ends before the window.
+///
+fn locate(
+    addr: usize,
+    end: usize,
+    chunk_start: usize,
+    chunk_len: usize,
+) -> Option<(Option<Range<usize>>, Range<usize>)> {
+    let chunk_end = chunk_start + chunk_len;
+    if chunk_end <= addr {
+        return None;
+    }
+    let start = chunk_start.max(addr);
+    let gap = (start > addr).then_some(addr..start);
+    Some((gap, start - chunk_start..chunk_end.min(end) - chunk_start))
+}
+
+/// Iterator over the data slices and gaps of an address window, in ascending order.
+#[derive(Debug)]
+pub struct ChunksIn<'a> {
+    /// Chunks of the buffer which may overlap with the window
+    chunks: btree_map::Range<'a, usize, Vec<u8>>,
+    /// Next address to be yielded
+    addr: usize,
+    /// End of the window (exclusive)
+    end: usize,
+    /// Data following an already yielded gap
+    pending: Option<(usize, &'a [u8])>,
+}
+
+impl<'a> ChunksIn<'a> {
+    pub(crate) const fn new(
+        chunks: btree_map::Range<'a, usize, Vec<u8>>,
+        window: Range<usize>,
+    ) -> Self {
+        Self {
+            chunks,
+            addr: window.start,
+            end: window.end,
+            pending: None,
+        }
+    }
+}
+
+impl<'a> Iterator for ChunksIn<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((addr, data)) = self.pending.take() {
+            self.addr = addr + data.len();
+            return Some(Chunk::Data(addr, data));
+        }
+        if self.addr >= self.end {
+            return None;
+        }
+
+        for (&chunk_start, data) in self.chunks.by_ref() {
+            let Some((gap, part)) = locate(self.addr, self.end, chunk_start, data.len()) else {
+                continue;
+            };
+            let addr = chunk_start + part.start;
+            let data = &data[part];
+
+            if let Some(gap) = gap {
+                self.pending = Some((addr, data));
+                self.addr = addr;
+                return Some(Chunk::Gap(gap));
+            }
+            self.addr = addr + data.len();
+            return Some(Chunk::Data(addr, data));
+        }
+
+        // No more data in the window
+        let gap = self.addr..self.end;
+        self.addr = self.end;
+        Some(Chunk::Gap(gap))
+    }
+}
+
+/// Iterator over the mutable data slices and gaps of an address window, in ascending order.
+#[derive(Debug)]
+pub struct ChunksInMut<'a> {
+    /// Chunks of the buffer which may overlap with the window
+    chunks: btree_map::RangeMut<'a, usize, Vec<u8>>,
+    /// Next address to be yielded
+    addr: usize,
+    /// End of the window (exclusive)
+    end: usize,
+    /// Data following an already yielded gap
+    pending: Option<(usize, &'a mut [u8])>,
+}
+
+impl<'a> ChunksInMut<'a> {
+    pub(crate) const fn new(
+        chunks: btree_map::RangeMut<'a, usize, Vec<u8>>,
+        window: Range<usize>,
+    ) -> Self {
+        Self {
+            chunks,
+            addr: window.start,
+            end: window.end,
+            pending: None,
+        }
+    }
+}
+
+impl<'a> Iterator for ChunksInMut<'a> {
+    type Item = ChunkMut<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((addr, data)) = self.pending.take() {
+            self.addr = addr + data.len();
+            return Some(ChunkMut::Data(addr, data));
+        }
+        if self.addr >= self.end {
+            return None;
+        }
+
+        for (&chunk_start, data) in self.chunks.by_ref() {
+            let Some((gap, part)) = locate(self.addr, self.end, chunk_start, data.len()) else {
+                continue;
+            };
+            let addr = chunk_start + part.start;
+            let data = &mut data[part];
+
+            if let Some(gap) = gap {
+                self.pending = Some((addr, data));
+                self.addr = addr;
+                return Some(ChunkMut::Gap(gap));
+            }
+            self.addr = addr + data.len();
+            return Some(ChunkMut::Data(addr, data));
+        }
+
+        // No more data in the window
+        let gap = self.addr..self.end;
+        self.addr = self.end;
+        Some(ChunkMut::Gap(gap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_locate() {
+        // Chunk ends before the window
+        assert_eq!(locate(0x10, 0x20, 0x08, 0x08), None);
+        // Chunk starts before the window
+        assert_eq!(locate(0x10, 0x20, 0x08, 0x10), Some((None, 0x08..0x10)));
+        // Chunk starts inside the window and ends after it
+        assert_eq!(
+            locate(0x10, 0x20, 0x18, 0x10),
+            Some((Some(0x10..0x18), 0x00..0x08))
+        );
+    }
+
+    #[test]
+    fn test_chunks_in_window() {
+        // Arrange
+        let buffer = BTreeMap::from([
+            (0x08, vec![0x00, 0x01, 0x02, 0x03]),
+            (0x10, vec![0x04]),
+            (0x14, vec![0x05, 0x06, 0x07, 0x08]),
+        ]);
+
+        // Act
+        let chunks: Vec<Chunk> = ChunksIn::new(buffer.range(0x08..0x16), 0x0A..0x16).collect();
+
+        // Assert
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk::Data(0x0A, &[0x02, 0x03]),
+                Chunk::Gap(0x0C..0x10),
+                Chunk::Data(0x10, &[0x04]),
+                Chunk::Gap(0x11..0x14),
+                Chunk::Data(0x14, &[0x05, 0x06]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunks_in_mut_window() {
+        // Arrange
+        let mut buffer = BTreeMap::from([(0x10, vec![0x00, 0x01])]);
+
+        // Act
+        let chunks: Vec<ChunkMut> =
+            ChunksInMut::new(buffer.range_mut(0x10..0x14), 0x0E..0x14).collect();
+
+        // Assert
+        assert_eq!(
+            chunks,
+            vec![
+                ChunkMut::Gap(0x0E..0x10),
+                ChunkMut::Data(0x10, &mut [0x00, 0x01]),
+                ChunkMut::Gap(0x12..0x14),
+            ]
+        );
+    }
+}
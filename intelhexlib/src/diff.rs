@@ -0,0 +1,219 @@
+//! The `diff` module defines the [`DiffRange`] enum which describes a difference between
+//! two `IntelHex` images, and the chunk-wise comparison of their data buffers.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+/// Difference between two `IntelHex` images found by [`IntelHex::diff()`](crate::IntelHex::diff).
+/// "Self" is the image `diff` is called on, "other" is the image it is compared with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiffRange {
+    /// Both images hold data in the address range, but the content differs
+    Changed(Range<usize>),
+    /// Only self holds data in the address range
+    OnlyInSelf(Range<usize>),
+    /// Only other holds data in the address range
+    OnlyInOther(Range<usize>),
+    /// Start address of the images differs
+    StartAddress,
+}
+
+impl fmt::Display for DiffRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Changed(range) => {
+                write!(
+                    f,
+                    "0x{:08X}..0x{:08X} ({} bytes): content differs",
+                    range.start,
+                    range.end,
+                    range.len()
+                )
+            }
+            Self::OnlyInSelf(range) => {
+                write!(
+                    f,
+                    "0x{:08X}..0x{:08X} ({} bytes): only in self",
+                    range.start,
+                    range.end,
+                    range.len()
+                )
+            }
+            Self::OnlyInOther(range) => {
+                write!(
+                    f,
+                    "0x{:08X}..0x{:08X} ({} bytes): only in other",
+                    range.start,
+                    range.end,
+                    range.len()
+                )
+            }
+            Self::StartAddress => {
+                write!(f, "Start address differs")
+            }
+        }
+    }
+}
+
+/// Push the range into the diff, extending the last range if it is of the same kind
+/// and the ranges are adjacent.
+///
+fn push_range(diff: &mut Vec<DiffRange>, range: DiffRange) {
+    match (diff.last_mut(), &range) {
+        (Some(DiffRange::Changed(last)), DiffRange::Changed(new))
+        | (Some(DiffRange::OnlyInSelf(last)), DiffRange::OnlyInSelf(new))
+        | (Some(DiffRange::OnlyInOther(last)), DiffRange::OnlyInOther(new))
+            if last.end == new.start =>
+        {
+            last.end = new.end;
+        }
+        _ => diff.push(range),
+    }
+}
+
+/// Compare the data present in both images at `start` and push the runs of differing bytes.
+///
+fn push_changed(diff: &mut Vec<DiffRange>, start: usize, left: &[u8], right: &[u8]) {
+    // Fast path for identical data
+    if left == right {
+        return;
+    }
+
+    let mut run_start: Option<usize> = None;
+    for (i, (l, r)) in left.iter().zip(right).enumerate() {
+        match (l == r, run_start) {
+            (false, None) => run_start = Some(i),
+            (true, Some(s)) => {
+                push_range(diff, DiffRange::Changed(start + s..start + i));
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = run_start {
+        push_range(diff, DiffRange::Changed(start + s..start + left.len()));
+    }
+}
+
+/// Remaining (not yet compared) part of a chunk: (start address, data)
+type Piece<'a> = Option<(usize, &'a [u8])>;
+
+/// Skip `len` bytes of the piece, moving to the next chunk once the piece is exhausted.
+///
+fn advance<'a>(
+    iter: &mut impl Iterator<Item = (&'a usize, &'a Vec<u8>)>,
+    start: usize,
+    data: &'a [u8],
+    len: usize,
+) -> Piece<'a> {
+    if len == data.len() {
+        iter.next().map(|(&a, d)| (a, d.as_slice()))
+    } else {
+        Some((start + len, &data[len..]))
+    }
+}
+
+/// Walk the chunks of both buffers in address order and collect the differences.
+///
+pub fn diff_buffers(
+    left: &BTreeMap<usize, Vec<u8>>,
+    right: &BTreeMap<usize, Vec<u8>>,
+) -> Vec<DiffRange> {
+    let mut diff = Vec::new();
+
+    let mut left_iter = left.iter();
+    let mut right_iter = right.iter();
+
+    let mut l: Piece = left_iter.next().map(|(&a, d)| (a, d.as_slice()));
+    let mut r: Piece = right_iter.next().map(|(&a, d)| (a, d.as_slice()));
+
+    loop {
+        match (l, r) {
+            (None, None) => break,
+            (Some((ls, ld)), None) => {
+                push_range(&mut diff, DiffRange::OnlyInSelf(ls..ls + ld.len()));
+                l = advance(&mut left_iter, ls, ld, ld.len());
+            }
+            (None, Some((rs, rd))) => {
+                push_range(&mut diff, DiffRange::OnlyInOther(rs..rs + rd.len()));
+                r = advance(&mut right_iter, rs, rd, rd.len());
+            }
+            (Some((ls, ld)), Some((rs, rd))) => match ls.cmp(&rs) {
+                Ordering::Less => {
+                    // Left data up to the start of the right chunk
+                    let len = ld.len().min(rs - ls);
+                    push_range(&mut diff, DiffRange::OnlyInSelf(ls..ls + len));
+                    l = advance(&mut left_iter, ls, ld, len);
+                }
+                Ordering::Greater => {
+                    // Right data up to the start of the left chunk
+                    let len = rd.len().min(ls - rs);
+                    push_range(&mut diff, DiffRange::OnlyInOther(rs..rs + len));
+                    r = advance(&mut right_iter, rs, rd, len);
+                }
+                Ordering::Equal => {
+                    // Both hold data -> compare the common part
+                    let len = ld.len().min(rd.len());
+                    push_changed(&mut diff, ls, &ld[..len], &rd[..len]);
+                    l = advance(&mut left_iter, ls, ld, len);
+                    r = advance(&mut right_iter, rs, rd, len);
+                }
+            },
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_buffers_identical() {
+        // Arrange
+        let left = BTreeMap::from([(0x10, vec![0x00, 0x01]), (0x20, vec![0x02])]);
+
+        // Act
+        let diff = diff_buffers(&left, &left.clone());
+
+        // Assert
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_buffers_overlapping_chunks() {
+        // Arrange
+        let left = BTreeMap::from([(0x10, vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05])]);
+        let right = BTreeMap::from([
+            (0x08, vec![0xFF, 0xFF]),
+            (0x12, vec![0xAA, 0xBB, 0x04, 0xCC, 0x06]),
+        ]);
+
+        // Act
+        let diff = diff_buffers(&left, &right);
+
+        // Assert
+        assert_eq!(
+            diff,
+            vec![
+                DiffRange::OnlyInOther(0x08..0x0A),
+                DiffRange::OnlyInSelf(0x10..0x12),
+                DiffRange::Changed(0x12..0x14),
+                DiffRange::Changed(0x15..0x16),
+                DiffRange::OnlyInOther(0x16..0x17),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_diff_range() {
+        assert_eq!(
+            DiffRange::Changed(0x100..0x120).to_string(),
+            "0x00000100..0x00000120 (32 bytes): content differs"
+        );
+        assert_eq!(DiffRange::StartAddress.to_string(), "Start address differs");
+    }
+}
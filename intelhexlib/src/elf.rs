@@ -0,0 +1,312 @@
+//! The `elf` module defines the `Elf` struct which is used for extracting loadable
+//! segments (`PT_LOAD` program headers) from 32- and 64-bit ELF files of either endianness.
+
+use crate::error::IntelHexErrorKind;
+
+mod consts {
+    pub const MAGIC: &[u8; 4] = b"\x7FELF";
+    pub const CLASS_32: u8 = 1;
+    pub const CLASS_64: u8 = 2;
+    pub const DATA_LE: u8 = 1;
+    pub const DATA_BE: u8 = 2;
+    pub const PT_LOAD: u32 = 1;
+    pub const EHDR_32_SIZE: usize = 52;
+    pub const EHDR_64_SIZE: usize = 64;
+    pub const PHDR_32_SIZE: usize = 32;
+    pub const PHDR_64_SIZE: usize = 56;
+}
+
+/// Selects which address of a `PT_LOAD` segment is used to place its data.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ElfAddress {
+    /// Physical address `p_paddr` (aka load memory address, LMA).
+    /// This is where the data is stored in flash and what `objcopy` uses.
+    #[default]
+    Physical,
+    /// Virtual address `p_vaddr` (aka virtual memory address, VMA).
+    Virtual,
+}
+
+/// Loadable segment of the ELF file
+#[derive(Debug, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub(crate) address: usize,
+    pub(crate) data: &'a [u8],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Elf<'a> {
+    /// Entry point address (`e_entry`)
+    pub(crate) entry: u64,
+    /// Loadable segments with non-empty file image, in program header order
+    pub(crate) segments: Vec<Segment<'a>>,
+}
+
+/// Endianness-aware reader of the fixed-size ELF fields
+struct Reader<'a> {
+    raw: &'a [u8],
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], IntelHexErrorKind> {
+        offset
+            .checked_add(N)
+            .and_then(|end| self.raw.get(offset..end))
+            .and_then(|b| b.try_into().ok())
+            .ok_or(IntelHexErrorKind::InvalidElfHeader)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, IntelHexErrorKind> {
+        let b = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, IntelHexErrorKind> {
+        let b = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, IntelHexErrorKind> {
+        let b = self.bytes(offset)?;
+        Ok(if self.big_endian {
+            u64::from_be_bytes(b)
+        } else {
+            u64::from_le_bytes(b)
+        })
+    }
+}
+
+impl<'a> Elf<'a> {
+    /// Parse the raw contents of the ELF file and collect its loadable segments.
+    ///
+    pub(crate) fn parse(raw: &'a [u8], addressing: ElfAddress) -> Result<Self, IntelHexErrorKind> {
+        // Check for magic number
+        if raw.len() < consts::EHDR_32_SIZE || &raw[..4] != consts::MAGIC {
+            return Err(IntelHexErrorKind::InvalidElfHeader);
+        }
+
+        // Get class (32- or 64-bit) and endianness
+        let is_64 = match raw[4] {
+            consts::CLASS_32 => false,
+            consts::CLASS_64 => true,
+            _ => return Err(IntelHexErrorKind::InvalidElfHeader),
+        };
+        let big_endian = match raw[5] {
+            consts::DATA_LE => false,
+            consts::DATA_BE => true,
+            _ => return Err(IntelHexErrorKind::InvalidElfHeader),
+        };
+
+        let reader = Reader { raw, big_endian };
+
+        // Get entry point and program header table location
+        let (entry, phoff, phentsize, phnum) = if is_64 {
+            if raw.len() < consts::EHDR_64_SIZE {
+                return Err(IntelHexErrorKind::InvalidElfHeader);
+            }
+            (
+                reader.u64(0x18)?,
+                reader.u64(0x20)?,
+                reader.u16(0x36)?,
+                reader.u16(0x38)?,
+            )
+        } else {
+            (
+                u64::from(reader.u32(0x18)?),
+                u64::from(reader.u32(0x1C)?),
+                reader.u16(0x2A)?,
+                reader.u16(0x2C)?,
+            )
+        };
+
+        // Sanity check for program header entry size
+        let min_phentsize = if is_64 {
+            consts::PHDR_64_SIZE
+        } else {
+            consts::PHDR_32_SIZE
+        };
+        if phnum != 0 && (phentsize as usize) < min_phentsize {
+            return Err(IntelHexErrorKind::InvalidElfHeader);
+        }
+
+        // The whole program header table has to be within the file
+        let phoff = usize::try_from(phoff)
+            .ok()
+            .filter(|&start| start.saturating_add(phnum as usize * phentsize as usize) <= raw.len())
+            .ok_or(IntelHexErrorKind::InvalidElfHeader)?;
+
+        let mut segments = Vec::new();
+
+        for index in 0..phnum as usize {
+            let ph = phoff + index * phentsize as usize;
+
+            // Skip everything but loadable segments
+            if reader.u32(ph)? != consts::PT_LOAD {
+                continue;
+            }
+
+            let (offset, vaddr, paddr, filesz) = if is_64 {
+                (
+                    reader.u64(ph + 0x08)?,
+                    reader.u64(ph + 0x10)?,
+                    reader.u64(ph + 0x18)?,
+                    reader.u64(ph + 0x20)?,
+                )
+            } else {
+                (
+                    u64::from(reader.u32(ph + 0x04)?),
+                    u64::from(reader.u32(ph + 0x08)?),
+                    u64::from(reader.u32(ph + 0x0C)?),
+                    u64::from(reader.u32(ph + 0x10)?),
+                )
+            };
+
+            // Segments without file image (e.g. .bss) do not hold any data
+            if filesz == 0 {
+                continue;
+            }
+
+            // Get the segment's data from the file image
+            let data = usize::try_from(offset)
+                .ok()
+                .zip(usize::try_from(filesz).ok())
+                .and_then(|(start, len)| start.checked_add(len).map(|end| (start, end)))
+                .and_then(|(start, end)| raw.get(start..end))
+                .ok_or(IntelHexErrorKind::ElfSegmentOutOfBounds(index))?;
+
+            let address = match addressing {
+                ElfAddress::Physical => paddr,
+                ElfAddress::Virtual => vaddr,
+            };
+            // The whole segment has to fit into the 32-bit address space of the hex formats
+            let address = usize::try_from(address)
+                .ok()
+                .filter(|address| {
+                    address
+                        .checked_add(data.len())
+                        .is_some_and(|end| end <= u32::MAX as usize + 1)
+                })
+                .ok_or(IntelHexErrorKind::ElfSegmentOutOfBounds(index))?;
+
+            segments.push(Segment { address, data });
+        }
+
+        Ok(Self { entry, segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a minimal ELF32 little endian header followed by the given program headers
+    ///
+    #[allow(clippy::cast_possible_truncation)]
+    fn build_elf32_le(entry: u32, phdrs: &[[u32; 8]]) -> Vec<u8> {
+        let mut raw = vec![0u8; consts::EHDR_32_SIZE];
+        raw[..4].copy_from_slice(consts::MAGIC);
+        raw[4] = consts::CLASS_32;
+        raw[5] = consts::DATA_LE;
+        raw[0x18..0x1C].copy_from_slice(&entry.to_le_bytes());
+        raw[0x1C..0x20].copy_from_slice(&(consts::EHDR_32_SIZE as u32).to_le_bytes());
+        raw[0x2A..0x2C].copy_from_slice(&(consts::PHDR_32_SIZE as u16).to_le_bytes());
+        raw[0x2C..0x2E].copy_from_slice(&(phdrs.len() as u16).to_le_bytes());
+        for ph in phdrs {
+            for field in ph {
+                raw.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+        raw
+    }
+
+    #[test]
+    fn test_parse_valid_elf() {
+        // Arrange: one loadable segment with 4 bytes right after the header
+        let mut raw = build_elf32_le(0x1234, &[[1, 84, 0x2000_0000, 0x0800_0000, 4, 4, 5, 4]]);
+        raw.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        // Act
+        let phys = Elf::parse(&raw, ElfAddress::Physical);
+        let virt = Elf::parse(&raw, ElfAddress::Virtual);
+
+        // Assert
+        assert_eq!(
+            phys,
+            Ok(Elf {
+                entry: 0x1234,
+                segments: vec![Segment {
+                    address: 0x0800_0000,
+                    data: &[0xDE, 0xAD, 0xBE, 0xEF]
+                }]
+            })
+        );
+        assert_eq!(virt.map(|elf| elf.segments[0].address), Ok(0x2000_0000));
+    }
+
+    #[test]
+    fn test_parse_skips_non_load_and_empty_segments() {
+        // Arrange: PT_NOTE segment and PT_LOAD segment without file image
+        let raw = build_elf32_le(
+            0,
+            &[[4, 0, 0, 0, 0, 0, 4, 4], [1, 0, 0x100, 0x100, 0, 8, 6, 4]],
+        );
+
+        // Act
+        let res = Elf::parse(&raw, ElfAddress::Physical);
+
+        // Assert
+        assert_eq!(res.map(|elf| elf.segments.len()), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_invalid_elf() {
+        // Bad magic number
+        let mut raw = build_elf32_le(0, &[]);
+        raw[0] = 0;
+        assert_eq!(
+            Elf::parse(&raw, ElfAddress::Physical),
+            Err(IntelHexErrorKind::InvalidElfHeader)
+        );
+
+        // Unknown class
+        let mut raw = build_elf32_le(0, &[]);
+        raw[4] = 3;
+        assert_eq!(
+            Elf::parse(&raw, ElfAddress::Physical),
+            Err(IntelHexErrorKind::InvalidElfHeader)
+        );
+
+        // Truncated program header table
+        let mut raw = build_elf32_le(0, &[[1, 0, 0, 0, 0, 0, 0, 0]]);
+        raw.truncate(raw.len() - 4);
+        assert_eq!(
+            Elf::parse(&raw, ElfAddress::Physical),
+            Err(IntelHexErrorKind::InvalidElfHeader)
+        );
+
+        // Segment data outside of the file
+        let raw = build_elf32_le(0, &[[1, 84, 0, 0, 16, 16, 5, 4]]);
+        assert_eq!(
+            Elf::parse(&raw, ElfAddress::Physical),
+            Err(IntelHexErrorKind::ElfSegmentOutOfBounds(0))
+        );
+
+        // Segment data above the 32-bit address space
+        let mut raw = build_elf32_le(0, &[[1, 84, 0xFFFF_FFF8, 0x1000, 16, 16, 5, 4]]);
+        raw.extend_from_slice(&[0u8; 16]);
+        assert_eq!(
+            Elf::parse(&raw, ElfAddress::Virtual),
+            Err(IntelHexErrorKind::ElfSegmentOutOfBounds(0))
+        );
+        assert!(Elf::parse(&raw, ElfAddress::Physical).is_ok());
+    }
+}
@@ -5,6 +5,7 @@
 //! 2. What kind of error was encountered (via [`IntelHexErrorKind`] struct).
 //! 3. What is the line number (if applicable), e.g., at which line in a hex file the parsing failed.
 
+use crate::options::HexFormat;
 use crate::record::RecordType;
 use std::error::Error;
 use std::fmt;
@@ -14,6 +15,8 @@ pub enum IntelHexError {
     ParseRecordError(IntelHexErrorKind, usize),
     CreateRecordError(IntelHexErrorKind),
     UpdateError(IntelHexErrorKind),
+    ParseElfError(IntelHexErrorKind),
+    ReadError(IntelHexErrorKind),
 }
 
 impl fmt::Display for IntelHexError {
@@ -37,6 +40,15 @@ impl fmt::Display for IntelHexError {
                     "Error encountered during update of IntelHex instance:\n{base_err}",
                 )
             }
+            Self::ParseElfError(base_err) => {
+                write!(f, "Error encountered during ELF file parsing:\n{base_err}")
+            }
+            Self::ReadError(base_err) => {
+                write!(
+                    f,
+                    "Error encountered during read of IntelHex instance:\n{base_err}"
+                )
+            }
         }
     }
 }
@@ -75,9 +87,54 @@ pub enum IntelHexErrorKind {
     IntelHexInstanceEmpty,
     /// Address relocation failed due to overflow
     RelocateAddressOverflow(usize),
+    /// Record count (S5 / S6 record) does not match the number of data records
+    RecordCountMismatch(usize, usize),
+    /// ELF header or program header table is invalid or not supported
+    InvalidElfHeader,
+    /// Data of the program header with the given index lies outside of the ELF file
+    /// or outside of the 32-bit address space
+    ElfSegmentOutOfBounds(usize),
+    /// Encountered records after the End Of File record
+    DataAfterEndOfFile,
+    /// Merged data differs from the existing data at the given address
+    MergeDataConflict(usize),
+    /// Fill pattern contains no bytes
+    EmptyFillPattern,
+    /// Alignment has to be greater than zero
+    InvalidAlignment(usize),
+    /// Checksum cannot be embedded at an address inside the checksummed range
+    ChecksumTargetOverlap(usize),
+    /// Address cannot be represented in the selected output format
+    AddressOutOfRangeForFormat(HexFormat, usize),
+    /// Search pattern contains characters other than hex digits and `?` wildcards,
+    /// or an odd number of them
+    InvalidSearchPattern,
+    /// Search mask length differs from pattern length: (pattern length, mask length)
+    SearchMaskLengthMismatch(usize, usize),
+    /// Data segment at the given address does not cover whole words of a word-addressed file
+    AddressNotWordAligned(usize),
+    /// Word size for byte swapping has to be 2, 4 or 8
+    InvalidWordSize(usize),
+    /// Permutation is not a reordering of the indices `0..len`
+    InvalidPermutation,
+    /// Range length is not a multiple of the group size: (range length, group size)
+    RangeLengthNotMultiple(usize, usize),
+    /// Name of an exported array is not a valid C / Rust / assembler identifier
+    InvalidIdentifier,
+    /// Word width in bits is zero
+    InvalidWordWidth(usize),
+    /// Word value contains invalid digits or does not fit into the word width
+    InvalidWordValue,
+    /// Memory initialization file contains an unexpected or malformed statement
+    InvalidMemInitSyntax,
+    /// Data is not preceded by an address (e.g. `@ADDR` line of TI-TXT files)
+    MissingAddress,
+    /// Record address lies below the end of the previously streamed data
+    RecordAddressNotAscending(usize),
 }
 
 impl fmt::Display for IntelHexErrorKind {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::MissingStartCode => {
@@ -140,9 +197,134 @@ impl fmt::Display for IntelHexErrorKind {
                     "Address relocation failed due to overflow. Max allowed start address: 0x{address:X}"
                 )
             }
+            Self::RecordCountMismatch(expected, actual) => {
+                write!(
+                    f,
+                    "Invalid record count - expected: {expected}, found: {actual}"
+                )
+            }
+            Self::InvalidElfHeader => {
+                write!(f, "Invalid or unsupported ELF header")
+            }
+            Self::ElfSegmentOutOfBounds(index) => {
+                write!(
+                    f,
+                    "Data of program header #{index} lies outside of the ELF file \
+                     or the 32-bit address space"
+                )
+            }
+            Self::DataAfterEndOfFile => {
+                write!(f, "Encountered data after End Of File record")
+            }
+            Self::MergeDataConflict(address) => {
+                write!(
+                    f,
+                    "Merged data differs from existing data at address: 0x{address:X}"
+                )
+            }
+            Self::EmptyFillPattern => {
+                write!(f, "Fill pattern is empty")
+            }
+            Self::InvalidAlignment(align) => {
+                write!(f, "Invalid alignment: {align}")
+            }
+            Self::ChecksumTargetOverlap(address) => {
+                write!(
+                    f,
+                    "Checksum target address 0x{address:X} lies inside the checksummed range"
+                )
+            }
+            Self::AddressOutOfRangeForFormat(format, address) => {
+                write!(
+                    f,
+                    "Address 0x{address:X} cannot be represented in {format:?} format"
+                )
+            }
+            Self::InvalidSearchPattern => {
+                write!(
+                    f,
+                    "Invalid search pattern: expected pairs of hex digits or '?' wildcards"
+                )
+            }
+            Self::SearchMaskLengthMismatch(pattern_len, mask_len) => {
+                write!(
+                    f,
+                    "Search mask length differs from pattern length - expected: {pattern_len}, found: {mask_len}"
+                )
+            }
+            Self::AddressNotWordAligned(address) => {
+                write!(
+                    f,
+                    "Data at address 0x{address:X} does not cover whole words of the address unit"
+                )
+            }
+            Self::InvalidWordSize(size) => {
+                write!(f, "Invalid word size: {size} (expected 2, 4 or 8)")
+            }
+            Self::InvalidPermutation => {
+                write!(f, "Permutation is not a reordering of the byte indices")
+            }
+            Self::RangeLengthNotMultiple(len, group) => {
+                write!(
+                    f,
+                    "Range length {len} is not a multiple of the group size {group}"
+                )
+            }
+            Self::InvalidIdentifier => {
+                write!(
+                    f,
+                    "Invalid identifier (expected a letter or '_' followed by letters, digits or '_')"
+                )
+            }
+            Self::InvalidWordWidth(width) => {
+                write!(
+                    f,
+                    "Invalid word width: {width} bits (expected at least 1 bit)"
+                )
+            }
+            Self::InvalidWordValue => {
+                write!(
+                    f,
+                    "Word value contains invalid digits or does not fit into the word width"
+                )
+            }
+            Self::InvalidMemInitSyntax => {
+                write!(f, "Unexpected or malformed statement")
+            }
+            Self::MissingAddress => {
+                write!(f, "Data is not preceded by an address")
+            }
+            Self::RecordAddressNotAscending(address) => {
+                write!(
+                    f,
+                    "Record address 0x{address:X} lies below the end of the previous data (records are not in ascending order)"
+                )
+            }
         }
     }
 }
 
+/// Problem found (and skipped or repaired) during lenient parsing of a hex file.
+/// See [`ParseOptions`](crate::ParseOptions).
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Line number (1-based) of the record in the hex file
+    pub line: usize,
+    /// Column number (1-based) of the offending field within the record
+    pub column: usize,
+    /// What kind of problem was encountered
+    pub kind: IntelHexErrorKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
 impl Error for IntelHexError {}
 impl Error for IntelHexErrorKind {}
@@ -0,0 +1,340 @@
+//! The `export` module extends [`IntelHex`] with exporters which emit the image data as
+//! source code, e.g. to embed a secondary firmware image into host firmware:
+//! C header, Rust `static` array or GNU assembler `.byte` directives (see [`SourceFormat`]).
+
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::{IntelHex, create_file_writer};
+use std::error::Error;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+/// Number of bytes per line of the emitted arrays
+const BYTES_PER_LINE: usize = 16;
+
+/// Source code flavour emitted by [`IntelHex::write_source()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SourceFormat {
+    /// C header with a `static const uint8_t` array and base address / length macros
+    #[default]
+    CHeader,
+    /// Rust `static` array with base address / length constants
+    RustArray,
+    /// GNU assembler `.byte` directives in the `.rodata` section
+    GnuAsm,
+}
+
+/// Part of the image exported by [`IntelHex::write_source()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExportScope {
+    /// Every segment as a separate array, named with the suffix `_<index>`
+    #[default]
+    AllSegments,
+    /// The segment containing the given address, named as given
+    Segment(usize),
+}
+
+/// Check that the name is a valid C / Rust / assembler identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Write the bytes as comma separated `0xNN` values, `BYTES_PER_LINE` per line.
+/// Each line starts with `prefix` and ends with `line_end` (the last one with `last_line_end`).
+fn write_byte_lines<W: Write>(
+    writer: &mut W,
+    data: &[u8],
+    prefix: &str,
+    line_end: &str,
+    last_line_end: &str,
+) -> std::io::Result<()> {
+    let lines = data.chunks(BYTES_PER_LINE).count();
+    for (i, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        let values: Vec<String> = line.iter().map(|b| format!("0x{b:02X}")).collect();
+        let end = if i + 1 < lines {
+            line_end
+        } else {
+            last_line_end
+        };
+        writeln!(writer, "{prefix}{}{end}", values.join(", "))?;
+    }
+    Ok(())
+}
+
+impl IntelHex {
+    /// Generates a source file with the image data at the specified path.
+    /// See [`IntelHex::write_source_to()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written, the name is not a valid identifier
+    /// or no segment contains the address of [`ExportScope::Segment`].
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ExportScope, IntelHex, SourceFormat};
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.write_source("build/ex1/image.h", SourceFormat::CHeader, "image", ExportScope::AllSegments)
+    ///     .unwrap();
+    ///
+    /// let header = std::fs::read_to_string("build/ex1/image.h").unwrap();
+    /// assert!(header.contains("#define IMAGE_1_BASE_ADDR 0x0001C200u"));
+    /// ```
+    pub fn write_source<P: AsRef<Path>>(
+        &self,
+        filepath: P,
+        format: SourceFormat,
+        name: &str,
+        scope: ExportScope,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_source_to(create_file_writer(filepath)?, format, name, scope)
+    }
+
+    /// Writes the image data as source code into the provided writer. Each exported
+    /// segment becomes an array together with its base address and length:
+    /// - [`SourceFormat::CHeader`]: `static const uint8_t name[]` with `NAME_BASE_ADDR` and
+    ///   `NAME_LEN` macros, wrapped in an include guard
+    /// - [`SourceFormat::RustArray`]: `pub static NAME: [u8; N]` with `NAME_BASE_ADDR` and
+    ///   `NAME_LEN` constants
+    /// - [`SourceFormat::GnuAsm`]: global label `name` followed by `.byte` directives
+    ///
+    /// # Errors
+    /// - Returns `InvalidIdentifier` if the name is not a valid identifier
+    /// - Returns `InvalidAddress` if no segment contains the address of [`ExportScope::Segment`]
+    /// - Returns an error if the writer fails
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ExportScope, IntelHex, SourceFormat};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x0800_0000, &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_source_to(&mut out, SourceFormat::RustArray, "radio_fw", ExportScope::Segment(0x0800_0000))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "// Generated by intelhexlib\n\
+    ///      \n\
+    ///      pub const RADIO_FW_BASE_ADDR: usize = 0x0800_0000;\n\
+    ///      pub const RADIO_FW_LEN: usize = 4;\n\
+    ///      pub static RADIO_FW: [u8; RADIO_FW_LEN] = [\n    0xDE, 0xAD, 0xBE, 0xEF,\n];\n"
+    /// );
+    /// ```
+    pub fn write_source_to<W: Write>(
+        &self,
+        mut writer: W,
+        format: SourceFormat,
+        name: &str,
+        scope: ExportScope,
+    ) -> Result<(), Box<dyn Error>> {
+        if !is_identifier(name) {
+            return Err(Box::new(IntelHexError::ReadError(
+                IntelHexErrorKind::InvalidIdentifier,
+            )));
+        }
+
+        // Name and address range of each exported array
+        let arrays: Vec<(String, Range<usize>)> = match scope {
+            ExportScope::AllSegments => self
+                .segments()
+                .enumerate()
+                .map(|(i, segment)| (format!("{name}_{i}"), segment))
+                .collect(),
+            ExportScope::Segment(address) => {
+                let segment = self
+                    .segment_containing(address)
+                    .ok_or(IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(
+                        address,
+                    )))?;
+                vec![(name.to_string(), segment)]
+            }
+        };
+
+        match format {
+            SourceFormat::CHeader => {
+                let guard = format!("{}_H", name.to_ascii_uppercase());
+                writeln!(writer, "/* Generated by intelhexlib */")?;
+                writeln!(writer, "#ifndef {guard}\n#define {guard}\n")?;
+                writeln!(writer, "#include <stdint.h>")?;
+                for (array, range) in &arrays {
+                    let macro_name = array.to_ascii_uppercase();
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "#define {macro_name}_BASE_ADDR 0x{:08X}u",
+                        range.start
+                    )?;
+                    writeln!(writer, "#define {macro_name}_LEN {}u", range.len())?;
+                    writeln!(
+                        writer,
+                        "static const uint8_t {array}[{macro_name}_LEN] = {{"
+                    )?;
+                    write_byte_lines(&mut writer, self.segment_data(range), "    ", ",", "")?;
+                    writeln!(writer, "}};")?;
+                }
+                writeln!(writer, "\n#endif /* {guard} */")?;
+            }
+            SourceFormat::RustArray => {
+                writeln!(writer, "// Generated by intelhexlib")?;
+                for (array, range) in &arrays {
+                    let const_name = array.to_ascii_uppercase();
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "pub const {const_name}_BASE_ADDR: usize = 0x{:04X}_{:04X};",
+                        range.start >> 16,
+                        range.start & 0xFFFF
+                    )?;
+                    writeln!(
+                        writer,
+                        "pub const {const_name}_LEN: usize = {};",
+                        range.len()
+                    )?;
+                    writeln!(
+                        writer,
+                        "pub static {const_name}: [u8; {const_name}_LEN] = ["
+                    )?;
+                    write_byte_lines(&mut writer, self.segment_data(range), "    ", ",", ",")?;
+                    writeln!(writer, "];")?;
+                }
+            }
+            SourceFormat::GnuAsm => {
+                writeln!(writer, "/* Generated by intelhexlib */")?;
+                writeln!(writer, "    .section .rodata")?;
+                for (array, range) in &arrays {
+                    writeln!(writer)?;
+                    writeln!(
+                        writer,
+                        "/* Base address 0x{:08X}, length {} bytes */",
+                        range.start,
+                        range.len()
+                    )?;
+                    writeln!(writer, "    .global {array}\n{array}:")?;
+                    write_byte_lines(&mut writer, self.segment_data(range), "    .byte ", "", "")?;
+                    writeln!(writer, "    .size {array}, . - {array}")?;
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Data of the whole segment (a contiguous range holding data).
+    fn segment_data(&self, segment: &Range<usize>) -> &[u8] {
+        self.read_slice(segment.start, segment.len())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("_radio_fw2"));
+        assert!(!is_identifier("2nd_fw"));
+        assert!(!is_identifier("radio-fw"));
+        assert!(!is_identifier(""));
+    }
+
+    #[test]
+    fn test_write_c_header_all_segments() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x01, 0x02]).is_ok());
+        assert!(ih.write_range(0x20, &[0x03]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_source_to(
+            &mut out,
+            SourceFormat::CHeader,
+            "fw",
+            ExportScope::AllSegments,
+        );
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(
+            String::from_utf8(out).unwrap_or_default(),
+            "/* Generated by intelhexlib */\n\
+             #ifndef FW_H\n#define FW_H\n\n\
+             #include <stdint.h>\n\n\
+             #define FW_0_BASE_ADDR 0x00000010u\n\
+             #define FW_0_LEN 2u\n\
+             static const uint8_t fw_0[FW_0_LEN] = {\n    0x01, 0x02\n};\n\n\
+             #define FW_1_BASE_ADDR 0x00000020u\n\
+             #define FW_1_LEN 1u\n\
+             static const uint8_t fw_1[FW_1_LEN] = {\n    0x03\n};\n\n\
+             #endif /* FW_H */\n"
+        );
+    }
+
+    #[test]
+    fn test_write_gnu_asm_segment() {
+        // Arrange: 17 bytes -> two lines of directives
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x100, &[0xAA; 17]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_source_to(
+            &mut out,
+            SourceFormat::GnuAsm,
+            "blob",
+            ExportScope::Segment(0x108),
+        );
+
+        // Assert
+        assert!(res.is_ok());
+        let text = String::from_utf8(out).unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[3], "/* Base address 0x00000100, length 17 bytes */");
+        assert_eq!(lines[4], "    .global blob");
+        assert_eq!(lines[5], "blob:");
+        assert_eq!(
+            lines[6],
+            format!("    .byte {}", vec!["0xAA"; 16].join(", "))
+        );
+        assert_eq!(lines[7], "    .byte 0xAA");
+        assert_eq!(lines[8], "    .size blob, . - blob");
+    }
+
+    #[test]
+    fn test_write_source_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x01]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res_name = ih.write_source_to(
+            &mut out,
+            SourceFormat::CHeader,
+            "fw-1",
+            ExportScope::AllSegments,
+        );
+        let res_addr = ih.write_source_to(
+            &mut out,
+            SourceFormat::CHeader,
+            "fw",
+            ExportScope::Segment(0x11),
+        );
+
+        // Assert: nothing is written
+        let expected_name = IntelHexError::ReadError(IntelHexErrorKind::InvalidIdentifier);
+        let expected_addr = IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(0x11));
+        assert!(res_name.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected_name)));
+        assert!(res_addr.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected_addr)));
+        assert!(out.is_empty());
+    }
+}
@@ -6,12 +6,26 @@
 //! The module also provides utilities for binary file interop, memory relocation,
 //! and generating valid Intel HEX output with configurable record sizes.
 
-use crate::error::{IntelHexError, IntelHexErrorKind};
-use crate::record::{Record, RecordType};
+use crate::checksum::Checksum;
+use crate::chunks::{ChunksIn, ChunksInMut};
+use crate::diff::{DiffRange, diff_buffers};
+use crate::elf::{Elf, ElfAddress};
+use crate::error::{Diagnostic, IntelHexError, IntelHexErrorKind};
+use crate::layout::{LayoutRecord, RecordLayout};
+use crate::options::{
+    AddressUnit, Endian, HexFormat, HexWriteOptions, LineEnding, OverlapPolicy, ParseOptions,
+};
+use crate::record::{Record, RecordType, StartAddress};
+use crate::search::{FindIter, Pattern};
+use crate::srecord::{SRecord, SRecordType};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::error::Error;
-use std::io::Write;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub struct IntelHex {
@@ -19,10 +33,20 @@ pub struct IntelHex {
     pub filepath: PathBuf,
     /// Intel HEX file size in bytes
     pub size: usize,
-    /// Start address of the Intel HEX file (stores full record as a byte slice)
-    pub start_addr: Option<[u8; 10]>,
+    /// Start address (entry point) taken from the start address record of an Intel HEX file,
+    /// from the S7 / S8 / S9 termination record of an S-record file, from the termination
+    /// record of a Tektronix hex file or from the ELF header
+    start_addr: Option<StartAddress>,
     /// Maximum payload size for data records
     max_payload_size: usize,
+    /// Intel HEX flavour used when writing hex files
+    format: HexFormat,
+    /// Textual layout of written hex files
+    write_options: HexWriteOptions,
+    /// Unit of the addresses in the hex file
+    address_unit: AddressUnit,
+    /// Record layout of the parsed hex file (if preserved), reproduced when writing
+    layout: Option<RecordLayout>,
     /// Offset of the linear address segment
     offset: usize,
     /// Data buffer of the Intel HEX file.
@@ -56,6 +80,248 @@ impl IntoIterator for IntelHex {
     }
 }
 
+/// Parses the contents of a hex file held in a string.
+///
+/// # Example
+/// ```
+/// use intelhexlib::IntelHex;
+///
+/// let ih: IntelHex = ":0400100000010203E6\n:00000001FF".parse().unwrap();
+///
+/// assert_eq!(ih.read_byte(0x13), Some(0x03));
+/// ```
+impl FromStr for IntelHex {
+    type Err = IntelHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_bytes(s.as_bytes())
+    }
+}
+
+/// Check that the range of `len` bytes starting at `start_addr` fits into 32-bit address space.
+///
+/// # Errors
+/// Returns `InvalidAddress` if the range does not fit.
+///
+const fn check_range_fits(start_addr: usize, len: usize) -> Result<(), IntelHexError> {
+    match start_addr.checked_add(len) {
+        Some(end_addr) if end_addr <= u32::MAX as usize + 1 => Ok(()),
+        _ => Err(IntelHexError::UpdateError(
+            IntelHexErrorKind::InvalidAddress(start_addr),
+        )),
+    }
+}
+
+/// Move the start address by the offset of a relocation.
+/// A segment start address keeps its IP if the new address can still be reached with it,
+/// otherwise it is normalized (IP below 16).
+///
+/// # Errors
+/// Returns `InvalidAddress` (with the original linear address) if the moved start address
+/// does not fit into the start address record.
+///
+fn shift_start_address(
+    start_addr: StartAddress,
+    offset: i64,
+) -> Result<StartAddress, IntelHexError> {
+    let error = || {
+        IntelHexError::UpdateError(IntelHexErrorKind::InvalidAddress(
+            start_addr.linear() as usize
+        ))
+    };
+    let new_address = i64::from(start_addr.linear()) + offset;
+
+    match start_addr {
+        StartAddress::Linear(_) => u32::try_from(new_address)
+            .map(StartAddress::Linear)
+            .map_err(|_| error()),
+        StartAddress::Segment { ip, .. } => {
+            let new_address = u32::try_from(new_address).map_err(|_| error())?;
+            let keep_ip = new_address
+                .checked_sub(u32::from(ip))
+                .filter(|base| base % 16 == 0)
+                .and_then(|base| u16::try_from(base / 16).ok())
+                .map(|cs| StartAddress::Segment { cs, ip });
+            let normalized = u16::try_from(new_address / 16)
+                .ok()
+                .zip(u16::try_from(new_address % 16).ok())
+                .map(|(cs, ip)| StartAddress::Segment { cs, ip });
+            keep_ip.or(normalized).ok_or_else(error)
+        }
+    }
+}
+
+/// Map the data of a record at the file address to the byte-addressed buffer.
+/// Returns the buffer address of the data and the data without phantom bytes.
+///
+fn file_to_buffer(unit: AddressUnit, file_addr: usize, data: Vec<u8>) -> (usize, Vec<u8>) {
+    match unit {
+        AddressUnit::Pic24 => {
+            // Phantom byte is the last of the 4 bytes of each instruction word
+            let is_real = |addr: usize| addr % 4 != 3;
+            let first = (file_addr..file_addr + data.len())
+                .find(|&addr| is_real(addr))
+                .unwrap_or(file_addr);
+            let data = data
+                .into_iter()
+                .zip(file_addr..)
+                .filter_map(|(byte, addr)| is_real(addr).then_some(byte))
+                .collect();
+            (first / 4 * 3 + first % 4, data)
+        }
+        AddressUnit::Byte | AddressUnit::Word(_) => (file_addr * unit.bytes_per_address(), data),
+    }
+}
+
+/// Map the byte-addressed buffer to the data of the file: insert the phantom bytes (PIC24)
+/// or check that each chunk covers whole words.
+///
+/// # Errors
+/// Returns `AddressNotWordAligned` with the start of the first chunk not covering whole words.
+///
+fn buffer_to_file(
+    unit: AddressUnit,
+    buffer: &BTreeMap<usize, Vec<u8>>,
+) -> Result<Cow<'_, BTreeMap<usize, Vec<u8>>>, IntelHexError> {
+    if unit == AddressUnit::Pic24 {
+        let file_buffer = buffer
+            .iter()
+            .map(|(&addr, data)| {
+                let mut file_data = Vec::with_capacity(data.len() / 3 * 4 + 4);
+                for (byte_addr, &byte) in (addr..).zip(data) {
+                    file_data.push(byte);
+                    if byte_addr % 3 == 2 {
+                        file_data.push(0x00);
+                    }
+                }
+                (addr / 3 * 4 + addr % 3, file_data)
+            })
+            .collect();
+        return Ok(Cow::Owned(file_buffer));
+    }
+
+    let size = unit.bytes_per_address();
+    match buffer
+        .iter()
+        .find(|(addr, data)| *addr % size != 0 || data.len() % size != 0)
+    {
+        Some((&addr, _)) => Err(IntelHexError::CreateRecordError(
+            IntelHexErrorKind::AddressNotWordAligned(addr),
+        )),
+        None => Ok(Cow::Borrowed(buffer)),
+    }
+}
+
+/// Resolve the start address of two merged instances according to the policy.
+///
+/// # Errors
+/// Returns an error if both are set and the policy does not allow it.
+///
+fn merge_start<T: PartialEq>(
+    own: Option<T>,
+    other: Option<T>,
+    policy: OverlapPolicy,
+) -> Result<Option<T>, IntelHexError> {
+    match (own, other) {
+        (Some(own), Some(other)) => match policy {
+            OverlapPolicy::KeepExisting => Ok(Some(own)),
+            OverlapPolicy::Overwrite => Ok(Some(other)),
+            OverlapPolicy::ErrorIfDifferent if own == other => Ok(Some(own)),
+            OverlapPolicy::Error | OverlapPolicy::ErrorIfDifferent => Err(
+                IntelHexError::UpdateError(IntelHexErrorKind::DuplicateStartAddress),
+            ),
+        },
+        (own, other) => Ok(own.or(other)),
+    }
+}
+
+/// Handle a problem found in the record at line `count`: return it as an error in strict
+/// parsing mode or store it as a diagnostic in lenient mode.
+///
+/// # Errors
+/// Returns an error if the parsing mode is strict.
+///
+fn report_problem(
+    options: ParseOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+    kind: IntelHexErrorKind,
+    count: usize,
+    line: &[u8],
+) -> Result<(), IntelHexError> {
+    if !options.lenient {
+        return Err(IntelHexError::ParseRecordError(kind, count));
+    }
+
+    // Point to the record field (1-based column) the problem originates from
+    let column = match kind {
+        IntelHexErrorKind::ContainsInvalidCharacters => line
+            .iter()
+            .skip(1)
+            .position(|b| !b.is_ascii_hexdigit())
+            .map_or(1, |i| i + 2),
+        IntelHexErrorKind::RecordInvalidPayloadLength
+        | IntelHexErrorKind::RecordLengthInvalidForType(..) => 2,
+        IntelHexErrorKind::RecordAddressInvalidForType(..)
+        | IntelHexErrorKind::RecordAddressOverlap(_) => 4,
+        IntelHexErrorKind::InvalidRecordType | IntelHexErrorKind::DuplicateStartAddress => 8,
+        IntelHexErrorKind::RecordChecksumMismatch(..) => line.len() - 1,
+        _ => 1,
+    };
+
+    diagnostics.push(Diagnostic {
+        line: count,
+        column,
+        kind,
+    });
+    Ok(())
+}
+
+/// Create the file at the specified path (including its parent directories) and wrap it
+/// in `BufWriter` for efficient line-by-line writing.
+pub fn create_file_writer<P: AsRef<Path>>(filepath: P) -> std::io::Result<BufWriter<File>> {
+    // Ensure the parent directory exists
+    if let Some(parent) = filepath.as_ref().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(filepath)?;
+
+    Ok(BufWriter::new(file))
+}
+
+/// Write `gap_size` bytes of `gap_fill` into the writer (gap between two chunks of a binary file).
+pub fn write_gap<W: Write>(writer: &mut W, gap_size: usize, gap_fill: u8) -> std::io::Result<()> {
+    // Use a small buffer to write gaps. Limit the buffer to 4096 KB as it is the
+    // default / typical page size of most OS - more efficient + avoids large heap allocations.
+    let gap_buf = vec![gap_fill; std::cmp::min(gap_size, 4096)];
+
+    let mut remaining = gap_size;
+    while remaining > 0 {
+        let to_write = std::cmp::min(remaining, gap_buf.len());
+        writer.write_all(&gap_buf[..to_write])?;
+        remaining -= to_write;
+    }
+    Ok(())
+}
+
+/// Write a single hex record followed by the line ending `eol`.
+///
+fn write_hex_record<W: Write>(
+    writer: &mut W,
+    record: &str,
+    lowercase: bool,
+    eol: &str,
+) -> std::io::Result<()> {
+    if lowercase {
+        write!(writer, "{}{eol}", record.to_ascii_lowercase())
+    } else {
+        write!(writer, "{record}{eol}")
+    }
+}
+
 impl IntelHex {
     /// Creates empty `IntelHex` struct instance.
     ///
@@ -73,6 +339,16 @@ impl IntelHex {
             size: 0,
             offset: 0,
             max_payload_size: 16,
+            format: HexFormat::I32Hex,
+            write_options: HexWriteOptions {
+                line_ending: LineEnding::Lf,
+                trailing_newline: false,
+                lowercase: false,
+                align_records: false,
+                extended_address_at_start: false,
+            },
+            address_unit: AddressUnit::Byte,
+            layout: None,
             start_addr: None,
             buffer: BTreeMap::new(),
         }
@@ -96,16 +372,177 @@ impl IntelHex {
         self.start_addr = None;
         self.offset = 0;
         self.buffer.clear();
+        self.layout = None;
+        self.address_unit = AddressUnit::Byte;
+    }
+
+    /// Insert a contiguous data chunk into the internal buffer.
+    /// The chunk is merged with its neighbours if they are adjacent, so that the buffer
+    /// always holds the smallest possible number of chunks.
+    ///
+    /// # Errors
+    /// Returns an error if the chunk overlaps with the data already present in the buffer.
+    ///
+    fn insert_chunk(&mut self, addr: usize, data: Vec<u8>) -> Result<(), IntelHexErrorKind> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let new_end_addr = addr + data.len();
+
+        // Find a neighbor (previous chunk) and check for overlaps
+        let (prev_key, can_append) = match self.buffer.range(..=addr).next_back() {
+            Some((&start, prev_chunk)) => {
+                let end = start + prev_chunk.len();
+                if addr < end {
+                    return Err(IntelHexErrorKind::RecordAddressOverlap(addr));
+                }
+                (Some(start), end == addr)
+            }
+            None => (None, false),
+        };
+
+        // Find a neighbor (next chunk) and check for overlaps
+        let can_prepend = match self.buffer.range(addr..).next() {
+            Some((&start, _)) => {
+                if start < new_end_addr {
+                    return Err(IntelHexErrorKind::RecordAddressOverlap(addr));
+                }
+                start == new_end_addr
+            }
+            None => false,
+        };
+
+        let mut current_data = data;
+
+        match (can_append, can_prepend) {
+            // BRIDGE: [prev][new][next] -> [prev_merged]
+            (true, true) => {
+                // Remove the 'next' chunk from the buffer and get its data
+                let mut next_data = self.buffer.remove(&new_end_addr).unwrap_or_default();
+                // Get the 'prev' chunk and append both 'new' and 'next' data to it.
+                // Error cases are not handled here as they were checked above.
+                if let Some(prev_data) = self.buffer.get_mut(&prev_key.unwrap_or_default()) {
+                    prev_data.append(&mut current_data);
+                    prev_data.append(&mut next_data);
+                }
+            }
+            // APPEND: [prev][new]
+            (true, false) => {
+                // Get the 'prev' chunk and append 'new' data to it.
+                // Error cases are not handled here as they were checked above.
+                if let Some(prev_data) = self.buffer.get_mut(&prev_key.unwrap_or_default()) {
+                    prev_data.append(&mut current_data);
+                }
+            }
+            // PREPEND: [new][next]
+            (false, true) => {
+                // Remove the 'next' chunk from the buffer and get its data
+                let mut next_data = self.buffer.remove(&new_end_addr).unwrap_or_default();
+                // Append 'next' data to the 'new' chunk and insert it into the buffer
+                current_data.append(&mut next_data);
+                self.buffer.insert(addr, current_data);
+            }
+            // NEW: [new]
+            (false, false) => {
+                self.buffer.insert(addr, current_data);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Split the address range `start..end` into consecutive parts which either hold data
+    /// (`true`) or fall into a gap of the buffer (`false`).
+    /// Each part holding data lies within a single chunk.
+    ///
+    fn partition_range(&self, start: usize, end: usize) -> Vec<(usize, usize, bool)> {
+        let mut parts = Vec::new();
+        let mut addr = start;
+
+        if start >= end {
+            return parts;
+        }
+
+        // Chunk starting before the range may still cover its beginning
+        let first = self.first_chunk_in(start);
+
+        for (&chunk_start, data) in self.buffer.range(first..end) {
+            let chunk_end = chunk_start + data.len();
+            if chunk_end <= addr {
+                continue;
+            }
+            if chunk_start > addr {
+                parts.push((addr, chunk_start, false));
+                addr = chunk_start;
+            }
+            let part_end = chunk_end.min(end);
+            parts.push((addr, part_end, true));
+            addr = part_end;
+        }
+
+        if addr < end {
+            parts.push((addr, end, false));
+        }
+
+        parts
+    }
+
+    /// Ensure that a data chunk starts at `addr` if the address holds data,
+    /// by splitting the chunk that spans across it.
+    ///
+    fn split_chunk_at(&mut self, addr: usize) {
+        if let Some((&chunk_start, chunk)) = self.buffer.range_mut(..addr).next_back()
+            && addr < chunk_start + chunk.len()
+        {
+            let tail = chunk.split_off(addr - chunk_start);
+            self.buffer.insert(addr, tail);
+        }
+    }
+
+    /// Write a contiguous data chunk into the internal buffer. Gaps are filled with the data
+    /// (creating or extending chunks), while the existing data is replaced only if `overwrite`
+    /// is set. The buffer is kept canonical, i.e., adjacent chunks are coalesced.
+    ///
+    fn write_chunk(&mut self, addr: usize, data: &[u8], overwrite: bool) {
+        let parts = self.partition_range(addr, addr + data.len());
+
+        // Overwrite existing data before filling the gaps, as filling
+        // can coalesce (and therefore re-key) the existing chunks
+        if overwrite {
+            for &(start, end, _) in parts.iter().filter(|(_, _, has_data)| *has_data) {
+                if let Some((&chunk_start, chunk)) = self.buffer.range_mut(..=start).next_back() {
+                    chunk[start - chunk_start..end - chunk_start]
+                        .copy_from_slice(&data[start - addr..end - addr]);
+                }
+            }
+        }
+
+        // Gaps do not overlap any data -> inserting cannot fail
+        for &(start, end, _) in parts.iter().filter(|(_, _, has_data)| !*has_data) {
+            let _ = self.insert_chunk(start, data[start - addr..end - addr].to_vec());
+        }
     }
 
     /// Parse the raw contents of the hex file and fill internal record vector.
+    /// In lenient mode bad records are skipped (or repaired) and reported as diagnostics.
     ///
     /// # Errors
+    /// In strict mode:
     /// - Returns an error if the record is corrupted
     /// - Returns an error if there is an issue during filling the internal buffer
     ///
-    fn parse(&mut self, raw_bytes: &[u8]) -> Result<(), IntelHexError> {
+    fn parse(
+        &mut self,
+        raw_bytes: &[u8],
+        options: ParseOptions,
+    ) -> Result<Vec<Diagnostic>, IntelHexError> {
+        let mut diagnostics = Vec::new();
         let mut count: usize = 0;
+        let mut eof_found = false;
+        let mut layout = (options.preserve_layout && options.address_unit.is_byte())
+            .then(|| RecordLayout::detect(raw_bytes));
+        self.address_unit = options.address_unit;
 
         // Iterate over lines of records
         for line in raw_bytes.split(|&b| b == b'\n') {
@@ -117,118 +554,172 @@ impl IntelHex {
 
             count += 1;
 
-            let record =
-                Record::parse(line).map_err(|err| IntelHexError::ParseRecordError(err, count))?;
+            // Everything after the EOF record is ignored in lenient mode
+            if eof_found && options.lenient {
+                diagnostics.push(Diagnostic {
+                    line: count,
+                    column: 1,
+                    kind: IntelHexErrorKind::DataAfterEndOfFile,
+                });
+                break;
+            }
+
+            let record = match Record::parse(line) {
+                Ok(record) => record,
+                Err(err @ IntelHexErrorKind::RecordChecksumMismatch(..)) if options.lenient => {
+                    // Checksum is the only problem of the record -> keep its data
+                    report_problem(options, &mut diagnostics, err, count, line)?;
+                    Record::parse_unchecked(line)
+                        .map_err(|err| IntelHexError::ParseRecordError(err, count))?
+                }
+                Err(err) => {
+                    report_problem(options, &mut diagnostics, err, count, line)?;
+                    continue;
+                }
+            };
+
+            // Remember the record (up to the EOF record) before it is consumed
+            let entry = layout
+                .as_ref()
+                .filter(|_| !eof_found)
+                .map(|_| LayoutRecord::new(&record, self.offset, line));
+
+            eof_found |= record.rtype == RecordType::EndOfFile;
 
             // Fill in self
-            match record.rtype {
-                RecordType::Data => {
-                    let addr = record.address as usize + self.offset;
-
-                    if !record.data.is_empty() {
-                        let new_end_addr = addr + record.data.len();
-
-                        // Find a neighbor (previous chunk) and check for overlaps
-                        let (prev_key, can_append) = match self.buffer.range(..=addr).next_back() {
-                            Some((&start, data)) => {
-                                let end = start + data.len();
-                                if addr < end {
-                                    return Err(IntelHexError::ParseRecordError(
-                                        IntelHexErrorKind::RecordAddressOverlap(addr),
-                                        count,
-                                    ));
-                                }
-                                (Some(start), end == addr)
-                            }
-                            None => (None, false),
-                        };
-
-                        // Find a neighbor (next chunk) and check for overlaps
-                        let can_prepend = match self.buffer.range(addr..).next() {
-                            Some((&start, _)) => {
-                                if start < new_end_addr {
-                                    return Err(IntelHexError::ParseRecordError(
-                                        IntelHexErrorKind::RecordAddressOverlap(addr),
-                                        count,
-                                    ));
-                                }
-                                start == new_end_addr
-                            }
-                            None => false,
-                        };
-
-                        // Take ownership of the data (not required)
-                        let mut current_data = record.data;
-
-                        match (can_append, can_prepend) {
-                            // BRIDGE: [prev][new][next] -> [prev_merged]
-                            (true, true) => {
-                                // Remove the 'next' chunk from the buffer and get its data
-                                let mut next_data =
-                                    self.buffer.remove(&new_end_addr).unwrap_or_default();
-                                // Get the 'prev' chunk and append both 'new' and 'next' data to it.
-                                // Error cases are not handled here as they were checked above.
-                                if let Some(prev_data) =
-                                    self.buffer.get_mut(&prev_key.unwrap_or_default())
-                                {
-                                    prev_data.append(&mut current_data);
-                                    prev_data.append(&mut next_data);
-                                }
-                            }
-                            // APPEND: [prev][new]
-                            (true, false) => {
-                                // Get the 'prev' chunk and append 'new' data to it.
-                                // Error cases are not handled here as they were checked above.
-                                if let Some(prev_data) =
-                                    self.buffer.get_mut(&prev_key.unwrap_or_default())
-                                {
-                                    prev_data.append(&mut current_data);
-                                }
-                            }
-                            // PREPEND: [new][next]
-                            (false, true) => {
-                                // Remove the 'next' chunk from the buffer and get its data
-                                let mut next_data =
-                                    self.buffer.remove(&new_end_addr).unwrap_or_default();
-                                // Append 'next' data to the 'new' chunk and insert it into the buffer
-                                current_data.append(&mut next_data);
-                                self.buffer.insert(addr, current_data);
-                            }
-                            // NEW: [new]
-                            (false, false) => {
-                                self.buffer.insert(addr, current_data);
-                            }
-                        }
+            match self.apply_record(record) {
+                Ok(()) => {
+                    if let (Some(layout), Some(entry)) = (layout.as_mut(), entry) {
+                        layout.records.push(entry);
                     }
                 }
-                RecordType::EndOfFile => {}
-                RecordType::ExtendedSegmentAddress => {
-                    self.offset = (record.data[0] as usize * 256 + record.data[1] as usize) * 16;
+                Err(err) => report_problem(options, &mut diagnostics, err, count, line)?,
+            }
+        }
+
+        if layout.is_some() {
+            self.layout = layout;
+        }
+        Ok(diagnostics)
+    }
+
+    /// Fill in the contents of a parsed record.
+    ///
+    /// # Errors
+    /// - Returns an error if the data overlaps with the data already present in the buffer
+    /// - Returns an error if the start address was already set
+    ///
+    fn apply_record(&mut self, record: Record) -> Result<(), IntelHexErrorKind> {
+        match record.rtype {
+            RecordType::Data => {
+                let file_addr = record.address as usize + self.offset;
+                let (addr, data) = file_to_buffer(self.address_unit, file_addr, record.data);
+                self.insert_chunk(addr, data)?;
+            }
+            _ => record.apply_address(&mut self.offset, &mut self.start_addr)?,
+        }
+        Ok(())
+    }
+
+    /// Parse the raw contents of the S-record file and fill internal buffer.
+    ///
+    /// # Errors
+    /// - Returns an error if the record is corrupted
+    /// - Returns an error if there is an issue during filling the internal buffer
+    /// - Returns an error if the record count (S5 / S6) does not match the data records
+    ///
+    fn parse_srec(&mut self, raw_bytes: &[u8]) -> Result<(), IntelHexError> {
+        let mut count: usize = 0;
+        let mut data_records: usize = 0;
+
+        // Iterate over lines of records
+        for line in raw_bytes.split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            count += 1;
+
+            let record =
+                SRecord::parse(line).map_err(|err| IntelHexError::ParseRecordError(err, count))?;
+
+            // Fill in self
+            match record.rtype {
+                SRecordType::Header => {}
+                SRecordType::Data16 | SRecordType::Data24 | SRecordType::Data32 => {
+                    data_records += 1;
+                    self.insert_chunk(record.address as usize, record.data)
+                        .map_err(|err| IntelHexError::ParseRecordError(err, count))?;
                 }
-                RecordType::ExtendedLinearAddress => {
-                    self.offset = (record.data[0] as usize * 256 + record.data[1] as usize) * 65536;
+                SRecordType::Count16 | SRecordType::Count24 => {
+                    if record.address as usize != data_records {
+                        return Err(IntelHexError::ParseRecordError(
+                            IntelHexErrorKind::RecordCountMismatch(
+                                record.address as usize,
+                                data_records,
+                            ),
+                            count,
+                        ));
+                    }
                 }
-                RecordType::StartSegmentAddress | RecordType::StartLinearAddress => {
+                SRecordType::Start32 | SRecordType::Start24 | SRecordType::Start16 => {
                     if self.start_addr.is_some() {
                         return Err(IntelHexError::ParseRecordError(
                             IntelHexErrorKind::DuplicateStartAddress,
-                            count + 1,
+                            count,
                         ));
                     }
-                    // Directly store the record slice.
-                    // Error cases are not checked here as it was done during record parsing.
-                    // TODO: split legacy and modern way of specifying start address?
-                    if line.len() == 10
-                        && let Ok(bytes) = line[1..=10].try_into()
-                    {
-                        self.start_addr = Some(bytes);
-                    }
+                    self.start_addr = Some(StartAddress::Linear(record.address));
                 }
             }
         }
         Ok(())
     }
 
+    /// Parse the raw contents of the ELF file and fill internal buffer with
+    /// the data of its loadable segments.
+    ///
+    /// # Errors
+    /// - Returns an error if the ELF header or program headers are corrupted
+    /// - Returns an error if the loadable segments overlap
+    ///
+    fn parse_elf(&mut self, raw_bytes: &[u8], addressing: ElfAddress) -> Result<(), IntelHexError> {
+        let elf = Elf::parse(raw_bytes, addressing).map_err(IntelHexError::ParseElfError)?;
+
+        for segment in elf.segments {
+            self.insert_chunk(segment.address, segment.data.to_vec())
+                .map_err(IntelHexError::ParseElfError)?;
+        }
+
+        // Entry points above 4 GiB (e.g. high virtual addresses of 64-bit images)
+        // cannot be stored in a start address record -> left unset
+        self.start_addr = u32::try_from(elf.entry).ok().map(StartAddress::Linear);
+
+        Ok(())
+    }
+
+    /// Clear the instance and fill it with the raw contents of a hex file.
+    ///
+    /// # Errors
+    /// Returns an error if the contents cannot be parsed.
+    ///
+    fn load_hex_bytes(
+        &mut self,
+        raw_bytes: &[u8],
+        options: ParseOptions,
+    ) -> Result<Vec<Diagnostic>, IntelHexError> {
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Parse contents
+        self.parse(raw_bytes, options)
+    }
+
     /// Creates an `IntelHex` instance and fills it with data from the provided hex file.
     ///
     /// # Errors
@@ -265,19 +756,145 @@ impl IntelHex {
         // Read the contents of the file
         let raw_bytes = std::fs::read(&filepath)?;
 
-        // Clear provided IntelHex instance
-        self.clear();
-
-        // Compute the size (in bytes)
-        self.size = raw_bytes.len();
+        // Parse contents
+        self.load_hex_bytes(&raw_bytes, ParseOptions::strict())?;
 
         // Load filepath
         self.filepath = filepath.as_ref().to_path_buf();
 
+        Ok(())
+    }
+
+    /// Creates an `IntelHex` instance and fills it with data from the provided hex file,
+    /// parsed according to the provided options.
+    /// Returns the instance together with the problems found in lenient mode.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, or (in strict mode) parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, IntelHexErrorKind, ParseOptions};
+    ///
+    /// let (ih, diagnostics) =
+    ///     IntelHex::from_hex_with_options("tests/fixtures/ih_bad_checksum.hex", ParseOptions::lenient())
+    ///         .unwrap();
+    ///
+    /// assert_eq!(diagnostics[0].line, 1);
+    /// assert_eq!(diagnostics[0].kind, IntelHexErrorKind::RecordChecksumMismatch(0x55, 0xFF));
+    /// ```
+    pub fn from_hex_with_options<P: AsRef<Path>>(
+        filepath: P,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Diagnostic>), Box<dyn Error>> {
+        let mut ih = Self::new();
+        let diagnostics = ih.load_hex_with_options(filepath, options)?;
+        Ok((ih, diagnostics))
+    }
+
+    /// Fills an `IntelHex` instance with data from the provided hex file,
+    /// parsed according to the provided options.
+    /// Returns the problems found in lenient mode.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, or (in strict mode) parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, ParseOptions};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// let diagnostics = ih
+    ///     .load_hex_with_options("tests/fixtures/ih_valid_1.hex", ParseOptions::lenient())
+    ///     .unwrap();
+    ///
+    /// assert!(diagnostics.is_empty());
+    /// ```
+    pub fn load_hex_with_options<P: AsRef<Path>>(
+        &mut self,
+        filepath: P,
+        options: ParseOptions,
+    ) -> Result<Vec<Diagnostic>, Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
         // Parse contents
-        self.parse(&raw_bytes)?;
+        let diagnostics = self.load_hex_bytes(&raw_bytes, options)?;
 
-        Ok(())
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        Ok(diagnostics)
+    }
+
+    /// Creates an `IntelHex` instance and fills it with hex data read from the provided reader
+    /// (e.g. stdin, a file inside an archive or an in-memory buffer).
+    ///
+    /// # Errors
+    /// Returns an error if the reader fails or the data cannot be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let file = std::fs::File::open("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// let ih = IntelHex::from_reader(file).unwrap();
+    ///
+    /// assert_eq!(ih.size, 239);
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut raw_bytes = Vec::new();
+        reader.read_to_end(&mut raw_bytes)?;
+        Ok(Self::parse_bytes(&raw_bytes)?)
+    }
+
+    /// Creates an `IntelHex` instance and fills it with data from the raw contents of a hex file.
+    /// To parse a string, see [`IntelHex::from_str()`].
+    ///
+    /// # Errors
+    /// Returns an error if the data cannot be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::parse_bytes(b":0400100000010203E6\n:00000001FF").unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x10, 4), Some(vec![0x00, 0x01, 0x02, 0x03]));
+    /// ```
+    pub fn parse_bytes(raw_bytes: &[u8]) -> Result<Self, IntelHexError> {
+        let mut ih = Self::new();
+        ih.load_hex_bytes(raw_bytes, ParseOptions::strict())?;
+        Ok(ih)
+    }
+
+    /// Creates an `IntelHex` instance and fills it with data from the raw contents of a hex file,
+    /// parsed according to the provided options.
+    /// Returns the instance together with the problems found in lenient mode.
+    ///
+    /// # Errors
+    /// Returns an error if the data cannot be parsed in strict mode.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Diagnostic, IntelHex, IntelHexErrorKind, ParseOptions};
+    ///
+    /// let raw = b":0400100000010203E6\n:0400100000010203E6\n:00000001FF";
+    /// let (ih, diagnostics) = IntelHex::parse_bytes_with_options(raw, ParseOptions::lenient()).unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x10, 4), Some(vec![0x00, 0x01, 0x02, 0x03]));
+    /// assert_eq!(
+    ///     diagnostics,
+    ///     vec![Diagnostic { line: 2, column: 4, kind: IntelHexErrorKind::RecordAddressOverlap(0x10) }]
+    /// );
+    /// ```
+    pub fn parse_bytes_with_options(
+        raw_bytes: &[u8],
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Diagnostic>), IntelHexError> {
+        let mut ih = Self::new();
+        let diagnostics = ih.load_hex_bytes(raw_bytes, options)?;
+        Ok((ih, diagnostics))
     }
 
     /// Creates an `IntelHex` instance and fills it with data from the provided binary.
@@ -326,89 +943,342 @@ impl IntelHex {
         // Read the contents of the file
         let data = std::fs::read(&filepath)?;
 
-        // Clear provided IntelHex instance
-        self.clear();
-
-        // Compute the size (in bytes)
-        self.size = data.len();
+        // Fill in the data
+        self.load_bin_data(data, base_address);
 
         // Load filepath
         self.filepath = filepath.as_ref().to_path_buf();
 
-        // Load data bytes into the map as one chunk
-        self.buffer.insert(base_address, data);
-
         Ok(())
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    /// Generates an Intel HEX file at the specified path.
+    /// Creates an `IntelHex` instance and fills it with binary data from the provided reader.
     ///
     /// # Errors
-    /// Returns an error if the file cannot be written.
+    /// Returns an error if the reader fails.
     ///
     /// # Example
     /// ```
     /// use intelhexlib::IntelHex;
     ///
-    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
-    /// ih.write_hex("build/ex1/ih.hex");
+    /// let file = std::fs::File::open("tests/fixtures/ih_valid_1.bin").unwrap();
+    /// let ih = IntelHex::from_bin_reader(file, 0x1000).unwrap();
     ///
-    /// assert_eq!(ih.size, 239);
+    /// assert_eq!(ih.size, 51596);
+    /// assert_eq!(ih.get_min_addr(), Some(0x1000));
+    /// ```
+    pub fn from_bin_reader<R: Read>(
+        mut reader: R,
+        base_address: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut ih = Self::new();
+        ih.load_bin_data(data, base_address);
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with the provided binary data placed at the base address.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_bin_bytes(&[0x01, 0x02, 0x03], 0x1000);
+    ///
+    /// assert_eq!(ih.read_range(0x1000, 3), Some(vec![0x01, 0x02, 0x03]));
+    /// ```
+    pub fn load_bin_bytes(&mut self, data: &[u8], base_address: usize) {
+        self.load_bin_data(data.to_vec(), base_address);
+    }
+
+    /// Clear the instance and fill it with the binary data as one chunk.
+    fn load_bin_data(&mut self, data: Vec<u8>, base_address: usize) {
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = data.len();
+
+        // Load data bytes into the map as one chunk
+        self.buffer.insert(base_address, data);
+    }
+
+    /// Creates an `IntelHex` instance and fills it with data from the provided
+    /// Motorola S-record file (S19 / S28 / S37).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_srec("tests/fixtures/ih_valid_2.s19").unwrap();
+    /// assert_eq!(ih.read_byte(0x100), Some(0x21));
+    /// ```
+    pub fn from_srec<P: AsRef<Path>>(filepath: P) -> Result<Self, Box<dyn Error>> {
+        let mut ih = Self::new();
+        ih.load_srec(filepath)?;
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with data from the provided
+    /// Motorola S-record file (S19 / S28 / S37).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_srec("tests/fixtures/ih_valid_2.s19").unwrap();
+    ///
+    /// assert_eq!(ih.get_min_addr(), Some(0x100));
+    /// ```
+    pub fn load_srec<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        // Parse contents
+        self.parse_srec(&raw_bytes)?;
+
+        Ok(())
+    }
+
+    /// Creates an `IntelHex` instance and fills it with data from the loadable segments
+    /// (`PT_LOAD` program headers) of the provided ELF file. The entry point of the ELF file
+    /// becomes the start address (left unset if it lies above 4 GiB).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ElfAddress, IntelHex};
+    ///
+    /// let ih = IntelHex::from_elf("tests/fixtures/elf_valid_1.elf", ElfAddress::Physical).unwrap();
+    /// assert_eq!(ih.read_byte(0x100), Some(0x21));
+    /// ```
+    pub fn from_elf<P: AsRef<Path>>(
+        filepath: P,
+        addressing: ElfAddress,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut ih = Self::new();
+        ih.load_elf(filepath, addressing)?;
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with data from the loadable segments
+    /// (`PT_LOAD` program headers) of the provided ELF file. The entry point of the ELF file
+    /// becomes the start address (left unset if it lies above 4 GiB).
+    ///
+    /// Segments are placed either at their physical (LMA) or virtual (VMA) address.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ElfAddress, IntelHex};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_elf("tests/fixtures/elf_valid_1.elf", ElfAddress::Virtual).unwrap();
+    ///
+    /// assert_eq!(ih.get_min_addr(), Some(0x2000_0100));
+    /// ```
+    pub fn load_elf<P: AsRef<Path>>(
+        &mut self,
+        filepath: P,
+        addressing: ElfAddress,
+    ) -> Result<(), Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        // Parse contents
+        self.parse_elf(&raw_bytes, addressing)?;
+
+        Ok(())
+    }
+
+    /// Generates an Intel HEX file at the specified path.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.write_hex("build/ex1/ih.hex");
+    ///
+    /// assert_eq!(ih.size, 239);
     /// ```
     pub fn write_hex<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), Box<dyn Error>> {
-        // Ensure the parent directory exists
-        if let Some(parent) = filepath.as_ref().parent() {
-            std::fs::create_dir_all(parent)?;
+        self.write_hex_to(create_file_writer(filepath)?)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    /// Writes Intel HEX records into the provided writer.
+    ///
+    /// Records are written one by one, so wrap unbuffered writers (e.g. `File`) in `BufWriter`.
+    ///
+    /// # Errors
+    /// - Returns an error if the writer fails or a record cannot be created
+    /// - Returns `CreateRecordError` with `AddressOutOfRangeForFormat` if the data or the start
+    ///   address does not fit into the selected [`HexFormat`] (I8HEX cannot store a start address)
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// assert_eq!(out, std::fs::read("tests/fixtures/ih_valid_2.hex").unwrap());
+    /// ```
+    pub fn write_hex_to<W: Write>(&self, mut writer: W) -> Result<(), Box<dyn Error>> {
+        let format = self.format;
+        let unit_size = self.address_unit.bytes_per_address();
+        let buffer = buffer_to_file(self.address_unit, &self.buffer)?;
+
+        // Check the whole address range up front, so that nothing is written on error
+        if let Some((&addr, data)) = buffer.last_key_value() {
+            let max_addr = (addr + data.len().saturating_sub(1)) / unit_size;
+            if max_addr > format.max_address() {
+                return Err(Box::new(IntelHexError::CreateRecordError(
+                    IntelHexErrorKind::AddressOutOfRangeForFormat(format, max_addr),
+                )));
+            }
         }
+        let start_addr = self.start_addr_for_format()?;
 
-        let file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(filepath)?;
+        // Reproduce the original file if its layout was preserved during parsing
+        // (and its records are allowed in the selected format)
+        if let Some(layout) = &self.layout
+            && self.address_unit.is_byte()
+            && layout.fits_format(format)
+        {
+            return self.write_hex_layout_to(layout, &mut writer);
+        }
 
-        // Wrap in BufWriter for efficient line-by-line writing
-        let mut writer = std::io::BufWriter::new(file);
+        let options = self.write_options;
+        let eol = options.line_ending.as_str();
 
-        // Write start address record (raw bytes + newline)
-        if let Some(s) = self.start_addr {
-            writer.write_all(b":")?;
-            writer.write_all(&s)?;
-            writeln!(writer)?;
+        // Window of the last emitted extended address record.
+        // Optionally the record of the first data address is emitted up front,
+        // even if it is the first 64KB window.
+        let mut curr_high_addr = if options.extended_address_at_start
+            && format != HexFormat::I8Hex
+            && let Some((&min_addr, _)) = buffer.first_key_value()
+        {
+            let high_addr = ((min_addr / unit_size) >> 16) as u16;
+            let record = Self::create_extended_address_record(format, high_addr)?;
+            write_hex_record(&mut writer, &record, options.lowercase, eol)?;
+            Some(high_addr)
+        } else {
+            Some(0)
+        };
+
+        // Write start address record (Start Segment Address or Start Linear Address)
+        if let Some(start_addr) = start_addr {
+            let record = Record::create(0, start_addr.record_type(), &start_addr.record_data())?;
+            write_hex_record(&mut writer, &record, options.lowercase, eol)?;
         }
 
-        let mut curr_high_addr = 0u16;
+        self.write_data_records(
+            &mut writer,
+            &buffer,
+            &mut curr_high_addr,
+            options.lowercase,
+            eol,
+        )?;
 
-        for (&chunk_start_addr, data) in &self.buffer {
+        // Write EOF record (last line has no newline by default)
+        let record = Record::create(0, RecordType::EndOfFile, &[])?;
+        let eol = if options.trailing_newline { eol } else { "" };
+        write_hex_record(&mut writer, &record, options.lowercase, eol)?;
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    /// Write the data records of the buffer, preceded by an extended address record
+    /// whenever the 64KB window differs from `curr_high_addr` (`None` = unknown window).
+    /// Record addresses count in the address unit, records always hold whole words.
+    ///
+    /// # Errors
+    /// Returns an error if the writer fails or a record cannot be created.
+    ///
+    fn write_data_records<W: Write>(
+        &self,
+        writer: &mut W,
+        buffer: &BTreeMap<usize, Vec<u8>>,
+        curr_high_addr: &mut Option<u16>,
+        lowercase: bool,
+        eol: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let unit_size = self.address_unit.bytes_per_address();
+        let payload_size = (self.max_payload_size / unit_size * unit_size).max(unit_size);
+
+        for (&chunk_start_addr, data) in buffer {
             let mut chunk_offset = 0;
 
             // Iterate over data chunk
             while chunk_offset < data.len() {
                 let addr = chunk_start_addr + chunk_offset;
 
-                // Split address into low and high
-                let high_addr = (addr >> 16) as u16;
-                let low_addr = (addr & 0xFFFF) as u16;
-
-                // If ELA segment changed -> emit ELA record
-                if curr_high_addr != high_addr {
-                    let msb = (high_addr >> 8) as u8;
-                    let lsb = (high_addr & 0xFF) as u8;
-
-                    let record = Record::create(0, RecordType::ExtendedLinearAddress, &[msb, lsb])?;
+                // Split address (in address units) into low and high
+                let high_addr = ((addr / unit_size) >> 16) as u16;
+                let low_addr = ((addr / unit_size) & 0xFFFF) as u16;
 
-                    writeln!(writer, "{record}")?;
+                // If 64KB window changed -> emit ELA record (I32HEX) or ESA record (I16HEX).
+                // I8HEX has no extended address records.
+                if self.format != HexFormat::I8Hex && *curr_high_addr != Some(high_addr) {
+                    let record = Self::create_extended_address_record(self.format, high_addr)?;
+                    write_hex_record(writer, &record, lowercase, eol)?;
 
-                    curr_high_addr = high_addr;
+                    *curr_high_addr = Some(high_addr);
                 }
 
                 // Determine how many bytes can fit in this record
                 // - Can't exceed max_payload_size
                 // - Can't cross a 64KB boundary (must stay within current high_addr)
-                let remaining_in_segment = 0x10000 - low_addr as usize;
+                // - Can't cross a multiple of max_payload_size (if records are aligned)
+                let remaining_in_segment = (0x10000 - low_addr as usize) * unit_size;
+                let max_payload_size = if self.write_options.align_records {
+                    payload_size - addr % payload_size
+                } else {
+                    payload_size
+                };
                 let chunk_size = std::cmp::min(
-                    self.max_payload_size,
+                    max_payload_size,
                     std::cmp::min(data.len() - chunk_offset, remaining_in_segment),
                 );
 
@@ -417,16 +1287,107 @@ impl IntelHex {
                     RecordType::Data,
                     &data[chunk_offset..chunk_offset + chunk_size],
                 )?;
-                writeln!(writer, "{record}")?;
+                write_hex_record(writer, &record, lowercase, eol)?;
 
                 chunk_offset += chunk_size;
             }
         }
 
+        Ok(())
+    }
+
+    /// Write the records of the preserved layout of the parsed file.
+    /// Records whose data is unchanged are written verbatim, the others are regenerated
+    /// from the current data. Data not covered by any original record and a new start
+    /// address are written before the End Of File record.
+    ///
+    /// # Errors
+    /// Returns an error if the writer fails or a record cannot be created.
+    ///
+    fn write_hex_layout_to<W: Write>(
+        &self,
+        layout: &RecordLayout,
+        writer: &mut W,
+    ) -> Result<(), Box<dyn Error>> {
+        let eol = layout.line_ending.as_str();
+        let lowercase = layout.lowercase;
+
+        // Data which is not covered by the original data records
+        let mut rest = Self::new();
+        rest.buffer.clone_from(&self.buffer);
+
+        let new_start_addr = self.start_addr_for_format()?;
+        let mut start_addr_written = false;
+
+        for entry in &layout.records {
+            match entry {
+                LayoutRecord::Data {
+                    address,
+                    offset,
+                    len,
+                    line,
+                } => {
+                    let end = address + len;
+                    rest.erase_range(*address..end);
+
+                    // Regenerate the record for each part of its range which still holds data
+                    for (start, part_end, has_data) in self.partition_range(*address, end) {
+                        if !has_data {
+                            continue;
+                        }
+                        let data = self.read_range(start, part_end - start).unwrap_or_default();
+                        let low_addr = u16::try_from(start - offset).map_err(|_| {
+                            IntelHexError::CreateRecordError(IntelHexErrorKind::InvalidAddress(
+                                start,
+                            ))
+                        })?;
+                        let record = Record::create(low_addr, RecordType::Data, &data)?;
+
+                        // Unchanged record is kept as it was (including its letter case)
+                        if record.eq_ignore_ascii_case(line) {
+                            write!(writer, "{line}{eol}")?;
+                        } else {
+                            write_hex_record(writer, &record, lowercase, eol)?;
+                        }
+                    }
+                }
+                LayoutRecord::Verbatim { line, .. } => write!(writer, "{line}{eol}")?,
+                LayoutRecord::Start { start_addr, line } => {
+                    // Start address might have been changed or removed since parsing
+                    if start_addr_written {
+                        continue;
+                    }
+                    if new_start_addr == Some(*start_addr) {
+                        write!(writer, "{line}{eol}")?;
+                    } else if let Some(start_addr) = new_start_addr {
+                        let record =
+                            Record::create(0, start_addr.record_type(), &start_addr.record_data())?;
+                        write_hex_record(writer, &record, lowercase, eol)?;
+                    }
+                    start_addr_written = true;
+                }
+                LayoutRecord::EndOfFile { .. } => break,
+            }
+        }
+
+        // New data (always preceded by an extended address record, as the window is unknown)
+        self.write_data_records(writer, &rest.buffer, &mut None, lowercase, eol)?;
+
+        // New start address
+        if !start_addr_written && let Some(start_addr) = new_start_addr {
+            let record = Record::create(0, start_addr.record_type(), &start_addr.record_data())?;
+            write_hex_record(writer, &record, lowercase, eol)?;
+        }
+
         // Write EOF record
-        let record = Record::create(0, RecordType::EndOfFile, &[])?;
-        write!(writer, "{record}")?; // write last line (no newline)
+        let record = match layout.records.last() {
+            Some(LayoutRecord::EndOfFile { line }) => line.clone(),
+            _ => Record::create(0, RecordType::EndOfFile, &[])?,
+        };
+        let eol = if layout.trailing_newline { eol } else { "" };
+        write!(writer, "{record}{eol}")?;
 
+        writer.flush()?;
         Ok(())
     }
 
@@ -452,20 +1413,32 @@ impl IntelHex {
         filepath: P,
         gap_fill: u8,
     ) -> Result<(), Box<dyn Error>> {
-        // Ensure the parent directory exists
-        if let Some(parent) = filepath.as_ref().parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        let file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(filepath)?;
-
-        // Wrap in BufWriter for efficient line-by-line writing
-        let mut writer = std::io::BufWriter::new(file);
+        self.write_bin_to(create_file_writer(filepath)?, gap_fill)
+    }
 
+    /// Writes binary data into the provided writer.
+    /// Address gaps are filled with the provided `gap_fill` byte (usually 0x00 or 0xFF).
+    ///
+    /// # Errors
+    /// Returns an error if the writer fails.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_bin_to(&mut out, 0xFF).unwrap();
+    ///
+    /// assert_eq!(out.len(), 115264);
+    /// assert_eq!(out[4], 0xFF); // gap right after the first chunk
+    /// ```
+    pub fn write_bin_to<W: Write>(
+        &self,
+        mut writer: W,
+        gap_fill: u8,
+    ) -> Result<(), Box<dyn Error>> {
         // Get the starting point
         let mut current_addr = self.get_min_addr().unwrap_or(0);
 
@@ -473,18 +1446,7 @@ impl IntelHex {
         for (&chunk_start_addr, data) in &self.buffer {
             // Fill the gap between the last written byte and the start of this chunk
             if chunk_start_addr > current_addr {
-                let gap_size = chunk_start_addr - current_addr;
-
-                // Use a small buffer to write gaps. Limit the buffer to 4096 KB as it is the
-                // default / typical page size of most OS - more efficient + avoids large heap allocations.
-                let gap_buf = vec![gap_fill; std::cmp::min(gap_size, 4096)];
-
-                let mut remaining = gap_size;
-                while remaining > 0 {
-                    let to_write = std::cmp::min(remaining, gap_buf.len());
-                    writer.write_all(&gap_buf[..to_write])?;
-                    remaining -= to_write;
-                }
+                write_gap(&mut writer, chunk_start_addr - current_addr, gap_fill)?;
             }
 
             // Write the entire contiguous chunk at once
@@ -498,6 +1460,103 @@ impl IntelHex {
         Ok(())
     }
 
+    /// Generates a Motorola S-record file at the specified path.
+    ///
+    /// The record type is picked from the highest address in the data (and the entry point):
+    /// S1 / S9 for 16-bit, S2 / S8 for 24-bit and S3 / S7 for 32-bit addresses.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written or if the data does not fit
+    /// into 32-bit address space.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.write_srec("build/ex4/ih.s19").unwrap();
+    ///
+    /// let ih_srec = IntelHex::from_srec("build/ex4/ih.s19").unwrap();
+    /// assert_eq!(ih.bytes().collect::<Vec<_>>(), ih_srec.bytes().collect::<Vec<_>>());
+    /// ```
+    pub fn write_srec<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), Box<dyn Error>> {
+        self.write_srec_to(create_file_writer(filepath)?)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    /// Writes Motorola S-records into the provided writer.
+    /// See [`IntelHex::write_srec()`] for the choice of record types.
+    ///
+    /// # Errors
+    /// Returns an error if the writer fails or if the data does not fit
+    /// into 32-bit address space.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_srec_to(&mut out).unwrap();
+    ///
+    /// assert!(out.starts_with(b"S0030000FC\nS113010021"));
+    /// ```
+    pub fn write_srec_to<W: Write>(&self, mut writer: W) -> Result<(), Box<dyn Error>> {
+        // Pick the record types based on the highest address to be written
+        let entry_point = self.start_addr.map_or(0, StartAddress::linear);
+        let max_addr = self.get_max_addr().unwrap_or(0).max(entry_point as usize);
+        let (data_type, start_type) = if max_addr <= 0xFFFF {
+            (SRecordType::Data16, SRecordType::Start16)
+        } else if max_addr <= 0xFF_FFFF {
+            (SRecordType::Data24, SRecordType::Start24)
+        } else if u32::try_from(max_addr).is_ok() {
+            (SRecordType::Data32, SRecordType::Start32)
+        } else {
+            return Err(
+                IntelHexError::CreateRecordError(IntelHexErrorKind::InvalidAddress(max_addr))
+                    .into(),
+            );
+        };
+
+        // Write header record
+        let record = SRecord::create(SRecordType::Header, 0, &[])?;
+        writeln!(writer, "{record}")?;
+
+        // Byte count field covers address, data and checksum -> limit the payload accordingly
+        let payload_size = std::cmp::min(
+            self.max_payload_size,
+            u8::MAX as usize - data_type.address_len() - 1,
+        );
+
+        let mut data_records: usize = 0;
+
+        for (&chunk_start_addr, data) in &self.buffer {
+            for (i, payload) in data.chunks(payload_size).enumerate() {
+                let addr = chunk_start_addr + i * payload_size;
+                let record = SRecord::create(data_type, addr as u32, payload)?;
+                writeln!(writer, "{record}")?;
+                data_records += 1;
+            }
+        }
+
+        // Write record count (skipped if it does not fit into S6 record)
+        if data_records <= 0xFFFF {
+            let record = SRecord::create(SRecordType::Count16, data_records as u32, &[])?;
+            writeln!(writer, "{record}")?;
+        } else if data_records <= 0xFF_FFFF {
+            let record = SRecord::create(SRecordType::Count24, data_records as u32, &[])?;
+            writeln!(writer, "{record}")?;
+        }
+
+        // Write termination record
+        let record = SRecord::create(start_type, entry_point, &[])?;
+        write!(writer, "{record}")?; // write last line (no newline)
+
+        writer.flush()?;
+        Ok(())
+    }
+
     /// Get an iterator over (address, contiguous data chunk) pairs in the
     /// `BTreeMap<usize, Vec<u8>` buffer of the `IntelHex`.
     /// For a more convenient way to iterate over the data, see [`IntelHex::bytes()`].
@@ -597,25 +1656,137 @@ impl IntelHex {
             .map(|(key, data)| *key + data.len() - 1)
     }
 
-    /// Read byte from `IntelHex` at the provided address.
+    /// Iterator over the address ranges of contiguous data (segments) in ascending order.
     ///
     /// # Example
     /// ```
     /// use intelhexlib::IntelHex;
     ///
     /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
-    /// let byte: u8 = ih.read_byte(0x0).unwrap();
+    /// let segments: Vec<_> = ih.segments().collect();
     ///
-    /// assert_eq!(byte, 0xFA);
+    /// assert_eq!(segments, vec![0x0..0x4, 0x1C200..0x1C240]);
     /// ```
-    #[must_use]
-    pub fn read_byte(&self, address: usize) -> Option<u8> {
-        if let Some((&start_addr, data)) = self.buffer.range(..=address).next_back()
-            && address < start_addr + data.len()
-        {
-            return Some(data[address - start_addr]);
-        }
-        None
+    pub fn segments(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.buffer
+            .iter()
+            .map(|(&addr, data)| addr..addr + data.len())
+    }
+
+    /// Iterator over the address ranges without data between the segments in ascending order.
+    /// Space below the first and above the last segment is not reported.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// let gaps: Vec<_> = ih.gaps().collect();
+    ///
+    /// assert_eq!(gaps, vec![0x4..0x1C200]);
+    /// ```
+    pub fn gaps(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.segments()
+            .zip(self.segments().skip(1))
+            .map(|(prev, next)| prev.end..next.start)
+    }
+
+    /// Get the address range of the segment holding data at the provided address.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// assert_eq!(ih.segment_containing(0x1C210), Some(0x1C200..0x1C240));
+    /// assert_eq!(ih.segment_containing(0x1C240), None);
+    /// ```
+    #[must_use]
+    pub fn segment_containing(&self, address: usize) -> Option<Range<usize>> {
+        self.buffer
+            .range(..=address)
+            .next_back()
+            .map(|(&addr, data)| addr..addr + data.len())
+            .filter(|range| range.contains(&address))
+    }
+
+    /// Get the lowest address above the provided one that holds data.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// assert_eq!(ih.next_data_addr(0x2), Some(0x3));
+    /// assert_eq!(ih.next_data_addr(0x3), Some(0x1C200));
+    /// assert_eq!(ih.next_data_addr(0x1C23F), None);
+    /// ```
+    #[must_use]
+    pub fn next_data_addr(&self, address: usize) -> Option<usize> {
+        let address = address.checked_add(1)?;
+        if self.segment_containing(address).is_some() {
+            return Some(address);
+        }
+        self.buffer.range(address..).next().map(|(&addr, _)| addr)
+    }
+
+    /// Get the highest address below the provided one that holds data.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// assert_eq!(ih.prev_data_addr(0x1C201), Some(0x1C200));
+    /// assert_eq!(ih.prev_data_addr(0x1C200), Some(0x3));
+    /// assert_eq!(ih.prev_data_addr(0x0), None);
+    /// ```
+    #[must_use]
+    pub fn prev_data_addr(&self, address: usize) -> Option<usize> {
+        let address = address.checked_sub(1)?;
+        self.buffer
+            .range(..=address)
+            .next_back()
+            .map(|(&addr, data)| address.min(addr + data.len() - 1))
+    }
+
+    /// Get the number of bytes holding data (without the gaps).
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// assert_eq!(ih.total_data_len(), 68);
+    /// ```
+    #[must_use]
+    pub fn total_data_len(&self) -> usize {
+        self.buffer.values().map(Vec::len).sum()
+    }
+
+    /// Read byte from `IntelHex` at the provided address.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// let byte: u8 = ih.read_byte(0x0).unwrap();
+    ///
+    /// assert_eq!(byte, 0xFA);
+    /// ```
+    #[must_use]
+    pub fn read_byte(&self, address: usize) -> Option<u8> {
+        if let Some((&start_addr, data)) = self.buffer.range(..=address).next_back()
+            && address < start_addr + data.len()
+        {
+            return Some(data[address - start_addr]);
+        }
+        None
     }
 
     /// Read an array of bytes from `IntelHex` at provided addresses.
@@ -632,6 +1803,25 @@ impl IntelHex {
     /// ```
     #[must_use]
     pub fn read_range(&self, start_addr: usize, len: usize) -> Option<Vec<u8>> {
+        self.read_slice(start_addr, len).map(<[u8]>::to_vec)
+    }
+
+    /// Borrow a slice of bytes from `IntelHex` at provided addresses (no copy is made).
+    /// Returns `None` if any of the addresses are invalid.
+    ///
+    /// Data is stored in contiguous chunks, so any range without gaps can be borrowed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// assert_eq!(ih.read_slice(0x0, 3), Some([0xFA, 0x00, 0x00].as_slice()));
+    /// assert_eq!(ih.read_slice(0x0, 5), None);
+    /// ```
+    #[must_use]
+    pub fn read_slice(&self, start_addr: usize, len: usize) -> Option<&[u8]> {
         // Find the chunk that might contain the start_addr
         if let Some((&chunk_start, data)) = self.buffer.range(..=start_addr).next_back() {
             let end_addr = start_addr + len;
@@ -640,7 +1830,7 @@ impl IntelHex {
             // Check if the entire requested range is within this chunk
             if start_addr >= chunk_start && end_addr <= chunk_end {
                 let chunk_offset = start_addr - chunk_start;
-                return Some(data[chunk_offset..chunk_offset + len].to_vec());
+                return Some(&data[chunk_offset..chunk_offset + len]);
             }
         }
 
@@ -648,6 +1838,121 @@ impl IntelHex {
         None
     }
 
+    /// Iterate over the data and gaps of the address range, in ascending order.
+    /// Data is yielded as slices borrowed from the internal buffer (no copy is made),
+    /// gaps as address ranges. Together, the pieces cover the whole address range.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Chunk, IntelHex};
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// let mut chunks = ih.chunks_in(0x2..0x1C202);
+    ///
+    /// assert_eq!(chunks.next(), Some(Chunk::Data(0x2, &[0x00, 0x02])));
+    /// assert_eq!(chunks.next(), Some(Chunk::Gap(0x4..0x1C200)));
+    /// assert_eq!(chunks.next(), Some(Chunk::Data(0x1C200, &[0xE0, 0xA5])));
+    /// assert_eq!(chunks.next(), None);
+    /// ```
+    #[must_use]
+    pub fn chunks_in(&self, range: Range<usize>) -> ChunksIn<'_> {
+        let first = self.first_chunk_in(range.start);
+        ChunksIn::new(self.buffer.range(first..range.end.max(first)), range)
+    }
+
+    /// Iterate over the data and gaps of the address range, in ascending order.
+    /// Same as [`IntelHex::chunks_in()`], but the data slices can be modified in place.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ChunkMut, IntelHex};
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// // Invert all data bytes of the range
+    /// for chunk in ih.chunks_in_mut(0x0..0x1C200) {
+    ///     if let ChunkMut::Data(_, data) = chunk {
+    ///         data.iter_mut().for_each(|b| *b = !*b);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(ih.read_slice(0x0, 4), Some([0x05, 0xFF, 0xFF, 0xFD].as_slice()));
+    /// ```
+    pub fn chunks_in_mut(&mut self, range: Range<usize>) -> ChunksInMut<'_> {
+        let first = self.first_chunk_in(range.start);
+        ChunksInMut::new(self.buffer.range_mut(first..range.end.max(first)), range)
+    }
+
+    /// Find the address of the first match of the pattern.
+    /// By default a match has to lie within a single contiguous segment,
+    /// see [`Pattern::across_gaps()`] to let matches span the gaps.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, Pattern};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x100, &[0x00, 0xDE, 0x12, 0xBE, 0xEF]).unwrap();
+    ///
+    /// let pattern: Pattern = "DE ?? BE EF".parse().unwrap();
+    ///
+    /// assert_eq!(ih.find(&pattern), Some(0x101));
+    /// ```
+    #[must_use]
+    pub fn find(&self, pattern: &Pattern) -> Option<usize> {
+        self.find_iter(pattern).next()
+    }
+
+    /// Find the addresses of all matches of the pattern, in ascending order.
+    /// Overlapping matches are included.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, Pattern};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x0, &[0xAA, 0xAA, 0xAA]).unwrap();
+    /// ih.write_range(0x10, &[0xAA, 0x5A]).unwrap();
+    ///
+    /// let pattern = Pattern::with_mask(&[0xAA, 0x0A], &[0xFF, 0x0F]).unwrap();
+    ///
+    /// assert_eq!(ih.find_all(&pattern), vec![0x0, 0x1, 0x10]);
+    /// ```
+    #[must_use]
+    pub fn find_all(&self, pattern: &Pattern) -> Vec<usize> {
+        self.find_iter(pattern).collect()
+    }
+
+    /// Iterate lazily over the addresses of all matches of the pattern, in ascending order.
+    /// The data chunks are searched in place, without copying the image.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, Pattern};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x0, &[0xFF; 8]).unwrap();
+    ///
+    /// let pattern = Pattern::new(&[0xFF, 0xFF]);
+    /// let first_two: Vec<usize> = ih.find_iter(&pattern).take(2).collect();
+    ///
+    /// assert_eq!(first_two, vec![0x0, 0x1]);
+    /// ```
+    #[must_use]
+    pub fn find_iter<'a>(&'a self, pattern: &'a Pattern) -> FindIter<'a> {
+        FindIter::new(self.buffer.iter(), pattern)
+    }
+
+    /// Start address of the first chunk which may hold data at or above `addr`
+    /// (the chunk starting before `addr` may still cover it).
+    ///
+    fn first_chunk_in(&self, addr: usize) -> usize {
+        self.buffer
+            .range(..=addr)
+            .next_back()
+            .map_or(addr, |(&chunk_start, _)| chunk_start)
+    }
+
     /// Read a range of bytes, returning a `Vec<Option<u8>>`.
     /// Each element is `Some(byte)` if data exists at that address, or `None` if it is a gap.
     ///
@@ -785,6 +2090,7 @@ impl IntelHex {
     ///
     /// This operation is atomic: if the range spans across a gap in the
     /// sparse buffer, an error is returned and no data is modified.
+    /// To write into gaps, see [`IntelHex::write_range()`].
     ///
     /// # Errors
     /// Returns `InvalidAddress` if any part of the range is not defined.
@@ -821,469 +2127,2469 @@ impl IntelHex {
         ))
     }
 
-    /// Update the max payload size (number of bytes) per record when writing `IntelHex` file.
-    /// Default = 16.
+    /// Writes a contiguous range of bytes starting at `start_addr`.
+    ///
+    /// Unlike [`IntelHex::update_range()`], the range does not have to be backed by data:
+    /// existing bytes are overwritten and gaps are filled, creating new data chunks or
+    /// extending (and joining) the existing ones.
     ///
     /// # Errors
-    /// Returns an error if the provided payload size is 0.
+    /// Returns `InvalidAddress` if the range exceeds 32-bit address space.
     ///
     /// # Example
     /// ```
     /// use intelhexlib::IntelHex;
     ///
     /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
-    /// ih.set_max_payload_size(0xFF);      // set to u8 max
-    /// ih.write_hex("build/ex2/ih.hex");   // now data records can have up to 255 bytes of payload
     ///
-    /// // Due to larger payload size, there are fewer records in the hex file.
-    /// // Hence, the size is smaller (originally = 239 bytes).
-    /// assert_eq!(std::fs::metadata("build/ex2/ih.hex").unwrap().len(), 187);
+    /// // Version stamp in an empty region
+    /// ih.write_range(0x8000, b"v1.2.3").unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x8000, 6), Some(b"v1.2.3".to_vec()));
     /// ```
-    pub const fn set_max_payload_size(&mut self, size: u8) -> Result<(), IntelHexError> {
-        if size == 0 {
-            return Err(IntelHexError::UpdateError(
-                IntelHexErrorKind::RecordInvalidPayloadLength,
-            ));
-        }
-        self.max_payload_size = size as usize;
+    pub fn write_range(&mut self, start_addr: usize, data: &[u8]) -> Result<(), IntelHexError> {
+        check_range_fits(start_addr, data.len())?;
+        self.write_chunk(start_addr, data, true);
         Ok(())
     }
 
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_possible_wrap,
-        clippy::cast_sign_loss
-    )]
-    /// Relocate the entire data buffer to a new starting address.
+    /// Inserts a contiguous range of bytes starting at `start_addr` into a gap of the buffer.
+    /// The bytes create a new data chunk or extend (and join) the neighbouring ones.
+    ///
+    /// This operation is atomic: if any part of the range already holds data,
+    /// an error is returned and no data is modified.
     ///
     /// # Errors
-    /// Returns an error if the new starting address is out of bounds or
-    /// if the `IntelHex` instance has no data.
+    /// - Returns `RecordAddressOverlap` if any part of the range is already defined
+    /// - Returns `InvalidAddress` if the range exceeds 32-bit address space
     ///
     /// # Example
     /// ```
     /// use intelhexlib::IntelHex;
     ///
     /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
-    /// ih.relocate(0x1234);
     ///
-    /// assert_eq!(ih.read_byte(0x0000), None);
-    /// assert_eq!(ih.read_byte(0x1234), Some(0xFA));
+    /// // Grow the first data chunk (0x0000..0x0004)
+    /// assert!(ih.insert_bytes(0x0004, &[0xAA, 0xBB]).is_ok());
+    /// assert_eq!(ih.iter().next(), Some((&0, &vec![0xFA, 0x00, 0x00, 0x02, 0xAA, 0xBB])));
+    ///
+    /// // Data is not overwritten
+    /// assert!(ih.insert_bytes(0x0000, &[0x00]).is_err());
     /// ```
-    pub fn relocate(&mut self, new_start_address: usize) -> Result<(), IntelHexError> {
-        let (min_addr, max_addr) =
-            self.get_min_addr()
-                .zip(self.get_max_addr())
-                .ok_or(IntelHexError::UpdateError(
-                    IntelHexErrorKind::IntelHexInstanceEmpty,
-                ))?;
+    pub fn insert_bytes(&mut self, start_addr: usize, data: &[u8]) -> Result<(), IntelHexError> {
+        check_range_fits(start_addr, data.len())?;
 
-        // Cap start address to u32::MAX (to not break API for now)
-        if new_start_address > u32::MAX as usize {
+        if let Some(&(start, _, _)) = self
+            .partition_range(start_addr, start_addr + data.len())
+            .iter()
+            .find(|(_, _, has_data)| *has_data)
+        {
             return Err(IntelHexError::UpdateError(
-                IntelHexErrorKind::InvalidAddress(new_start_address),
+                IntelHexErrorKind::RecordAddressOverlap(start),
             ));
         }
 
-        let offset = new_start_address as i64 - min_addr as i64;
+        self.write_chunk(start_addr, data, false);
+        Ok(())
+    }
 
-        if max_addr as i64 + offset > i64::from(u32::MAX) {
-            let max_allowed_start_address = u32::MAX - max_addr as u32 + min_addr as u32;
-            return Err(IntelHexError::UpdateError(
-                IntelHexErrorKind::RelocateAddressOverflow(max_allowed_start_address as usize),
-            ));
+    /// Erases the data in the address range, splitting the data chunks when needed.
+    /// Addresses in the range without data are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.erase_range(0x0001..0x0003);
+    ///
+    /// assert_eq!(ih.read_byte(0x0000), Some(0xFA));
+    /// assert_eq!(ih.read_byte(0x0001), None);
+    /// assert_eq!(ih.read_byte(0x0003), Some(0x02));
+    /// ```
+    pub fn erase_range(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
         }
 
-        self.buffer = std::mem::take(&mut self.buffer)
-            .into_iter()
-            .map(|(addr, data)| ((addr as i64 + offset) as usize, data))
-            .collect();
+        self.split_chunk_at(range.start);
+        self.split_chunk_at(range.end);
 
-        Ok(())
+        // Detach everything from the range start and re-attach what lies after the range
+        let mut erased = self.buffer.split_off(&range.start);
+        let mut after = erased.split_off(&range.end);
+        self.buffer.append(&mut after);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_set_max_payload_size_valid() {
-        // Arrange
-        let mut ih = IntelHex::new();
-        let new_payload_length = 2;
+    /// Keeps only the data in the address range, erasing everything outside of it.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.crop(0x0110..0x0120);
+    ///
+    /// assert_eq!(ih.get_min_addr(), Some(0x0110));
+    /// assert_eq!(ih.get_max_addr(), Some(0x011F));
+    /// ```
+    pub fn crop(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            self.buffer.clear();
+            return;
+        }
 
-        // Act
-        let res = ih.set_max_payload_size(new_payload_length);
+        self.split_chunk_at(range.start);
+        self.split_chunk_at(range.end);
 
-        // Assert
-        assert!(res.is_ok());
-        assert_eq!(ih.max_payload_size, new_payload_length as usize);
+        let mut kept = self.buffer.split_off(&range.start);
+        let _ = kept.split_off(&range.end);
+        self.buffer = kept;
     }
 
-    #[test]
-    fn test_set_max_payload_size_invalid() {
-        // Arrange
-        let mut ih = IntelHex::new();
-        let new_payload_length = 0;
-        let default_payload_length = ih.max_payload_size;
+    /// Extracts the data in the address range as a new `IntelHex` instance.
+    /// The new instance keeps the payload size setting, but not the start address.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// let calibration = ih.slice(0x0130..0x0200);
+    ///
+    /// assert_eq!(calibration.get_min_addr(), Some(0x0130));
+    /// assert_eq!(calibration.bytes().count(), 16);
+    /// // Original instance is left untouched
+    /// assert_eq!(ih.get_min_addr(), Some(0x0100));
+    /// ```
+    #[must_use]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        let mut ih = Self::new();
+        ih.max_payload_size = self.max_payload_size;
+        ih.format = self.format;
+        ih.write_options = self.write_options;
+        ih.address_unit = self.address_unit;
 
-        // Act
-        let res = ih.set_max_payload_size(new_payload_length);
+        for (start, end, has_data) in self.partition_range(range.start, range.end) {
+            if !has_data {
+                continue;
+            }
+            if let Some((&chunk_start, chunk)) = self.buffer.range(..=start).next_back() {
+                ih.buffer.insert(
+                    start,
+                    chunk[start - chunk_start..end - chunk_start].to_vec(),
+                );
+            }
+        }
+
+        ih
+    }
+
+    /// Fills the addresses without data in the range with the repeated `pattern`.
+    /// The pattern is anchored at the range start, i.e., the byte written at address `a`
+    /// is `pattern[(a - range.start) % pattern.len()]`. Existing data is left untouched.
+    ///
+    /// # Errors
+    /// - Returns `EmptyFillPattern` if the pattern is empty
+    /// - Returns `InvalidAddress` if the range exceeds 32-bit address space
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.fill(0x0000..0x0008, &[0xDE, 0xAD]).unwrap();
+    ///
+    /// // First 4 bytes hold data already
+    /// assert_eq!(
+    ///     ih.read_range(0x0000, 8),
+    ///     Some(vec![0xFA, 0x00, 0x00, 0x02, 0xDE, 0xAD, 0xDE, 0xAD])
+    /// );
+    /// ```
+    pub fn fill(&mut self, range: Range<usize>, pattern: &[u8]) -> Result<(), IntelHexError> {
+        if pattern.is_empty() {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::EmptyFillPattern,
+            ));
+        }
+        check_range_fits(range.start, range.len())?;
+
+        for (start, end, has_data) in self.partition_range(range.start, range.end) {
+            if has_data {
+                continue;
+            }
+            let data = (start..end)
+                .map(|addr| pattern[(addr - range.start) % pattern.len()])
+                .collect();
+            self.insert_chunk(start, data)
+                .map_err(IntelHexError::UpdateError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pads every data segment with the `fill` byte, so that it starts and ends
+    /// at a multiple of `align` (e.g. flash page size). Segments sharing a page are joined.
+    ///
+    /// # Errors
+    /// - Returns `InvalidAlignment` if the alignment is zero
+    /// - Returns `InvalidAddress` if the padded segment exceeds 32-bit address space
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.pad_to_alignment(0x100, 0xFF).unwrap();
+    ///
+    /// assert!(ih.iter().all(|(addr, data)| addr % 0x100 == 0 && data.len() % 0x100 == 0));
+    /// assert_eq!(ih.read_byte(0x00FF), Some(0xFF));
+    /// ```
+    pub fn pad_to_alignment(&mut self, align: usize, fill: u8) -> Result<(), IntelHexError> {
+        if align == 0 {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAlignment(align),
+            ));
+        }
+
+        let segments: Vec<(usize, usize)> = self
+            .buffer
+            .iter()
+            .map(|(&start, data)| (start, start + data.len()))
+            .collect();
+
+        // Check all segments first, so that no data is modified on error
+        let mut padded = Vec::with_capacity(segments.len());
+        for (start, end) in segments {
+            let aligned_start = start - start % align;
+            let aligned_end = end
+                .checked_next_multiple_of(align)
+                .filter(|&aligned_end| {
+                    check_range_fits(aligned_start, aligned_end - aligned_start).is_ok()
+                })
+                .ok_or(IntelHexError::UpdateError(
+                    IntelHexErrorKind::InvalidAddress(start),
+                ))?;
+            padded.push(aligned_start..aligned_end);
+        }
+
+        for range in padded {
+            self.fill(range, &[fill])?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the max payload size (number of bytes) per record when writing `IntelHex` file.
+    /// Default = 16.
+    ///
+    /// # Errors
+    /// Returns an error if the provided payload size is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.set_max_payload_size(0xFF);      // set to u8 max
+    /// ih.write_hex("build/ex2/ih.hex");   // now data records can have up to 255 bytes of payload
+    ///
+    /// // Due to larger payload size, there are fewer records in the hex file.
+    /// // Hence, the size is smaller (originally = 239 bytes).
+    /// assert_eq!(std::fs::metadata("build/ex2/ih.hex").unwrap().len(), 187);
+    /// ```
+    pub const fn set_max_payload_size(&mut self, size: u8) -> Result<(), IntelHexError> {
+        if size == 0 {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RecordInvalidPayloadLength,
+            ));
+        }
+        self.max_payload_size = size as usize;
+        Ok(())
+    }
+
+    /// Returns the maximum payload size of written data records (16 by default).
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.set_max_payload_size(32).unwrap();
+    ///
+    /// assert_eq!(ih.max_payload_size(), 32);
+    /// ```
+    #[must_use]
+    pub const fn max_payload_size(&self) -> usize {
+        self.max_payload_size
+    }
+
+    /// Select the Intel HEX flavour used when writing `IntelHex` file.
+    /// Default = [`HexFormat::I32Hex`].
+    ///
+    /// - I8HEX writes neither extended address nor start address records,
+    ///   hence all data has to be below 64 KiB and no start address may be set.
+    /// - I16HEX addresses data via Extended Segment Address records (up to 1 MiB),
+    ///   a linear start address is written as a Start Segment Address record.
+    /// - I32HEX addresses data via Extended Linear Address records.
+    ///
+    /// Writing fails if the data does not fit into the address range of the format.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{HexFormat, IntelHex};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x1_FFFF, &[0xAA, 0xBB]).unwrap();
+    /// ih.set_hex_format(HexFormat::I16Hex);
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     ":020000021000EC\n:01FFFF00AA57\n:020000022000DC\n:01000000BB44\n:00000001FF"
+    /// );
+    /// ```
+    pub const fn set_hex_format(&mut self, format: HexFormat) {
+        self.format = format;
+    }
+
+    /// Returns the Intel HEX flavour used when writing `IntelHex` file.
+    ///
+    #[must_use]
+    pub const fn hex_format(&self) -> HexFormat {
+        self.format
+    }
+
+    /// Update the unit of the addresses used when writing `IntelHex` file.
+    /// See [`AddressUnit`]. Loading a hex file sets the unit of its parse options.
+    ///
+    /// Writing word-addressed files fails if a data segment does not cover whole words.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{AddressUnit, IntelHex};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x0, &[0x11, 0x22, 0x33, 0x44, 0x55, 0x66]).unwrap();
+    /// ih.set_address_unit(AddressUnit::Pic24);
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// // Phantom byte follows each 24-bit word
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     ":08000000112233004455660093\n:00000001FF"
+    /// );
+    /// ```
+    pub const fn set_address_unit(&mut self, unit: AddressUnit) {
+        self.address_unit = unit;
+    }
+
+    /// Returns the unit of the addresses used when writing `IntelHex` file.
+    ///
+    #[must_use]
+    pub const fn address_unit(&self) -> AddressUnit {
+        self.address_unit
+    }
+
+    /// Update the textual layout (line endings, case, record alignment, etc.)
+    /// used when writing `IntelHex` file. See [`HexWriteOptions`].
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{HexWriteOptions, IntelHex};
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.set_hex_write_options(HexWriteOptions {
+    ///     trailing_newline: true,
+    ///     ..HexWriteOptions::default()
+    /// });
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// assert!(out.ends_with(b":00000001FF\n"));
+    /// ```
+    pub const fn set_hex_write_options(&mut self, options: HexWriteOptions) {
+        self.write_options = options;
+    }
+
+    /// Returns the textual layout used when writing `IntelHex` file.
+    ///
+    #[must_use]
+    pub const fn hex_write_options(&self) -> HexWriteOptions {
+        self.write_options
+    }
+
+    /// Returns `true` if the record layout of the parsed hex file is preserved and
+    /// reproduced by [`IntelHex::write_hex()`]. See [`ParseOptions::preserve_layout`].
+    ///
+    #[must_use]
+    pub const fn has_record_layout(&self) -> bool {
+        self.layout.is_some()
+    }
+
+    /// Forgets the preserved record layout of the parsed hex file,
+    /// so that the records are generated from scratch when writing.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, ParseOptions};
+    ///
+    /// let options = ParseOptions::strict().with_preserved_layout();
+    /// let mut ih = IntelHex::from_hex_with_options("tests/fixtures/ih_valid_1.hex", options)
+    ///     .unwrap()
+    ///     .0;
+    /// assert!(ih.has_record_layout());
+    ///
+    /// ih.discard_record_layout();
+    /// assert!(!ih.has_record_layout());
+    /// ```
+    pub fn discard_record_layout(&mut self) {
+        self.layout = None;
+    }
+
+    /// Create the extended address record of the 64KB window `high_addr` for the hex format:
+    /// Extended Segment Address for I16HEX, Extended Linear Address otherwise.
+    ///
+    fn create_extended_address_record(
+        format: HexFormat,
+        high_addr: u16,
+    ) -> Result<String, IntelHexError> {
+        let (rtype, base) = match format {
+            HexFormat::I16Hex => (RecordType::ExtendedSegmentAddress, high_addr << 12),
+            _ => (RecordType::ExtendedLinearAddress, high_addr),
+        };
+        Record::create(0, rtype, &base.to_be_bytes())
+    }
+
+    /// Start address as written in the selected hex format.
+    ///
+    /// # Errors
+    /// - Returns an error if a start address is set, as I8HEX has no start address records
+    /// - Returns an error if a linear start address does not fit into I16HEX segment addressing
+    ///
+    const fn start_addr_for_format(&self) -> Result<Option<StartAddress>, IntelHexError> {
+        match (self.format, self.start_addr) {
+            (HexFormat::I8Hex, Some(start_addr)) => Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::AddressOutOfRangeForFormat(
+                    HexFormat::I8Hex,
+                    start_addr.linear() as usize,
+                ),
+            )),
+            (HexFormat::I16Hex, Some(StartAddress::Linear(addr))) => {
+                let addr = addr as usize;
+                if addr > HexFormat::I16Hex.max_address() {
+                    return Err(IntelHexError::CreateRecordError(
+                        IntelHexErrorKind::AddressOutOfRangeForFormat(HexFormat::I16Hex, addr),
+                    ));
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                Ok(Some(StartAddress::Segment {
+                    cs: ((addr >> 4) & 0xF000) as u16,
+                    ip: (addr & 0xFFFF) as u16,
+                }))
+            }
+            (_, start_addr) => Ok(start_addr),
+        }
+    }
+
+    /// Returns the start address (entry point) of the image, if any.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{ElfAddress, IntelHex, StartAddress};
+    ///
+    /// let ih = IntelHex::from_elf("tests/fixtures/elf_valid_1.elf", ElfAddress::Physical).unwrap();
+    ///
+    /// assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x0100)));
+    /// ```
+    #[must_use]
+    pub const fn start_address(&self) -> Option<StartAddress> {
+        self.start_addr
+    }
+
+    /// Sets the start address (entry point) of the image or removes it (with `None`).
+    /// When writing a hex file, it is stored as Start Segment Address (03)
+    /// or Start Linear Address (05) record.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, StartAddress};
+    ///
+    /// let mut ih = IntelHex::parse_bytes(b":0400100000010203E6\n:00000001FF").unwrap();
+    /// ih.set_start_address(Some(StartAddress::Linear(0x0000_0010)));
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// assert!(out.starts_with(b":0400000500000010E7"));
+    /// ```
+    pub const fn set_start_address(&mut self, start_addr: Option<StartAddress>) {
+        self.start_addr = start_addr;
+    }
+
+    /// Relocate the entire data buffer to a new starting address.
+    /// The start address (entry point) is left unchanged,
+    /// see [`IntelHex::relocate_with_start_address()`].
+    ///
+    /// # Errors
+    /// Returns an error if the new starting address is out of bounds or
+    /// if the `IntelHex` instance has no data.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.relocate(0x1234);
+    ///
+    /// assert_eq!(ih.read_byte(0x0000), None);
+    /// assert_eq!(ih.read_byte(0x1234), Some(0xFA));
+    /// ```
+    pub fn relocate(&mut self, new_start_address: usize) -> Result<(), IntelHexError> {
+        let offset = self.relocate_offset(new_start_address)?;
+        self.shift_buffer(offset);
+        Ok(())
+    }
+
+    /// Relocate the entire data buffer to a new starting address and move the start address
+    /// (entry point) by the same offset, so that it keeps pointing to the same code.
+    ///
+    /// A segment start address keeps its IP if possible, otherwise it is normalized
+    /// (IP below 16).
+    ///
+    /// # Errors
+    /// Returns an error if the new starting address (or the moved start address)
+    /// is out of bounds or if the `IntelHex` instance has no data.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, StartAddress};
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// ih.set_start_address(Some(StartAddress::Linear(0x0002)));
+    /// ih.relocate_with_start_address(0x1234);
+    ///
+    /// assert_eq!(ih.read_byte(0x1234), Some(0xFA));
+    /// assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x1236)));
+    /// ```
+    pub fn relocate_with_start_address(
+        &mut self,
+        new_start_address: usize,
+    ) -> Result<(), IntelHexError> {
+        let offset = self.relocate_offset(new_start_address)?;
+
+        // Compute the new start address before modifying anything
+        let start_addr = self
+            .start_addr
+            .map(|start_addr| shift_start_address(start_addr, offset))
+            .transpose()?;
+
+        self.shift_buffer(offset);
+        self.start_addr = start_addr;
+        Ok(())
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    /// Offset moving the lowest data address to the new starting address.
+    ///
+    /// # Errors
+    /// Returns an error if the new starting address is out of bounds or
+    /// if the `IntelHex` instance has no data.
+    ///
+    fn relocate_offset(&self, new_start_address: usize) -> Result<i64, IntelHexError> {
+        let (min_addr, max_addr) =
+            self.get_min_addr()
+                .zip(self.get_max_addr())
+                .ok_or(IntelHexError::UpdateError(
+                    IntelHexErrorKind::IntelHexInstanceEmpty,
+                ))?;
+
+        // Cap start address to u32::MAX (to not break API for now)
+        if new_start_address > u32::MAX as usize {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(new_start_address),
+            ));
+        }
+
+        let offset = new_start_address as i64 - min_addr as i64;
+
+        if max_addr as i64 + offset > i64::from(u32::MAX) {
+            let max_allowed_start_address = u32::MAX - max_addr as u32 + min_addr as u32;
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RelocateAddressOverflow(max_allowed_start_address as usize),
+            ));
+        }
+
+        Ok(offset)
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    /// Move all chunks of the buffer by the offset (checked by `relocate_offset`).
+    /// The preserved record layout no longer matches the data and is discarded.
+    fn shift_buffer(&mut self, offset: i64) {
+        self.layout = None;
+        self.buffer = std::mem::take(&mut self.buffer)
+            .into_iter()
+            .map(|(addr, data)| ((addr as i64 + offset) as usize, data))
+            .collect();
+    }
+
+    /// Merge the data of another `IntelHex` instance into this one.
+    /// Adjacent data is coalesced into contiguous chunks, and overlaps
+    /// (of data as well as of the start address) are resolved by the `policy`.
+    ///
+    /// # Errors
+    /// Returns an error if the instances overlap and the policy does not allow it.
+    /// In that case the instance is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, OverlapPolicy};
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    /// let app = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// ih.merge(&app, OverlapPolicy::Error).unwrap();
+    ///
+    /// assert_eq!(ih.read_byte(0x0000), Some(0xFA));
+    /// assert_eq!(ih.read_byte(0x0100), Some(0x21));
+    /// ```
+    pub fn merge(&mut self, other: &Self, policy: OverlapPolicy) -> Result<(), IntelHexError> {
+        // Resolve start address first (nothing is modified yet)
+        let start_addr = merge_start(self.start_addr, other.start_addr, policy)?;
+
+        // Check the overlapping data against the policy
+        if matches!(
+            policy,
+            OverlapPolicy::Error | OverlapPolicy::ErrorIfDifferent
+        ) {
+            for (&addr, data) in &other.buffer {
+                for (start, end, has_data) in self.partition_range(addr, addr + data.len()) {
+                    if !has_data {
+                        continue;
+                    }
+                    if policy == OverlapPolicy::Error {
+                        return Err(IntelHexError::UpdateError(
+                            IntelHexErrorKind::RecordAddressOverlap(start),
+                        ));
+                    }
+                    if let Some(offset) =
+                        (start..end).position(|a| self.read_byte(a) != Some(data[a - addr]))
+                    {
+                        return Err(IntelHexError::UpdateError(
+                            IntelHexErrorKind::MergeDataConflict(start + offset),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Fill in the data
+        for (&addr, data) in &other.buffer {
+            self.write_chunk(addr, data, policy == OverlapPolicy::Overwrite);
+        }
+
+        self.start_addr = start_addr;
+
+        Ok(())
+    }
+
+    /// Compare the data of this `IntelHex` instance with another one.
+    /// Returns address ranges (in ascending order) with differing content or present
+    /// only in one of the instances, followed by the start address difference (if any).
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{DiffRange, IntelHex};
+    ///
+    /// let golden = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// let mut built = golden.clone();
+    /// built.update_range(0x0104, &[0xAA, 0xBB]).unwrap();
+    ///
+    /// let diff = built.diff(&golden);
+    ///
+    /// assert_eq!(diff, vec![DiffRange::Changed(0x0104..0x0106)]);
+    /// assert_eq!(diff[0].to_string(), "0x00000104..0x00000106 (2 bytes): content differs");
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<DiffRange> {
+        let mut diff = diff_buffers(&self.buffer, &other.buffer);
+
+        if self.start_addr != other.start_addr {
+            diff.push(DiffRange::StartAddress);
+        }
+
+        diff
+    }
+
+    /// Computes the checksum over the address range.
+    /// Addresses without data are treated as holding the `gap_fill` byte.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Checksum, Crc, IntelHex};
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_1.hex").unwrap();
+    ///
+    /// let crc = ih.checksum(0x0000..0x0008, Checksum::Crc(Crc::CRC_32), 0xFF);
+    /// let sum = ih.checksum(0x0000..0x0008, Checksum::Sum8, 0xFF);
+    ///
+    /// assert_eq!(crc, Checksum::Crc(Crc::CRC_32).compute([[0xFA, 0x00, 0x00, 0x02, 0xFF, 0xFF, 0xFF, 0xFF].as_slice()]));
+    /// assert_eq!(sum, 0xF8);
+    /// ```
+    #[must_use]
+    pub fn checksum(&self, range: Range<usize>, algorithm: Checksum, gap_fill: u8) -> u64 {
+        // Gaps are fed in pieces of the (page sized) gap buffer
+        let gap_buf = [gap_fill; 4096];
+
+        let mut slices: Vec<&[u8]> = Vec::new();
+        for (start, end, has_data) in self.partition_range(range.start, range.end) {
+            if has_data {
+                if let Some((&chunk_start, chunk)) = self.buffer.range(..=start).next_back() {
+                    slices.push(&chunk[start - chunk_start..end - chunk_start]);
+                }
+            } else {
+                let mut remaining = end - start;
+                while remaining > 0 {
+                    let len = remaining.min(gap_buf.len());
+                    slices.push(&gap_buf[..len]);
+                    remaining -= len;
+                }
+            }
+        }
+
+        algorithm.compute(slices)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    /// Computes the checksum over the address range (see [`IntelHex::checksum()`]) and writes
+    /// it into the image at `address` with the given byte order.
+    /// The checksum occupies [`Checksum::size()`] bytes. Returns the computed checksum.
+    ///
+    /// # Errors
+    /// - Returns `ChecksumTargetOverlap` if the checksum would be written inside the range
+    /// - Returns `InvalidAddress` if the checksum does not fit into 32-bit address space
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Checksum, Crc, Endian, IntelHex};
+    ///
+    /// let mut ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// let crc = ih
+    ///     .embed_checksum(0x0100..0x0140, Checksum::Crc(Crc::CRC_16_CCITT), 0xFF, 0x0140, Endian::Big)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x0140, 2), Some(vec![(crc >> 8) as u8, crc as u8]));
+    /// ```
+    pub fn embed_checksum(
+        &mut self,
+        range: Range<usize>,
+        algorithm: Checksum,
+        gap_fill: u8,
+        address: usize,
+        endian: Endian,
+    ) -> Result<u64, IntelHexError> {
+        let size = algorithm.size();
+
+        if address < range.end && range.start < address.saturating_add(size) {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::ChecksumTargetOverlap(address),
+            ));
+        }
+
+        let value = self.checksum(range, algorithm, gap_fill);
+
+        let bytes = match endian {
+            Endian::Little => value.to_le_bytes()[..size].to_vec(),
+            Endian::Big => value.to_be_bytes()[8 - size..].to_vec(),
+        };
+        self.write_range(address, &bytes)?;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum::Crc;
+    use crate::chunks::{Chunk, ChunkMut};
+
+    #[test]
+    fn test_set_max_payload_size_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let new_payload_length = 2;
+
+        // Act
+        let res = ih.set_max_payload_size(new_payload_length);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(ih.max_payload_size, new_payload_length as usize);
+    }
+
+    #[test]
+    fn test_set_max_payload_size_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let new_payload_length = 0;
+        let default_payload_length = ih.max_payload_size;
+
+        // Act
+        let res = ih.set_max_payload_size(new_payload_length);
+
+        // Assert
+        assert!(res.is_err());
+        assert_eq!(ih.max_payload_size, default_payload_length);
+    }
+
+    #[test]
+    fn test_read_byte_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let addr = 0x1234;
+        let value = 0xFF;
+        ih.buffer.insert(addr, vec![value]);
+
+        // Act
+        let byte = ih.read_byte(addr);
+
+        // Assert
+        assert_eq!(byte, Some(value));
+    }
+
+    #[test]
+    fn test_read_byte_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let addr = 0x1234;
+        let value = 0xFF;
+        ih.buffer.insert(addr, vec![value]);
+
+        // Act
+        let byte = ih.read_byte(addr - 1);
+
+        // Assert
+        assert!(byte.is_none());
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_read_range_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut addr_vec = Vec::with_capacity(length);
+
+        for addr in addr_start..addr_start + length {
+            // construct data vector where each byte is a corresponding address
+            addr_vec.push(addr);
+        }
+
+        ih.buffer
+            .insert(addr_start, addr_vec.iter().map(|&x| x as u8).collect());
+
+        // Act
+        let res_vec: Option<Vec<u8>> = ih.read_range(addr_start, length);
+
+        // Assert
+        assert_eq!(res_vec, Some(addr_vec.iter().map(|&x| x as u8).collect()));
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_read_range_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut addr_vec = Vec::with_capacity(length);
+
+        for addr in addr_start..addr_start + length {
+            // construct data vector where each byte is a corresponding address
+            addr_vec.push(addr);
+        }
+
+        ih.buffer.insert(
+            addr_start,
+            addr_vec
+                .iter()
+                .map(|&x| x as u8)
+                .take(length - 1) // skip last byte
+                .collect(),
+        );
+
+        // Act
+        let res_vec: Option<Vec<u8>> = ih.read_range(addr_start, length);
+
+        // Assert
+        assert_eq!(res_vec, None);
+    }
+
+    #[test]
+    fn test_update_byte_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let addr = 0x1234;
+        let value = 0xFF;
+        ih.buffer.insert(addr, vec![value]);
+
+        // Act
+        let res = ih.update_byte(addr, value - 1);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(*ih.buffer.get(&addr).unwrap_or(&vec![]), [value - 1]);
+    }
+
+    #[test]
+    fn test_update_byte_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let addr = 0x1234;
+        let value = 0xFF;
+        ih.buffer.insert(addr, vec![value]);
+
+        // Act
+        let res = ih.update_byte(addr - 1, value - 1);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_update_slice_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+
+        for addr in addr_start..=addr_start + length {
+            update_map.push((addr, (addr - 1) as u8));
+        }
+
+        ih.buffer
+            .insert(addr_start, update_map.iter().map(|(_, x)| *x).collect());
+
+        // Act
+        let res = ih.update_slice(&update_map);
+
+        // Assert
+        assert!(res.is_ok());
+        for addr in addr_start..=addr_start + length {
+            assert_eq!(ih.read_byte(addr).unwrap_or_default(), (addr - 1) as u8);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_update_slice_with_gap() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+
+        // todo: fail
+        for addr in addr_start..addr_start + length {
+            update_map.push((addr * 2, (addr + 2) as u8));
+            ih.buffer.insert(addr * 2, vec![addr as u8]);
+        }
+
+        // Act
+        let res = ih.update_slice(&update_map);
+
+        // Assert
+        assert!(res.is_ok());
+        for (i, addr) in (addr_start..addr_start + length).enumerate() {
+            assert_eq!(ih.read_byte(addr * 2), Some(update_map[i].1));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_update_slice_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+
+        // todo: fail
+        for addr in addr_start..=addr_start + length {
+            update_map.push((addr, (addr - 1) as u8));
+            ih.buffer.insert(addr, vec![addr as u8]);
+        }
+        ih.buffer.pop_last(); // pop the last addr
+
+        // Act
+        let res = ih.update_slice(&update_map);
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_update_range_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+
+        for addr in addr_start..=addr_start + length {
+            update_map.push((addr, (addr + 7) as u8));
+        }
+
+        ih.buffer
+            .insert(addr_start, update_map.iter().map(|(_, x)| *x).collect());
+
+        // Act
+        let res = ih.update_range(
+            addr_start,
+            update_map
+                .iter()
+                .map(|(_, x)| *x)
+                .collect::<Vec<u8>>()
+                .as_slice(),
+        );
+
+        // Assert
+        assert!(res.is_ok());
+        for addr in addr_start..=addr_start + length {
+            assert_eq!(ih.read_byte(addr).unwrap_or_default(), (addr + 7) as u8);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_update_range_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 16;
+        let length = 10;
+
+        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+
+        for addr in addr_start..=addr_start + length {
+            update_map.push((addr, (addr + 7) as u8));
+        }
+
+        ih.buffer.insert(
+            addr_start,
+            update_map
+                .iter()
+                .map(|(_, x)| *x)
+                .take(length - 1) // skip last byte
+                .collect(),
+        );
+
+        // Act
+        let res = ih.update_range(
+            addr_start,
+            update_map
+                .iter()
+                .map(|(_, x)| *x)
+                .collect::<Vec<u8>>()
+                .as_slice(),
+        );
+
+        // Assert
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_get_min_and_max_addr_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        let addr_start = 10;
+        let length = 10;
+
+        ih.buffer.insert(addr_start, vec![0u8; 10]);
+
+        // Act
+        let min_addr = ih.get_min_addr();
+        let max_addr = ih.get_max_addr();
+
+        // Assert
+        assert_eq!(min_addr, Some(addr_start));
+        assert_eq!(max_addr, Some(addr_start + length - 1));
+
+        // Arrange
+        ih.buffer.clear();
+        ih.buffer.insert(0, vec![0]);
+
+        // Act
+        let min_addr = ih.get_min_addr();
+        let max_addr = ih.get_max_addr();
+
+        // Assert
+        assert_eq!(min_addr, Some(0));
+        assert_eq!(max_addr, Some(0));
+    }
+
+    #[test]
+    fn test_get_min_and_max_addr_invalid() {
+        // Arrange
+        let ih = IntelHex::new();
+
+        // Act
+        let min_addr = ih.get_min_addr();
+        let max_addr = ih.get_max_addr();
+
+        // Assert
+        assert!(min_addr.is_none());
+        assert!(max_addr.is_none());
+    }
+
+    #[test]
+    fn test_parse_bytes_valid() {
+        // Arrange
+        let raw = b":0400100000010203E6\r\n:02001400AABB85\r\n:00000001FF\r\n";
+
+        // Act
+        let res = IntelHex::parse_bytes(raw);
+
+        // Assert
+        assert!(res.is_ok());
+        if let Ok(ih) = res {
+            assert_eq!(ih.size, raw.len());
+            assert_eq!(
+                ih.buffer.get(&0x10),
+                Some(&vec![0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB])
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        // Arrange
+        let raw = ":0400100000010203E6\n:0400100000010203E5\n:00000001FF";
+
+        // Act
+        let res = raw.parse::<IntelHex>();
+
+        // Assert
+        assert_eq!(
+            res.err(),
+            Some(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::RecordChecksumMismatch(0xE6, 0xE5),
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_skips_and_repairs_records() {
+        // Arrange: bad checksum, wrong record type, overlap and data after EOF
+        let mut ih = IntelHex::new();
+        let raw = b":0400100000010203E5\n:0400200600010203D0\n\
+            :0200100000AA44\n:00000001FF\n:0400200000010203D6\n";
+
+        // Act
+        let res = ih.parse(raw, ParseOptions::lenient());
+
+        // Assert
+        assert_eq!(
+            res,
+            Ok(vec![
+                Diagnostic {
+                    line: 1,
+                    column: 18,
+                    kind: IntelHexErrorKind::RecordChecksumMismatch(0xE6, 0xE5)
+                },
+                Diagnostic {
+                    line: 2,
+                    column: 8,
+                    kind: IntelHexErrorKind::InvalidRecordType
+                },
+                Diagnostic {
+                    line: 3,
+                    column: 4,
+                    kind: IntelHexErrorKind::RecordAddressOverlap(0x10)
+                },
+                Diagnostic {
+                    line: 5,
+                    column: 1,
+                    kind: IntelHexErrorKind::DataAfterEndOfFile
+                },
+            ])
+        );
+        assert_eq!(ih.buffer.len(), 1);
+        assert_eq!(ih.buffer.get(&0x10), Some(&vec![0x00, 0x01, 0x02, 0x03]));
+    }
+
+    #[test]
+    fn test_parse_strict_returns_first_error() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let raw = b":0400100000010203E6\n:04002000000102G3D6\n:0400100000010203E5\n";
+
+        // Act
+        let res = ih.parse(raw, ParseOptions::strict());
+
+        // Assert
+        assert_eq!(
+            res,
+            Err(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::ContainsInvalidCharacters,
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_srec_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let raw = b"S0030000FC\nS3090800000001020304E4\nS30708000004AABB87\nS70508000000F2";
+
+        // Act
+        let res = ih.parse_srec(raw);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(
+            ih.buffer.get(&0x0800_0000),
+            Some(&vec![0x01, 0x02, 0x03, 0x04, 0xAA, 0xBB])
+        );
+        assert_eq!(ih.start_addr, Some(StartAddress::Linear(0x0800_0000)));
+    }
+
+    #[test]
+    fn test_parse_srec_overlap() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let raw = b"S3090800000001020304E4\nS3090800000001020304E4";
+
+        // Act
+        let res = ih.parse_srec(raw);
+
+        // Assert
+        assert_eq!(
+            res,
+            Err(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::RecordAddressOverlap(0x0800_0000),
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_srec_duplicate_start_address() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let raw = b"S9030000FC\r\nS9030000FC\r\n";
+
+        // Act
+        let res = ih.parse_srec(raw);
+
+        // Assert
+        assert_eq!(
+            res,
+            Err(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::DuplicateStartAddress,
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_from_bin_reader() {
+        // Arrange
+        let data: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF];
+
+        // Act
+        let res = IntelHex::from_bin_reader(data, 0x0800_0000);
+
+        // Assert
+        assert!(res.is_ok());
+        if let Ok(ih) = res {
+            assert_eq!(ih.size, 4);
+            assert_eq!(
+                ih.buffer.get(&0x0800_0000),
+                Some(&vec![0xDE, 0xAD, 0xBE, 0xEF])
+            );
+            assert_eq!(ih.filepath, PathBuf::new());
+        }
+    }
+
+    #[test]
+    fn test_relocate_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0xFFFF, vec![0xFF]);
+
+        // Act
+        let res = ih.relocate(0x0);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(ih.buffer.get(&0x0), Some(&vec![0xFFu8]));
+    }
+
+    #[test]
+    fn test_relocate_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x0000, vec![0xFF]); // min addr
+        ih.buffer.insert(0xFFFF, vec![0xFF]); // max addr
+
+        // Act
+        let res = ih.relocate(u32::MAX as usize);
+
+        // Assert
+        assert_eq!(
+            res,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RelocateAddressOverflow(0xFFFF_0000)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_relocate_with_start_address_linear() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x0800_0000, vec![0xFF]);
+        ih.start_addr = Some(StartAddress::Linear(0x0800_0001));
+        let mut ih_unchanged = ih.clone();
+        let mut ih_underflow = ih.clone();
+        ih_underflow.start_addr = Some(StartAddress::Linear(0x10));
+
+        // Act
+        let res = ih.relocate_with_start_address(0x0);
+        let res_unchanged = ih_unchanged.relocate(0x0);
+        let res_underflow = ih_underflow.relocate_with_start_address(0x0);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(ih.start_addr, Some(StartAddress::Linear(0x1)));
+        assert!(res_unchanged.is_ok());
+        assert_eq!(
+            ih_unchanged.start_addr,
+            Some(StartAddress::Linear(0x0800_0001))
+        );
+        assert_eq!(
+            res_underflow,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0x10)
+            ))
+        );
+        assert_eq!(ih_underflow.get_min_addr(), Some(0x0800_0000));
+    }
+
+    #[test]
+    fn test_relocate_with_start_address_segment() {
+        // Arrange: entry point 0x0100:0x0010 (linear 0x1010)
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x1000, vec![0xFF]);
+        ih.start_addr = Some(StartAddress::Segment {
+            cs: 0x0100,
+            ip: 0x0010,
+        });
+        let mut ih_normalized = ih.clone();
+        let mut ih_overflow = ih.clone();
+
+        // Act
+        let res = ih.relocate_with_start_address(0x3000);
+        let res_normalized = ih_normalized.relocate_with_start_address(0x1008);
+        let res_overflow = ih_overflow.relocate_with_start_address(0x0800_0000);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(
+            ih.start_addr,
+            Some(StartAddress::Segment {
+                cs: 0x0300,
+                ip: 0x0010
+            })
+        );
+        assert!(res_normalized.is_ok());
+        assert_eq!(
+            ih_normalized.start_addr,
+            Some(StartAddress::Segment {
+                cs: 0x0101,
+                ip: 0x0008
+            })
+        );
+        assert_eq!(
+            res_overflow,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0x1010)
+            ))
+        );
+        assert_eq!(ih_overflow.get_min_addr(), Some(0x1000));
+    }
+
+    #[test]
+    fn test_parse_elf_entry_above_4gib() {
+        // Arrange: ELF64 (little endian) with one segment at 0x1000 and a high entry point
+        let mut raw = vec![0u8; 64 + 56];
+        raw[..4].copy_from_slice(b"\x7FELF");
+        raw[4] = 2; // 64-bit
+        raw[5] = 1; // little endian
+        raw[0x18..0x20].copy_from_slice(&0xFFFF_FFFF_8000_1000u64.to_le_bytes());
+        raw[0x20..0x28].copy_from_slice(&64u64.to_le_bytes());
+        raw[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+        raw[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+        raw[64..68].copy_from_slice(&1u32.to_le_bytes()); // PT_LOAD
+        raw[64 + 0x08..64 + 0x10].copy_from_slice(&120u64.to_le_bytes());
+        raw[64 + 0x10..64 + 0x18].copy_from_slice(&0xFFFF_FFFF_8000_1000u64.to_le_bytes());
+        raw[64 + 0x18..64 + 0x20].copy_from_slice(&0x1000u64.to_le_bytes());
+        raw[64 + 0x20..64 + 0x28].copy_from_slice(&2u64.to_le_bytes());
+        raw.extend_from_slice(&[0xAA, 0xBB]);
+
+        // Act
+        let mut ih = IntelHex::new();
+        let res = ih.parse_elf(&raw, ElfAddress::Physical);
 
         // Assert
-        assert!(res.is_err());
-        assert_eq!(ih.max_payload_size, default_payload_length);
+        assert_eq!(res, Ok(()));
+        assert_eq!(ih.read_range(0x1000, 2), Some(vec![0xAA, 0xBB]));
+        assert_eq!(ih.start_addr, None);
+
+        // The virtual address of the segment lies above 4 GiB
+        let res = IntelHex::new().parse_elf(&raw, ElfAddress::Virtual);
+        assert_eq!(
+            res,
+            Err(IntelHexError::ParseElfError(
+                IntelHexErrorKind::ElfSegmentOutOfBounds(0)
+            ))
+        );
+
+        // The end of the segment overflows the address
+        raw[64 + 0x10..64 + 0x18].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+        let res = IntelHex::new().parse_elf(&raw, ElfAddress::Virtual);
+        assert_eq!(
+            res,
+            Err(IntelHexError::ParseElfError(
+                IntelHexErrorKind::ElfSegmentOutOfBounds(0)
+            ))
+        );
     }
 
     #[test]
-    fn test_read_byte_valid() {
+    fn test_parse_start_address_records() {
         // Arrange
-        let mut ih = IntelHex::new();
-        let addr = 0x1234;
-        let value = 0xFF;
-        ih.buffer.insert(addr, vec![value]);
+        let mut ih_segment = IntelHex::new();
+        let mut ih_linear = IntelHex::new();
 
         // Act
-        let byte = ih.read_byte(addr);
+        let res_segment =
+            ih_segment.parse(b":040000031043FFAAFD\n:00000001FF", ParseOptions::strict());
+        let res_linear =
+            ih_linear.parse(b":040000051043FFAAFB\n:00000001FF", ParseOptions::strict());
 
         // Assert
-        assert_eq!(byte, Some(value));
+        assert!(res_segment.is_ok());
+        assert_eq!(
+            ih_segment.start_addr,
+            Some(StartAddress::Segment {
+                cs: 0x1043,
+                ip: 0xFFAA
+            })
+        );
+        assert!(res_linear.is_ok());
+        assert_eq!(
+            ih_linear.start_addr,
+            Some(StartAddress::Linear(0x1043_FFAA))
+        );
     }
 
     #[test]
-    fn test_read_byte_invalid() {
+    fn test_parse_duplicate_start_address() {
+        // Arrange
+        let raw = b":040000031043FFAAFD\n:040000051043FFAAFB\n:00000001FF";
+        let mut ih_strict = IntelHex::new();
+        let mut ih_lenient = IntelHex::new();
+
+        // Act
+        let res_strict = ih_strict.parse(raw, ParseOptions::strict());
+        let res_lenient = ih_lenient.parse(raw, ParseOptions::lenient());
+
+        // Assert
+        assert_eq!(
+            res_strict,
+            Err(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::DuplicateStartAddress,
+                2
+            ))
+        );
+        assert_eq!(
+            res_lenient,
+            Ok(vec![Diagnostic {
+                line: 2,
+                column: 8,
+                kind: IntelHexErrorKind::DuplicateStartAddress
+            }])
+        );
+        // First start address is kept
+        assert_eq!(
+            ih_lenient.start_addr,
+            Some(StartAddress::Segment {
+                cs: 0x1043,
+                ip: 0xFFAA
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_hex_start_address_records() {
         // Arrange
         let mut ih = IntelHex::new();
-        let addr = 0x1234;
-        let value = 0xFF;
-        ih.buffer.insert(addr, vec![value]);
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.start_addr = Some(StartAddress::Segment {
+            cs: 0x1043,
+            ip: 0xFFAA,
+        });
 
         // Act
-        let byte = ih.read_byte(addr - 1);
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
 
         // Assert
-        assert!(byte.is_none());
+        assert!(res.is_ok());
+        assert_eq!(
+            out,
+            b":040000031043FFAAFD\n:0400100000010203E6\n:00000001FF".to_vec()
+        );
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_read_range_valid() {
+    fn test_write_hex_i8hex() {
         // Arrange
         let mut ih = IntelHex::new();
+        ih.buffer.insert(0xFFFE, vec![0x00, 0x01]);
+        ih.set_hex_format(HexFormat::I8Hex);
 
-        let addr_start = 16;
-        let length = 10;
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
 
-        let mut addr_vec = Vec::with_capacity(length);
+        // Assert: no extended address records
+        assert!(res.is_ok());
+        assert_eq!(out, b":02FFFE00000100\n:00000001FF".to_vec());
+    }
 
-        for addr in addr_start..addr_start + length {
-            // construct data vector where each byte is a corresponding address
-            addr_vec.push(addr);
+    #[test]
+    fn test_write_hex_i8hex_start_address() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x0100, vec![0x00, 0x01]);
+        ih.start_addr = Some(StartAddress::Linear(0x1234));
+        ih.set_hex_format(HexFormat::I8Hex);
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: the start address is not dropped silently, nothing is written
+        let expected = IntelHexError::CreateRecordError(
+            IntelHexErrorKind::AddressOutOfRangeForFormat(HexFormat::I8Hex, 0x1234),
+        );
+        assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_write_hex_i8hex_address_too_high() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0xFFFF, vec![0x00, 0x01]);
+        ih.set_hex_format(HexFormat::I8Hex);
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: nothing is written
+        let expected = IntelHexError::CreateRecordError(
+            IntelHexErrorKind::AddressOutOfRangeForFormat(HexFormat::I8Hex, 0x1_0000),
+        );
+        assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_word_addressed_roundtrip() {
+        // Arrange: record at word address 0x1_0004
+        let raw = b":020000040001F9\n:04000400AABBCCDDEA\n:00000001FF";
+        let options = ParseOptions::strict().with_address_unit(AddressUnit::Word(2));
+
+        // Act
+        let res = IntelHex::parse_bytes_with_options(raw, options);
+
+        // Assert
+        assert!(res.is_ok());
+        if let Ok((ih, _)) = res {
+            assert_eq!(ih.address_unit(), AddressUnit::Word(2));
+            assert_eq!(
+                ih.read_slice(0x2_0008, 4),
+                Some([0xAA, 0xBB, 0xCC, 0xDD].as_slice())
+            );
+
+            let mut out = Vec::new();
+            assert!(ih.write_hex_to(&mut out).is_ok());
+            assert_eq!(out, raw);
+        }
+    }
+
+    #[test]
+    fn test_load_after_word_addressed_parse() {
+        // Arrange: word addressed hex file
+        let raw = b":020000040001F9\n:04000400AABBCCDDEA\n:00000001FF";
+        let options = ParseOptions::strict().with_address_unit(AddressUnit::Word(2));
+        let (mut ih, _) = IntelHex::parse_bytes_with_options(raw, options).unwrap_or_default();
+        assert_eq!(ih.address_unit(), AddressUnit::Word(2));
+
+        // Act: loading another file starts over with byte addressing
+        ih.load_bin_bytes(&[0x01, 0x02], 0x100);
+
+        // Assert
+        assert_eq!(ih.address_unit(), AddressUnit::Byte);
+        let mut out = Vec::new();
+        assert!(ih.write_hex_to(&mut out).is_ok());
+        assert_eq!(out, b":020100000102FA\n:00000001FF".to_vec());
+    }
+
+    #[test]
+    fn test_pic24_drops_phantom_bytes() {
+        // Arrange: last record holds only a phantom byte
+        let raw = b":08000000112233004455660093\n:04000800778899005C\n:01000F00EE02\n:00000001FF";
+        let options = ParseOptions::strict().with_address_unit(AddressUnit::Pic24);
+
+        // Act
+        let res = IntelHex::parse_bytes_with_options(raw, options);
+
+        // Assert
+        assert!(res.is_ok());
+        if let Ok((ih, _)) = res {
+            assert_eq!(
+                ih.read_range(0x0, 9),
+                Some(vec![0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99])
+            );
+            assert_eq!(ih.total_data_len(), 9);
+
+            let mut out = Vec::new();
+            assert!(ih.write_hex_to(&mut out).is_ok());
+            assert_eq!(
+                String::from_utf8(out).unwrap_or_default(),
+                ":0C000000112233004455660077889900F7\n:00000001FF"
+            );
         }
+    }
+
+    #[test]
+    fn test_write_word_addressed_partial_word() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02]);
+        ih.set_address_unit(AddressUnit::Word(2));
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: nothing is written
+        let expected =
+            IntelHexError::CreateRecordError(IntelHexErrorKind::AddressNotWordAligned(0x10));
+        assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_write_hex_i16hex() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x0_FFFF, vec![0x00, 0x01]);
+        ih.buffer.insert(0xF_FFFF, vec![0x02]);
+        ih.start_addr = Some(StartAddress::Linear(0x1_2345));
+        ih.set_hex_format(HexFormat::I16Hex);
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: data is split at the segment boundary, start address converted
+        assert!(res.is_ok());
+        assert_eq!(
+            out,
+            b":040000031000234581\n\
+             :01FFFF000001\n\
+             :020000021000EC\n\
+             :0100000001FE\n\
+             :02000002F0000C\n\
+             :01FFFF0002FF\n\
+             :00000001FF"
+        );
+
+        // Written file reads back into the same image
+        let res = IntelHex::parse_bytes(&out);
+        assert!(res.is_ok_and(|ih_read| ih_read.buffer == ih.buffer
+            && ih_read.start_addr
+                == Some(StartAddress::Segment {
+                    cs: 0x1000,
+                    ip: 0x2345
+                })));
+    }
+
+    #[test]
+    fn test_write_hex_i16hex_address_too_high() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10_0000, vec![0x00]);
+        ih.set_hex_format(HexFormat::I16Hex);
+
+        // Act
+        let res = ih.write_hex_to(Vec::new());
+
+        // Assert
+        let expected = IntelHexError::CreateRecordError(
+            IntelHexErrorKind::AddressOutOfRangeForFormat(HexFormat::I16Hex, 0x10_0000),
+        );
+        assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+    }
 
+    #[test]
+    fn test_write_hex_aligned_records() {
+        // Arrange
+        let mut ih = IntelHex::new();
         ih.buffer
-            .insert(addr_start, addr_vec.iter().map(|&x| x as u8).collect());
+            .insert(0x1_0003, vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05]);
+        ih.set_max_payload_size(4).unwrap_or_default();
+        ih.set_hex_format(HexFormat::I16Hex);
+        ih.set_hex_write_options(HexWriteOptions {
+            line_ending: LineEnding::CrLf,
+            align_records: true,
+            extended_address_at_start: true,
+            ..HexWriteOptions::default()
+        });
 
         // Act
-        let res_vec: Option<Vec<u8>> = ih.read_range(addr_start, length);
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: records split at multiples of 4, single ESA record at the start
+        assert!(res.is_ok());
+        assert_eq!(
+            out,
+            b":020000021000EC\r\n\
+              :0100030000FC\r\n\
+              :0400040001020304EE\r\n\
+              :0100080005F2\r\n\
+              :00000001FF"
+        );
+    }
+
+    #[test]
+    fn test_write_hex_extended_address_at_start_i8hex() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.set_hex_format(HexFormat::I8Hex);
+        ih.set_hex_write_options(HexWriteOptions {
+            extended_address_at_start: true,
+            trailing_newline: true,
+            ..HexWriteOptions::default()
+        });
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: I8HEX has no extended address records
+        assert!(res.is_ok());
+        assert_eq!(out, b":0400100000010203E6\n:00000001FF\n");
+    }
+
+    const LAYOUT_HEX: &[u8] = b":020000040001F9\n\
+        :0400000000010203F6\n\
+        :0400040004050607E2\n\
+        :0400000500010000F6\n\
+        :00000001FF\n";
+
+    #[test]
+    fn test_write_hex_preserved_layout_unchanged() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let res = ih.parse(LAYOUT_HEX, ParseOptions::strict().with_preserved_layout());
+        assert!(res.is_ok());
+        ih.set_max_payload_size(32).unwrap_or_default();
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: payload size and start address position of the original are kept
+        assert!(res.is_ok());
+        assert!(ih.has_record_layout());
+        assert_eq!(out, LAYOUT_HEX);
+    }
+
+    #[test]
+    fn test_write_hex_preserved_layout_modified() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let res = ih.parse(LAYOUT_HEX, ParseOptions::strict().with_preserved_layout());
+        assert!(res.is_ok());
+
+        // Act: patch, erase within a record, add data outside of the records
+        assert!(ih.update_byte(0x1_0005, 0xAA).is_ok());
+        ih.erase_range(0x1_0001..0x1_0003);
+        assert!(ih.write_range(0x2_0000, &[0x11]).is_ok());
+        ih.set_start_address(Some(StartAddress::Linear(0x2_0000)));
+
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
 
         // Assert
-        assert_eq!(res_vec, Some(addr_vec.iter().map(|&x| x as u8).collect()));
+        assert!(res.is_ok());
+        assert_eq!(
+            out,
+            b":020000040001F9\n\
+              :0100000000FF\n\
+              :0100030003F9\n\
+              :0400040004AA06073D\n\
+              :0400000500020000F5\n\
+              :020000040002F8\n\
+              :0100000011EE\n\
+              :00000001FF\n"
+        );
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_read_range_invalid() {
+    fn test_write_hex_discarded_layout() {
         // Arrange
         let mut ih = IntelHex::new();
+        let res = ih.parse(LAYOUT_HEX, ParseOptions::strict().with_preserved_layout());
+        assert!(res.is_ok());
 
-        let addr_start = 16;
-        let length = 10;
+        // Act
+        ih.discard_record_layout();
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
 
-        let mut addr_vec = Vec::with_capacity(length);
+        // Assert: records are generated from scratch
+        assert!(res.is_ok());
+        assert_eq!(
+            out,
+            b":0400000500010000F6\n\
+              :020000040001F9\n\
+              :080000000001020304050607DC\n\
+              :00000001FF"
+        );
+    }
 
-        for addr in addr_start..addr_start + length {
-            // construct data vector where each byte is a corresponding address
-            addr_vec.push(addr);
-        }
+    #[test]
+    fn test_write_hex_preserved_layout_i8hex() {
+        // Arrange: I8HEX forbids the Extended Linear Address record of the original
+        let raw = b":020000040000FA\n:0400100000010203E6\n:00000001FF\n";
+        let mut ih = IntelHex::new();
+        let res = ih.parse(raw, ParseOptions::strict().with_preserved_layout());
+        assert!(res.is_ok());
 
-        ih.buffer.insert(
-            addr_start,
-            addr_vec
-                .iter()
-                .map(|&x| x as u8)
-                .take(length - 1) // skip last byte
-                .collect(),
+        // Act
+        ih.set_hex_format(HexFormat::I8Hex);
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+
+        // Assert: records are generated from scratch
+        assert!(res.is_ok());
+        assert_eq!(out, b":0400100000010203E6\n:00000001FF");
+    }
+
+    #[test]
+    fn test_relocate_discards_layout() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        let res = ih.parse(LAYOUT_HEX, ParseOptions::strict().with_preserved_layout());
+        assert!(res.is_ok());
+
+        // Act
+        let res = ih.relocate(0x0);
+        let mut out = Vec::new();
+        let res_write = ih.write_hex_to(&mut out);
+
+        // Assert: no stale extended address records of the original
+        assert!(res.is_ok());
+        assert!(!ih.has_record_layout());
+        assert!(res_write.is_ok());
+        assert_eq!(
+            out,
+            b":0400000500010000F6\n\
+              :080000000001020304050607DC\n\
+              :00000001FF"
         );
+    }
+
+    #[test]
+    fn test_segments_and_gaps() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01]);
+        ih.buffer.insert(0x20, vec![0x02]);
+        ih.buffer.insert(0x30, vec![0x03, 0x04, 0x05]);
 
         // Act
-        let res_vec: Option<Vec<u8>> = ih.read_range(addr_start, length);
+        let segments: Vec<_> = ih.segments().collect();
+        let gaps: Vec<_> = ih.gaps().collect();
 
         // Assert
-        assert_eq!(res_vec, None);
+        assert_eq!(segments, vec![0x10..0x12, 0x20..0x21, 0x30..0x33]);
+        assert_eq!(gaps, vec![0x12..0x20, 0x21..0x30]);
+        assert_eq!(ih.total_data_len(), 6);
+    }
+
+    #[test]
+    fn test_segments_and_gaps_empty() {
+        // Arrange
+        let ih = IntelHex::new();
+
+        // Act + Assert
+        assert_eq!(ih.segments().count(), 0);
+        assert_eq!(ih.gaps().count(), 0);
+        assert_eq!(ih.total_data_len(), 0);
+        assert_eq!(ih.segment_containing(0x0), None);
+        assert_eq!(ih.next_data_addr(0x0), None);
+        assert_eq!(ih.prev_data_addr(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_next_and_prev_data_addr() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01]);
+        ih.buffer.insert(0x20, vec![0x02]);
+
+        // Act + Assert
+        assert_eq!(ih.segment_containing(0x11), Some(0x10..0x12));
+        assert_eq!(ih.segment_containing(0x12), None);
+
+        assert_eq!(ih.next_data_addr(0x0), Some(0x10));
+        assert_eq!(ih.next_data_addr(0x10), Some(0x11));
+        assert_eq!(ih.next_data_addr(0x11), Some(0x20));
+        assert_eq!(ih.next_data_addr(0x20), None);
+        assert_eq!(ih.next_data_addr(usize::MAX), None);
+
+        assert_eq!(ih.prev_data_addr(usize::MAX), Some(0x20));
+        assert_eq!(ih.prev_data_addr(0x20), Some(0x11));
+        assert_eq!(ih.prev_data_addr(0x11), Some(0x10));
+        assert_eq!(ih.prev_data_addr(0x10), None);
+    }
+
+    #[test]
+    fn test_read_slice() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02]);
+        ih.buffer.insert(0x14, vec![0x04]);
+
+        // Act + Assert
+        assert_eq!(ih.read_slice(0x11, 2), Some([0x01, 0x02].as_slice()));
+        assert_eq!(ih.read_slice(0x13, 0), Some([].as_slice()));
+        assert_eq!(ih.read_slice(0x12, 2), None);
+        assert_eq!(ih.read_slice(0x0F, 1), None);
+    }
+
+    #[test]
+    fn test_chunks_in() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02]);
+        ih.buffer.insert(0x14, vec![0x04]);
+
+        // Act
+        let inside: Vec<Chunk> = ih.chunks_in(0x11..0x12).collect();
+        let around: Vec<Chunk> = ih.chunks_in(0x0E..0x16).collect();
+        let (start, end) = (0x16, 0x10);
+        let mut reversed = ih.chunks_in(start..end);
+
+        // Assert
+        assert_eq!(inside, vec![Chunk::Data(0x11, &[0x01])]);
+        assert_eq!(
+            around,
+            vec![
+                Chunk::Gap(0x0E..0x10),
+                Chunk::Data(0x10, &[0x00, 0x01, 0x02]),
+                Chunk::Gap(0x13..0x14),
+                Chunk::Data(0x14, &[0x04]),
+                Chunk::Gap(0x15..0x16),
+            ]
+        );
+        assert_eq!(reversed.next(), None);
+    }
+
+    #[test]
+    fn test_chunks_in_mut() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02]);
+        ih.buffer.insert(0x14, vec![0x04]);
+
+        // Act: overwrite all data in the window
+        for chunk in ih.chunks_in_mut(0x12..0x20) {
+            if let ChunkMut::Data(_, data) = chunk {
+                data.fill(0xAA);
+            }
+        }
+
+        // Assert
+        assert_eq!(ih.buffer.get(&0x10), Some(&vec![0x00, 0x01, 0xAA]));
+        assert_eq!(ih.buffer.get(&0x14), Some(&vec![0xAA]));
     }
 
     #[test]
-    fn test_update_byte_valid() {
+    fn test_write_range_valid() {
         // Arrange
         let mut ih = IntelHex::new();
-        let addr = 0x1234;
-        let value = 0xFF;
-        ih.buffer.insert(addr, vec![value]);
+        ih.buffer.insert(0x10, vec![0x00, 0x01]);
+        ih.buffer.insert(0x14, vec![0x04]);
 
-        // Act
-        let res = ih.update_byte(addr, value - 1);
+        // Act: overwrite, fill the gap and extend at once
+        let res = ih.write_range(0x11, &[0xAA, 0xBB, 0xCC, 0xDD, 0xEE]);
 
         // Assert
         assert!(res.is_ok());
-        assert_eq!(*ih.buffer.get(&addr).unwrap_or(&vec![]), [value - 1]);
+        assert_eq!(ih.buffer.len(), 1);
+        assert_eq!(
+            ih.buffer.get(&0x10),
+            Some(&vec![0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE])
+        );
     }
 
     #[test]
-    fn test_update_byte_invalid() {
+    fn test_write_range_invalid() {
         // Arrange
         let mut ih = IntelHex::new();
-        let addr = 0x1234;
-        let value = 0xFF;
-        ih.buffer.insert(addr, vec![value]);
 
         // Act
-        let res = ih.update_byte(addr - 1, value - 1);
+        let res = ih.write_range(u32::MAX as usize, &[0x00, 0x01]);
 
         // Assert
-        assert!(res.is_err());
+        assert_eq!(
+            res,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(u32::MAX as usize)
+            ))
+        );
+        assert!(ih.buffer.is_empty());
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_update_slice_valid() {
+    fn test_insert_bytes_valid() {
         // Arrange
         let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01]);
+        ih.buffer.insert(0x14, vec![0x04]);
 
-        let addr_start = 16;
-        let length = 10;
-
-        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+        // Act
+        let res_join = ih.insert_bytes(0x12, &[0x02, 0x03]);
+        let res_new = ih.insert_bytes(0x20, &[0x20]);
 
-        for addr in addr_start..=addr_start + length {
-            update_map.push((addr, (addr - 1) as u8));
-        }
+        // Assert
+        assert!(res_join.is_ok());
+        assert!(res_new.is_ok());
+        assert_eq!(
+            ih.buffer.get(&0x10),
+            Some(&vec![0x00, 0x01, 0x02, 0x03, 0x04])
+        );
+        assert_eq!(ih.buffer.get(&0x20), Some(&vec![0x20]));
+    }
 
-        ih.buffer
-            .insert(addr_start, update_map.iter().map(|(_, x)| *x).collect());
+    #[test]
+    fn test_insert_bytes_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x14, vec![0x04]);
 
         // Act
-        let res = ih.update_slice(&update_map);
+        let res = ih.insert_bytes(0x12, &[0x02, 0x03, 0xFF]);
 
         // Assert
-        assert!(res.is_ok());
-        for addr in addr_start..=addr_start + length {
-            assert_eq!(ih.read_byte(addr).unwrap_or_default(), (addr - 1) as u8);
-        }
+        assert_eq!(
+            res,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RecordAddressOverlap(0x14)
+            ))
+        );
+        assert_eq!(ih.buffer.len(), 1);
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_update_slice_with_gap() {
+    fn test_erase_range_valid() {
         // Arrange
         let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.buffer.insert(0x18, vec![0x08, 0x09]);
+        ih.buffer.insert(0x20, vec![0x20]);
 
-        let addr_start = 16;
-        let length = 10;
+        // Act: split the first chunk, remove the second one and leave the third one
+        ih.erase_range(0x11..0x13);
+        ih.erase_range(0x14..0x1A);
 
-        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+        // Assert
+        assert_eq!(
+            ih.buffer,
+            BTreeMap::from([(0x10, vec![0x00]), (0x13, vec![0x03]), (0x20, vec![0x20])])
+        );
+    }
 
-        // todo: fail
-        for addr in addr_start..addr_start + length {
-            update_map.push((addr * 2, (addr + 2) as u8));
-            ih.buffer.insert(addr * 2, vec![addr as u8]);
-        }
+    #[test]
+    fn test_crop_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.buffer.insert(0x18, vec![0x08, 0x09]);
+        ih.buffer.insert(0x20, vec![0x20]);
 
         // Act
-        let res = ih.update_slice(&update_map);
+        ih.crop(0x12..0x19);
 
         // Assert
-        assert!(res.is_ok());
-        for (i, addr) in (addr_start..addr_start + length).enumerate() {
-            assert_eq!(ih.read_byte(addr * 2), Some(update_map[i].1));
-        }
+        assert_eq!(
+            ih.buffer,
+            BTreeMap::from([(0x12, vec![0x02, 0x03]), (0x18, vec![0x08])])
+        );
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_update_slice_invalid() {
+    fn test_slice_valid() {
         // Arrange
         let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.buffer.insert(0x18, vec![0x08, 0x09]);
+        ih.start_addr = Some(StartAddress::Linear(0x10));
 
-        let addr_start = 16;
-        let length = 10;
+        // Act
+        let sliced = ih.slice(0x13..0x19);
+        let empty = ih.slice(0x14..0x18);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = ih.slice(0x18..0x10);
 
-        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+        // Assert
+        assert_eq!(
+            sliced.buffer,
+            BTreeMap::from([(0x13, vec![0x03]), (0x18, vec![0x08])])
+        );
+        assert_eq!(sliced.start_addr, None);
+        assert!(empty.buffer.is_empty());
+        assert!(reversed.buffer.is_empty());
+        assert_eq!(ih.buffer.len(), 2);
+    }
 
-        // todo: fail
-        for addr in addr_start..=addr_start + length {
-            update_map.push((addr, (addr - 1) as u8));
-            ih.buffer.insert(addr, vec![addr as u8]);
-        }
-        ih.buffer.pop_last(); // pop the last addr
+    #[test]
+    fn test_fill_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x12, vec![0xAA]);
+        ih.buffer.insert(0x16, vec![0xBB]);
 
         // Act
-        let res = ih.update_slice(&update_map);
+        let res = ih.fill(0x10..0x18, &[0x00, 0x01, 0x02]);
 
         // Assert
-        assert!(res.is_err());
+        assert!(res.is_ok());
+        assert_eq!(
+            ih.buffer,
+            BTreeMap::from([(0x10, vec![0x00, 0x01, 0xAA, 0x00, 0x01, 0x02, 0xBB, 0x01])])
+        );
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_update_range_valid() {
+    fn test_fill_invalid() {
         // Arrange
         let mut ih = IntelHex::new();
 
-        let addr_start = 16;
-        let length = 10;
-
-        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
+        // Act
+        let res_pattern = ih.fill(0x10..0x18, &[]);
+        let res_range = ih.fill(0xFFFF_FFF0..0x1_0000_0010, &[0xFF]);
 
-        for addr in addr_start..=addr_start + length {
-            update_map.push((addr, (addr + 7) as u8));
-        }
+        // Assert
+        assert_eq!(
+            res_pattern,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::EmptyFillPattern
+            ))
+        );
+        assert_eq!(
+            res_range,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0xFFFF_FFF0)
+            ))
+        );
+        assert!(ih.buffer.is_empty());
+    }
 
-        ih.buffer
-            .insert(addr_start, update_map.iter().map(|(_, x)| *x).collect());
+    #[test]
+    fn test_pad_to_alignment_valid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x02, vec![0x02, 0x03]);
+        ih.buffer.insert(0x06, vec![0x06]);
+        ih.buffer.insert(0x11, vec![0x11]);
 
         // Act
-        let res = ih.update_range(
-            addr_start,
-            update_map
-                .iter()
-                .map(|(_, x)| *x)
-                .collect::<Vec<u8>>()
-                .as_slice(),
-        );
+        let res = ih.pad_to_alignment(8, 0xFF);
 
         // Assert
         assert!(res.is_ok());
-        for addr in addr_start..=addr_start + length {
-            assert_eq!(ih.read_byte(addr).unwrap_or_default(), (addr + 7) as u8);
-        }
+        assert_eq!(
+            ih.buffer,
+            BTreeMap::from([
+                (0x00, vec![0xFF, 0xFF, 0x02, 0x03, 0xFF, 0xFF, 0x06, 0xFF]),
+                (0x10, vec![0xFF, 0x11, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            ])
+        );
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn test_update_range_invalid() {
+    fn test_pad_to_alignment_invalid() {
         // Arrange
         let mut ih = IntelHex::new();
-
-        let addr_start = 16;
-        let length = 10;
-
-        let mut update_map: Vec<(usize, u8)> = Vec::with_capacity(length);
-
-        for addr in addr_start..=addr_start + length {
-            update_map.push((addr, (addr + 7) as u8));
-        }
-
-        ih.buffer.insert(
-            addr_start,
-            update_map
-                .iter()
-                .map(|(_, x)| *x)
-                .take(length - 1) // skip last byte
-                .collect(),
-        );
+        ih.buffer.insert(0x02, vec![0x02]);
+        ih.buffer.insert(0xFFFF_FFF0, vec![0xF0]);
 
         // Act
-        let res = ih.update_range(
-            addr_start,
-            update_map
-                .iter()
-                .map(|(_, x)| *x)
-                .collect::<Vec<u8>>()
-                .as_slice(),
-        );
+        let res_zero = ih.pad_to_alignment(0, 0xFF);
+        let res_overflow = ih.pad_to_alignment(0x30, 0xFF); // 2^32 is not a multiple of 0x30
 
         // Assert
-        assert!(res.is_err());
+        assert_eq!(
+            res_zero,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAlignment(0)
+            ))
+        );
+        assert_eq!(
+            res_overflow,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0xFFFF_FFF0)
+            ))
+        );
+        assert_eq!(ih.buffer.get(&0x02), Some(&vec![0x02]));
     }
 
     #[test]
-    fn test_get_min_and_max_addr_valid() {
+    fn test_checksum_with_gaps() {
         // Arrange
         let mut ih = IntelHex::new();
-
-        let addr_start = 10;
-        let length = 10;
-
-        ih.buffer.insert(addr_start, vec![0u8; 10]);
+        ih.buffer.insert(0x0000, b"1234".to_vec());
+        ih.buffer.insert(0x1005, b"6789".to_vec());
+        let mut ih_filled = ih.clone();
+        ih_filled.fill(0x0000..0x1009, b"5").ok();
 
         // Act
-        let min_addr = ih.get_min_addr();
-        let max_addr = ih.get_max_addr();
+        let crc = ih.checksum(0x0000..0x1009, Checksum::Crc(Crc::CRC_32), b'5');
+        let crc_filled = ih_filled.checksum(0x0000..0x1009, Checksum::Crc(Crc::CRC_32), 0x00);
+        let sum_outside = ih.checksum(0x2000..0x2002, Checksum::Sum16, 0xFF);
 
         // Assert
-        assert_eq!(min_addr, Some(addr_start));
-        assert_eq!(max_addr, Some(addr_start + length - 1));
+        assert_eq!(crc, crc_filled);
+        assert_eq!(sum_outside, 0x01FE);
+    }
 
+    #[test]
+    fn test_embed_checksum_valid() {
         // Arrange
-        ih.buffer.clear();
-        ih.buffer.insert(0, vec![0]);
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, b"123456789".to_vec());
 
         // Act
-        let min_addr = ih.get_min_addr();
-        let max_addr = ih.get_max_addr();
+        let res_crc32 = ih.embed_checksum(
+            0x10..0x19,
+            Checksum::Crc(Crc::CRC_32),
+            0xFF,
+            0x19,
+            Endian::Little,
+        );
+        let res_modbus = ih.embed_checksum(
+            0x10..0x19,
+            Checksum::Crc(Crc::CRC_16_MODBUS),
+            0xFF,
+            0x08,
+            Endian::Big,
+        );
 
         // Assert
-        assert_eq!(min_addr, Some(0));
-        assert_eq!(max_addr, Some(0));
+        assert_eq!(res_crc32, Ok(0xCBF4_3926));
+        assert_eq!(res_modbus, Ok(0x4B37));
+        assert_eq!(ih.read_range(0x19, 4), Some(vec![0x26, 0x39, 0xF4, 0xCB]));
+        assert_eq!(ih.read_range(0x08, 2), Some(vec![0x4B, 0x37]));
     }
 
     #[test]
-    fn test_get_min_and_max_addr_invalid() {
+    fn test_embed_checksum_invalid() {
         // Arrange
-        let ih = IntelHex::new();
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, b"123456789".to_vec());
 
         // Act
-        let min_addr = ih.get_min_addr();
-        let max_addr = ih.get_max_addr();
+        let res = ih.embed_checksum(0x10..0x19, Checksum::Sum32, 0xFF, 0x16, Endian::Little);
 
         // Assert
-        assert!(min_addr.is_none());
-        assert!(max_addr.is_none());
+        assert_eq!(
+            res,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::ChecksumTargetOverlap(0x16)
+            ))
+        );
+        assert_eq!(ih.read_range(0x10, 9), Some(b"123456789".to_vec()));
+    }
+
+    /// Returns a pair of instances which overlap at 0x12..0x14
+    ///
+    fn get_overlapping_pair() -> (IntelHex, IntelHex) {
+        let mut ih = IntelHex::new();
+        ih.buffer.insert(0x10, vec![0x00, 0x01, 0x02, 0x03]);
+        ih.buffer.insert(0x20, vec![0x20]);
+        ih.start_addr = Some(StartAddress::Linear(0x10));
+
+        let mut other = IntelHex::new();
+        other.buffer.insert(0x12, vec![0x02, 0xAA, 0x14, 0x15]);
+        other.buffer.insert(0x1F, vec![0x1F]);
+        other.start_addr = Some(StartAddress::Linear(0x12));
+
+        (ih, other)
     }
 
     #[test]
-    fn test_relocate_valid() {
+    fn test_merge_coalesces_chunks() {
         // Arrange
         let mut ih = IntelHex::new();
-        ih.buffer.insert(0xFFFF, vec![0xFF]);
+        ih.buffer.insert(0x10, vec![0x00, 0x01]);
+        ih.buffer.insert(0x14, vec![0x04]);
+        let mut other = IntelHex::new();
+        other.buffer.insert(0x12, vec![0x02, 0x03]);
+        other.buffer.insert(0x15, vec![0x05]);
+        other.start_addr = Some(StartAddress::Linear(0x12));
 
         // Act
-        let res = ih.relocate(0x0);
+        let res = ih.merge(&other, OverlapPolicy::Error);
 
         // Assert
         assert!(res.is_ok());
-        assert_eq!(ih.buffer.get(&0x0), Some(&vec![0xFFu8]));
+        assert_eq!(ih.buffer.len(), 1);
+        assert_eq!(
+            ih.buffer.get(&0x10),
+            Some(&vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05])
+        );
+        assert_eq!(ih.start_addr, Some(StartAddress::Linear(0x12)));
     }
 
     #[test]
-    fn test_relocate_invalid() {
+    fn test_merge_keep_existing_and_overwrite() {
         // Arrange
-        let mut ih = IntelHex::new();
-        ih.buffer.insert(0x0000, vec![0xFF]); // min addr
-        ih.buffer.insert(0xFFFF, vec![0xFF]); // max addr
+        let (mut ih_keep, other) = get_overlapping_pair();
+        let (mut ih_overwrite, _) = get_overlapping_pair();
 
         // Act
-        let res = ih.relocate(u32::MAX as usize);
+        let res_keep = ih_keep.merge(&other, OverlapPolicy::KeepExisting);
+        let res_overwrite = ih_overwrite.merge(&other, OverlapPolicy::Overwrite);
 
         // Assert
+        assert!(res_keep.is_ok());
         assert_eq!(
-            res,
+            ih_keep.buffer.get(&0x10),
+            Some(&vec![0x00, 0x01, 0x02, 0x03, 0x14, 0x15])
+        );
+        assert_eq!(ih_keep.buffer.get(&0x1F), Some(&vec![0x1F, 0x20]));
+        assert_eq!(ih_keep.start_addr, Some(StartAddress::Linear(0x10)));
+
+        assert!(res_overwrite.is_ok());
+        assert_eq!(
+            ih_overwrite.buffer.get(&0x10),
+            Some(&vec![0x00, 0x01, 0x02, 0xAA, 0x14, 0x15])
+        );
+        assert_eq!(ih_overwrite.buffer.get(&0x1F), Some(&vec![0x1F, 0x20]));
+        assert_eq!(ih_overwrite.start_addr, Some(StartAddress::Linear(0x12)));
+    }
+
+    #[test]
+    fn test_merge_invalid() {
+        // Arrange
+        let (mut ih, mut other) = get_overlapping_pair();
+
+        // Act + Assert: start address conflict is checked first
+        assert_eq!(
+            ih.merge(&other, OverlapPolicy::Error),
             Err(IntelHexError::UpdateError(
-                IntelHexErrorKind::RelocateAddressOverflow(0xFFFF_0000)
+                IntelHexErrorKind::DuplicateStartAddress
+            ))
+        );
+
+        // Act + Assert: data overlap
+        other.start_addr = None;
+        assert_eq!(
+            ih.merge(&other, OverlapPolicy::Error),
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RecordAddressOverlap(0x12)
+            ))
+        );
+
+        // Act + Assert: identical byte at 0x12 is fine, but 0x13 differs
+        assert_eq!(
+            ih.merge(&other, OverlapPolicy::ErrorIfDifferent),
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::MergeDataConflict(0x13)
             ))
         );
+
+        // Instance is left unchanged
+        assert_eq!(ih.buffer.len(), 2);
+        assert_eq!(ih.buffer.get(&0x10), Some(&vec![0x00, 0x01, 0x02, 0x03]));
+    }
+
+    #[test]
+    fn test_merge_error_if_different_valid() {
+        // Arrange
+        let (mut ih, mut other) = get_overlapping_pair();
+        other.buffer.insert(0x12, vec![0x02, 0x03, 0x14, 0x15]);
+        other.start_addr = Some(StartAddress::Linear(0x10));
+
+        // Act
+        let res = ih.merge(&other, OverlapPolicy::ErrorIfDifferent);
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(
+            ih.buffer.get(&0x10),
+            Some(&vec![0x00, 0x01, 0x02, 0x03, 0x14, 0x15])
+        );
+        assert_eq!(ih.start_addr, Some(StartAddress::Linear(0x10)));
     }
 }
 
@@ -1293,6 +4599,6 @@ mod tests {
 impl IntelHex {
     #[cfg(feature = "benchmarking")]
     pub fn bench_priv_parse(ih: &mut Self, raw_bytes: &[u8]) {
-        let _ = ih.parse(raw_bytes);
+        let _ = ih.parse(raw_bytes, ParseOptions::strict());
     }
 }
@@ -0,0 +1,194 @@
+//! The `layout` module defines the [`RecordLayout`] which remembers the records of a parsed
+//! Intel HEX file (boundaries, order and extended address records), so that the file can be
+//! written back with only the changed records regenerated.
+//! See [`ParseOptions::preserve_layout`](crate::ParseOptions::preserve_layout).
+
+use crate::options::{HexFormat, LineEnding};
+use crate::record::{Record, RecordType, StartAddress};
+
+/// Single record of the original hex file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutRecord {
+    /// Data record covering `len` bytes from the absolute `address`.
+    /// `offset` is the base address set by the preceding extended address record.
+    Data {
+        address: usize,
+        offset: usize,
+        len: usize,
+        line: String,
+    },
+    /// Extended Segment Address or Extended Linear Address record, written back verbatim
+    Verbatim { rtype: RecordType, line: String },
+    /// Start Segment Address or Start Linear Address record
+    Start {
+        start_addr: StartAddress,
+        line: String,
+    },
+    /// End Of File record
+    EndOfFile { line: String },
+}
+
+impl LayoutRecord {
+    /// Create the layout entry of a parsed record.
+    /// `offset` is the base address in effect when the record was read.
+    ///
+    pub fn new(record: &Record, offset: usize, line: &[u8]) -> Self {
+        let line = String::from_utf8_lossy(line).into_owned();
+        match record.rtype {
+            RecordType::Data => Self::Data {
+                address: record.address as usize + offset,
+                offset,
+                len: record.data.len(),
+                line,
+            },
+            RecordType::ExtendedSegmentAddress | RecordType::ExtendedLinearAddress => {
+                Self::Verbatim {
+                    rtype: record.rtype,
+                    line,
+                }
+            }
+            RecordType::StartSegmentAddress | RecordType::StartLinearAddress => {
+                match StartAddress::from_record(record) {
+                    Some(start_addr) => Self::Start { start_addr, line },
+                    // Payload length was checked during record parsing
+                    None => Self::Verbatim {
+                        rtype: record.rtype,
+                        line,
+                    },
+                }
+            }
+            RecordType::EndOfFile => Self::EndOfFile { line },
+        }
+    }
+}
+
+/// Record structure and text style of a parsed hex file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordLayout {
+    /// Records in the order of the original file
+    pub records: Vec<LayoutRecord>,
+    /// Line ending of the original file
+    pub line_ending: LineEnding,
+    /// Original file ends with a line ending
+    pub trailing_newline: bool,
+    /// Original file uses lowercase hex digits
+    pub lowercase: bool,
+}
+
+impl RecordLayout {
+    /// Create an empty layout with the text style detected from the raw file contents.
+    ///
+    pub fn detect(raw_bytes: &[u8]) -> Self {
+        let line_ending = match raw_bytes.iter().position(|&b| b == b'\n') {
+            Some(pos) if pos > 0 && raw_bytes[pos - 1] == b'\r' => LineEnding::CrLf,
+            _ => LineEnding::Lf,
+        };
+
+        Self {
+            records: Vec::new(),
+            line_ending,
+            trailing_newline: raw_bytes.ends_with(b"\n"),
+            lowercase: raw_bytes.iter().any(|b| matches!(b, b'a'..=b'f')),
+        }
+    }
+
+    /// Returns `true` if all records of the layout are allowed in the hex format:
+    /// I8HEX has no extended or start address records, I16HEX no linear ones.
+    ///
+    pub fn fits_format(&self, format: HexFormat) -> bool {
+        self.records.iter().all(|entry| {
+            let rtype = match entry {
+                LayoutRecord::Verbatim { rtype, .. } => *rtype,
+                LayoutRecord::Start { start_addr, .. } => start_addr.record_type(),
+                LayoutRecord::Data { .. } | LayoutRecord::EndOfFile { .. } => return true,
+            };
+            match format {
+                HexFormat::I8Hex => false,
+                HexFormat::I16Hex => matches!(
+                    rtype,
+                    RecordType::ExtendedSegmentAddress | RecordType::StartSegmentAddress
+                ),
+                HexFormat::I32Hex => true,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_text_style() {
+        // Act
+        let layout_lf = RecordLayout::detect(b":0400100000010203E6\n:00000001FF");
+        let layout_crlf = RecordLayout::detect(b":0400100000010203e6\r\n:00000001ff\r\n");
+
+        // Assert
+        assert_eq!(layout_lf.line_ending, LineEnding::Lf);
+        assert!(!layout_lf.trailing_newline);
+        assert!(!layout_lf.lowercase);
+
+        assert_eq!(layout_crlf.line_ending, LineEnding::CrLf);
+        assert!(layout_crlf.trailing_newline);
+        assert!(layout_crlf.lowercase);
+    }
+
+    #[test]
+    fn test_fits_format() {
+        // Arrange
+        let mut layout = RecordLayout::detect(b"");
+        let data = LayoutRecord::Data {
+            address: 0x10,
+            offset: 0,
+            len: 4,
+            line: String::from(":0400100000010203E6"),
+        };
+        let esa = LayoutRecord::Verbatim {
+            rtype: RecordType::ExtendedSegmentAddress,
+            line: String::from(":020000021000EC"),
+        };
+        let ela = LayoutRecord::Verbatim {
+            rtype: RecordType::ExtendedLinearAddress,
+            line: String::from(":020000040800F2"),
+        };
+
+        // Act + Assert: data records only
+        layout.records = vec![data.clone()];
+        assert!(layout.fits_format(HexFormat::I8Hex));
+
+        // Extended Segment Address records
+        layout.records = vec![esa, data.clone()];
+        assert!(!layout.fits_format(HexFormat::I8Hex));
+        assert!(layout.fits_format(HexFormat::I16Hex));
+
+        // Extended Linear Address records
+        layout.records = vec![ela, data];
+        assert!(!layout.fits_format(HexFormat::I16Hex));
+        assert!(layout.fits_format(HexFormat::I32Hex));
+    }
+
+    #[test]
+    fn test_layout_record_from_data_record() {
+        // Arrange
+        let line = b":0400100000010203E6";
+        let record = Record::parse(line);
+        assert!(record.is_ok());
+
+        if let Ok(record) = record {
+            // Act
+            let entry = LayoutRecord::new(&record, 0x1_0000, line);
+
+            // Assert
+            assert_eq!(
+                entry,
+                LayoutRecord::Data {
+                    address: 0x1_0010,
+                    offset: 0x1_0000,
+                    len: 4,
+                    line: String::from(":0400100000010203E6"),
+                }
+            );
+        }
+    }
+}
@@ -3,9 +3,21 @@
 //! `intelhexlib` is a Rust library for parsing, validating, and working with Intel HEX files.
 //!
 //! The library provides:
-//! - Parser for Intel HEX files (via [`IntelHex`] struct).
+//! - Parser and writer for Intel HEX files (via [`IntelHex`] struct) in I8HEX / I16HEX / I32HEX format.
+//! - Word-addressed hex files of 16-bit and 24-bit word architectures (via [`AddressUnit`]).
+//! - Reader and writer for Motorola S-record files (S19 / S28 / S37).
+//! - Reader and writer for TI-TXT and (extended) Tektronix hex files (via [`TekHexFormat`]).
+//! - Streaming hex-to-bin conversion and record callbacks from a `BufRead` with bounded memory.
+//! - Loader for the loadable segments of 32- and 64-bit ELF files.
+//! - Reader and writer for FPGA memory initialization files (`$readmemh` / `$readmemb`, Xilinx COE,
+//!   Intel MIF) with configurable word width and byte order (via [`MemInitOptions`]).
+//! - CRC and additive checksums over address ranges (via [`Checksum`]) and their embedding.
 //! - Error handling with [`IntelHexError`].
-//! - Easy access to hex data for its reading and modification.
+//! - Lenient parsing of damaged hex files (via [`ParseOptions`]) with [`Diagnostic`] reports.
+//! - Easy access to hex data for its reading and modification (incl. byte swapping of words).
+//! - Merging of multiple images (via [`OverlapPolicy`]) and their comparison (via [`DiffRange`]).
+//! - Pattern search with masks and nibble wildcards (via [`Pattern`]).
+//! - Export of the data as C header, Rust array or GNU assembler source (via [`SourceFormat`]).
 //!
 //! ## Example
 //!
@@ -16,11 +28,38 @@
 //! ih.write_hex("build/ex1/ih.hex");
 //! ```
 
+mod checksum;
+mod chunks;
+mod diff;
+mod elf;
 mod error;
+mod export;
 mod intelhex;
+mod layout;
+mod meminit;
+mod options;
 mod record;
+mod search;
+mod srecord;
+mod stream;
+mod tekhex;
+mod titxt;
+mod transform;
+mod typed;
 
 // Public APIs
-pub use error::{IntelHexError, IntelHexErrorKind};
+pub use checksum::{Checksum, Crc};
+pub use chunks::{Chunk, ChunkMut, ChunksIn, ChunksInMut};
+pub use diff::DiffRange;
+pub use elf::ElfAddress;
+pub use error::{Diagnostic, IntelHexError, IntelHexErrorKind};
+pub use export::{ExportScope, SourceFormat};
 pub use intelhex::IntelHex;
-pub use record::RecordType;
+pub use meminit::MemInitFormat;
+pub use options::{
+    AddressUnit, Endian, HexFormat, HexWriteOptions, LineEnding, MemInitOptions, OverlapPolicy,
+    ParseOptions,
+};
+pub use record::{RecordType, StartAddress};
+pub use search::{FindIter, Pattern};
+pub use tekhex::TekHexFormat;
@@ -0,0 +1,1133 @@
+//! The `meminit` module extends [`IntelHex`] with readers and writers of FPGA memory
+//! initialization files (see [`MemInitFormat`]), e.g. to load soft-core firmware into block RAM.
+//! The word width, byte order and base address of the memory are set via [`MemInitOptions`].
+//! Words of widths which are not a multiple of 8 bits (e.g. the 9, 18 or 36 bits of block RAM
+//! with parity bits) are padded to whole bytes in the image, the padding bits are zero.
+
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::{IntelHex, create_file_writer};
+use crate::options::{Endian, MemInitOptions};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+/// Format of an FPGA memory initialization file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MemInitFormat {
+    /// Verilog `$readmemh` text: hexadecimal words with `@addr` markers
+    ReadMemH,
+    /// Verilog `$readmemb` text: binary words with `@addr` markers (addresses in hex)
+    ReadMemB,
+    /// Xilinx coefficient file (`.coe`) with `memory_initialization_radix` and
+    /// `memory_initialization_vector` statements
+    Coe,
+    /// Intel / Altera memory initialization file (`.mif`)
+    Mif,
+}
+
+/// Characters forming tokens of their own (`.` forms runs such as `..`)
+const SYMBOLS: &[u8] = b";=,:[].";
+
+/// Token of a memory initialization file with its line number.
+#[derive(Copy, Clone, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+}
+
+/// Split the text into tokens, skipping whitespace and the comments of the format.
+fn tokenize(text: &str, format: MemInitFormat) -> Vec<Token<'_>> {
+    let (line_comment, block_comment) = match format {
+        MemInitFormat::ReadMemH | MemInitFormat::ReadMemB => ("//", Some(("/*", "*/"))),
+        MemInitFormat::Coe => (";", None),
+        MemInitFormat::Mif => ("--", Some(("%", "%"))),
+    };
+    let starts_comment = |rest: &[u8]| {
+        rest.starts_with(line_comment.as_bytes())
+            || block_comment.is_some_and(|(open, _)| rest.starts_with(open.as_bytes()))
+    };
+
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.as_bytes()[0];
+
+        if c == b'\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+        } else if c.is_ascii_whitespace() {
+            i += 1;
+        } else if rest.starts_with(line_comment)
+            // Comments of .coe files start with ';' at the beginning of a line,
+            // anywhere else it ends a statement
+            && (line_start || format != MemInitFormat::Coe)
+        {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some((open, close)) = block_comment
+            && rest.starts_with(open)
+        {
+            let len = rest[open.len()..]
+                .find(close)
+                .map_or(rest.len(), |end| open.len() + end + close.len());
+            line += rest[..len].matches('\n').count();
+            i += len;
+        } else {
+            line_start = false;
+            let len = if c == b'.' {
+                rest.bytes().take_while(|&b| b == b'.').count()
+            } else if SYMBOLS.contains(&c) {
+                1
+            } else {
+                // Words end at ASCII characters only, so the slices stay on char boundaries
+                let bytes = rest.as_bytes();
+                (1..bytes.len())
+                    .find(|&j| {
+                        bytes[j].is_ascii_whitespace()
+                            || SYMBOLS.contains(&bytes[j])
+                            || starts_comment(&bytes[j..])
+                    })
+                    .unwrap_or(bytes.len())
+            };
+            tokens.push(Token {
+                text: &rest[..len],
+                line,
+            });
+            i += len;
+        }
+    }
+    tokens
+}
+
+/// Iterator over the tokens, which remembers the line of the last returned token.
+struct Tokens<'a> {
+    iter: std::vec::IntoIter<Token<'a>>,
+    line: usize,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.iter.next()?;
+        self.line = token.line;
+        Some(token)
+    }
+}
+
+impl<'a> Tokens<'a> {
+    fn new(tokens: Vec<Token<'a>>) -> Self {
+        Self {
+            iter: tokens.into_iter(),
+            line: 1,
+        }
+    }
+
+    /// Syntax error at the line of the last returned token.
+    const fn syntax_error(&self) -> IntelHexError {
+        IntelHexError::ParseRecordError(IntelHexErrorKind::InvalidMemInitSyntax, self.line)
+    }
+
+    /// Next token, which has to exist.
+    fn expect_any(&mut self) -> Result<Token<'a>, IntelHexError> {
+        self.next().ok_or_else(|| self.syntax_error())
+    }
+
+    /// Next token, which has to be the given keyword or symbol (case-insensitive).
+    fn expect(&mut self, text: &str) -> Result<(), IntelHexError> {
+        let token = self.expect_any()?;
+        if token.text.eq_ignore_ascii_case(text) {
+            Ok(())
+        } else {
+            Err(self.syntax_error())
+        }
+    }
+
+    /// Next token, which has to be a number in the radix.
+    fn expect_number(&mut self, radix: u32) -> Result<usize, IntelHexError> {
+        let token = self.expect_any()?;
+        parse_number(token.text, radix).ok_or_else(|| self.syntax_error())
+    }
+}
+
+/// Number of bytes of a word of the given width in bits (padded to whole bytes).
+const fn word_bytes(word_width: usize) -> Option<usize> {
+    if word_width > 0 {
+        Some(word_width.div_ceil(8))
+    } else {
+        None
+    }
+}
+
+/// Bits of the most significant byte of a word which lie within the word width.
+const fn top_byte_mask(word_width: usize) -> u8 {
+    match word_width % 8 {
+        0 => 0xFF,
+        bits => (1 << bits) - 1,
+    }
+}
+
+/// Parse an address or a header value in the radix (`_` separators are allowed).
+fn parse_number(text: &str, radix: u32) -> Option<usize> {
+    usize::from_str_radix(&text.replace('_', ""), radix).ok()
+}
+
+/// Parse a word value in the radix into the bytes of a word of `word_width` bits,
+/// the most significant first.
+/// Returns `None` for invalid digits or a value which does not fit into the word.
+fn parse_word(text: &str, radix: u32, word_width: usize) -> Option<Vec<u8>> {
+    let mut bytes = vec![0u8; word_width.div_ceil(8)];
+    let mut has_digits = false;
+
+    for c in text.chars().filter(|&c| c != '_') {
+        // Multiply the value by the radix and add the digit, byte by byte
+        let mut carry = c.to_digit(radix)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * radix + carry;
+            *byte = value.to_le_bytes()[0];
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+        has_digits = true;
+    }
+    // The padding bits above the word width have to stay clear
+    let fits = bytes
+        .first()
+        .is_some_and(|&top| top & !top_byte_mask(word_width) == 0);
+    (has_digits && fits).then_some(bytes)
+}
+
+/// Format the bytes of a word of `word_width` bits (the most significant first)
+/// as hexadecimal or binary digits.
+fn format_word(bytes: &[u8], radix: u32, word_width: usize) -> String {
+    let digits: String = bytes
+        .iter()
+        .map(|b| {
+            if radix == 2 {
+                format!("{b:08b}")
+            } else {
+                format!("{b:02X}")
+            }
+        })
+        .collect();
+
+    // Digits of the padding bits are dropped (these are zero)
+    let len = if radix == 2 {
+        word_width
+    } else {
+        word_width.div_ceil(4)
+    };
+    digits[digits.len() - len..].to_string()
+}
+
+/// Radix of the `ADDRESS_RADIX` / `DATA_RADIX` values of .mif files.
+fn mif_radix(name: &str) -> Option<u32> {
+    match name.to_ascii_uppercase().as_str() {
+        "HEX" => Some(16),
+        "DEC" | "UNS" => Some(10),
+        "OCT" => Some(8),
+        "BIN" => Some(2),
+        _ => None,
+    }
+}
+
+/// Collects the parsed words into runs of consecutive word addresses,
+/// which are inserted into the image at once.
+struct WordSink<'a> {
+    ih: &'a mut IntelHex,
+    options: MemInitOptions,
+    /// Word width in bits and the number of bytes of a word
+    word_width: usize,
+    word_bytes: usize,
+    /// Word address and line of the first word of the run
+    run_start: (usize, usize),
+    run: Vec<u8>,
+}
+
+impl<'a> WordSink<'a> {
+    const fn new(ih: &'a mut IntelHex, options: MemInitOptions, word_width: usize) -> Self {
+        Self {
+            ih,
+            options,
+            word_width,
+            word_bytes: word_width.div_ceil(8),
+            run_start: (0, 0),
+            run: Vec::new(),
+        }
+    }
+
+    /// Add the word (bytes with the most significant first) parsed at the line.
+    fn push(
+        &mut self,
+        word_addr: usize,
+        mut value: Vec<u8>,
+        line: usize,
+    ) -> Result<(), IntelHexError> {
+        // Word address right after the current run (does not exist at the end of address space)
+        let next_addr = self
+            .run_start
+            .0
+            .checked_add(self.run.len() / self.word_bytes)
+            .ok_or(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::InvalidAddress(word_addr),
+                line,
+            ))?;
+        if self.run.is_empty() || word_addr != next_addr {
+            self.flush()?;
+            self.run_start = (word_addr, line);
+        }
+        if self.options.endian == Endian::Little {
+            value.reverse();
+        }
+        self.run.extend(value);
+        Ok(())
+    }
+
+    /// Insert the current run into the image.
+    fn flush(&mut self) -> Result<(), IntelHexError> {
+        if self.run.is_empty() {
+            return Ok(());
+        }
+
+        let (word_addr, line) = self.run_start;
+        let res = match word_addr
+            .checked_mul(self.word_bytes)
+            .and_then(|offset| offset.checked_add(self.options.base_addr))
+        {
+            Some(addr) => self.ih.insert_bytes(addr, &self.run),
+            None => Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(word_addr),
+            )),
+        };
+        self.run.clear();
+
+        res.map_err(|err| match err {
+            IntelHexError::UpdateError(kind) => IntelHexError::ParseRecordError(kind, line),
+            other => other,
+        })
+    }
+}
+
+/// Parse `$readmemh` / `$readmemb` words in the radix with `@addr` markers.
+fn parse_readmem(sink: &mut WordSink, tokens: Vec<Token>, radix: u32) -> Result<(), IntelHexError> {
+    let mut word_addr: usize = 0;
+
+    for token in tokens {
+        if let Some(addr) = token.text.strip_prefix('@') {
+            // Addresses are hexadecimal for both $readmemh and $readmemb
+            word_addr = parse_number(addr, 16).ok_or(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::InvalidMemInitSyntax,
+                token.line,
+            ))?;
+        } else {
+            let value = parse_word(token.text, radix, sink.word_width).ok_or(
+                IntelHexError::ParseRecordError(IntelHexErrorKind::InvalidWordValue, token.line),
+            )?;
+            sink.push(word_addr, value, token.line)?;
+            word_addr = word_addr.saturating_add(1);
+        }
+    }
+    Ok(())
+}
+
+/// Parse the `key = value, ...;` statements of a .coe file. The words of the
+/// `memory_initialization_vector` start at word address 0; other keys are ignored.
+fn parse_coe(sink: &mut WordSink, tokens: Vec<Token>) -> Result<(), IntelHexError> {
+    let mut tokens = Tokens::new(tokens);
+    let mut radix = 10;
+
+    while let Some(key) = tokens.next() {
+        tokens.expect("=")?;
+
+        // Values up to the end of the statement (the last ';' may be missing)
+        let mut values = Vec::new();
+        for token in tokens.by_ref() {
+            match token.text {
+                ";" => break,
+                "," => {}
+                _ => values.push(token),
+            }
+        }
+
+        match key.text.to_ascii_lowercase().as_str() {
+            "memory_initialization_radix" => {
+                radix = match values.as_slice() {
+                    [value] => match parse_number(value.text, 10) {
+                        Some(2) => 2,
+                        Some(10) => 10,
+                        Some(16) => 16,
+                        _ => return Err(tokens.syntax_error()),
+                    },
+                    _ => return Err(tokens.syntax_error()),
+                };
+            }
+            "memory_initialization_vector" => {
+                for (word_addr, value) in values.iter().enumerate() {
+                    let word = parse_word(value.text, radix, sink.word_width).ok_or(
+                        IntelHexError::ParseRecordError(
+                            IntelHexErrorKind::InvalidWordValue,
+                            value.line,
+                        ),
+                    )?;
+                    sink.push(word_addr, word, value.line)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parse the header and `CONTENT BEGIN ... END;` section of a .mif file.
+/// Content entries are `addr : value;`, `addr : value value ...;` (consecutive words)
+/// or `[first..last] : value ...;` (range filled by repeating the values).
+fn parse_mif(sink: &mut WordSink, tokens: Vec<Token>) -> Result<(), IntelHexError> {
+    let mut tokens = Tokens::new(tokens);
+    let mut width = None;
+    let mut depth = None;
+    let mut address_radix = 16;
+    let mut data_radix = 16;
+
+    // Header
+    loop {
+        let key = tokens.expect_any()?;
+        if key.text.eq_ignore_ascii_case("CONTENT") {
+            tokens.expect("BEGIN")?;
+            break;
+        }
+        tokens.expect("=")?;
+        let value = tokens.expect_any()?;
+        match key.text.to_ascii_uppercase().as_str() {
+            "WIDTH" => {
+                let bits = parse_number(value.text, 10).ok_or_else(|| tokens.syntax_error())?;
+                if word_bytes(bits).is_none() {
+                    return Err(IntelHexError::ParseRecordError(
+                        IntelHexErrorKind::InvalidWordWidth(bits),
+                        value.line,
+                    ));
+                }
+                width = Some(bits);
+            }
+            "DEPTH" => depth = parse_number(value.text, 10),
+            "ADDRESS_RADIX" => {
+                address_radix = mif_radix(value.text).ok_or_else(|| tokens.syntax_error())?;
+            }
+            "DATA_RADIX" => {
+                data_radix = mif_radix(value.text).ok_or_else(|| tokens.syntax_error())?;
+            }
+            _ => return Err(tokens.syntax_error()),
+        }
+        tokens.expect(";")?;
+    }
+
+    // Width and depth are mandatory
+    let (Some(width), Some(depth)) = (width, depth) else {
+        return Err(tokens.syntax_error());
+    };
+    sink.word_width = width;
+    sink.word_bytes = width.div_ceil(8);
+
+    // Content
+    loop {
+        let token = tokens.expect_any()?;
+        if token.text.eq_ignore_ascii_case("END") {
+            tokens.expect(";")?;
+            break;
+        }
+
+        let (first, last) = if token.text == "[" {
+            let first = tokens.expect_number(address_radix)?;
+            tokens.expect("..")?;
+            let last = tokens.expect_number(address_radix)?;
+            tokens.expect("]")?;
+            if last < first {
+                return Err(tokens.syntax_error());
+            }
+            (first, Some(last))
+        } else {
+            let first =
+                parse_number(token.text, address_radix).ok_or_else(|| tokens.syntax_error())?;
+            (first, None)
+        };
+        tokens.expect(":")?;
+
+        let mut values = Vec::new();
+        loop {
+            let value = tokens.expect_any()?;
+            if value.text == ";" {
+                break;
+            }
+            let word = parse_word(value.text, data_radix, width).ok_or(
+                IntelHexError::ParseRecordError(IntelHexErrorKind::InvalidWordValue, value.line),
+            )?;
+            values.push(word);
+        }
+        if values.is_empty() {
+            return Err(tokens.syntax_error());
+        }
+
+        let last = match last {
+            Some(last) => last,
+            None => first
+                .checked_add(values.len() - 1)
+                .ok_or(IntelHexError::ParseRecordError(
+                    IntelHexErrorKind::InvalidAddress(first),
+                    tokens.line,
+                ))?,
+        };
+        if last >= depth {
+            return Err(IntelHexError::ParseRecordError(
+                IntelHexErrorKind::InvalidAddress(last),
+                tokens.line,
+            ));
+        }
+        for (word_addr, value) in (first..=last).zip(values.iter().cycle()) {
+            sink.push(word_addr, value.clone(), tokens.line)?;
+        }
+    }
+    Ok(())
+}
+
+impl IntelHex {
+    /// Creates an `IntelHex` instance and fills it with data from the provided
+    /// FPGA memory initialization file. See [`IntelHex::parse_mem_init()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, MemInitFormat, MemInitOptions};
+    ///
+    /// let ih = IntelHex::from_mem_init("tests/fixtures/mem_valid_1.mif", MemInitFormat::Mif, MemInitOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x0, 4), Some(vec![0x78, 0x56, 0x34, 0x12]));
+    /// ```
+    pub fn from_mem_init<P: AsRef<Path>>(
+        filepath: P,
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut ih = Self::new();
+        ih.load_mem_init(filepath, format, options)?;
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with data from the provided
+    /// FPGA memory initialization file. See [`IntelHex::parse_mem_init()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, MemInitFormat, MemInitOptions};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_mem_init("tests/fixtures/mem_valid_1.mif", MemInitFormat::Mif, MemInitOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(ih.get_max_addr(), Some(0x27));
+    /// ```
+    pub fn load_mem_init<P: AsRef<Path>>(
+        &mut self,
+        filepath: P,
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        // Parse contents
+        self.parse_mem_init_data(&raw_bytes, format, options)?;
+
+        Ok(())
+    }
+
+    /// Creates an `IntelHex` instance from the contents of an FPGA memory initialization file.
+    ///
+    /// The word at word address `n` is placed at `options.base_addr + n * word_bytes`,
+    /// its bytes ordered by `options.endian` (`word_bytes` is the word width rounded up to
+    /// whole bytes). The word width of .mif files is taken from
+    /// their `WIDTH` statement instead of the options.
+    ///
+    /// # Errors
+    /// Returns `ParseRecordError` with the line number of the problem:
+    /// - `InvalidWordWidth` if the word width is 0 (line 0 for the width of the options)
+    /// - `InvalidWordValue` if a word has invalid digits or does not fit into the word width
+    /// - `InvalidMemInitSyntax` if a statement or an address is malformed
+    /// - `InvalidAddress` if a word lies outside of the memory (`DEPTH` of .mif files)
+    ///   or of 32-bit address space
+    /// - `RecordAddressOverlap` if a word address is defined twice
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{Endian, IntelHex, MemInitFormat, MemInitOptions};
+    ///
+    /// let raw = b"// boot ROM\n@0\nDEADBEEF\n@4 00C0FFEE";
+    /// let options = MemInitOptions::new(32).with_endian(Endian::Big);
+    /// let ih = IntelHex::parse_mem_init(raw, MemInitFormat::ReadMemH, options).unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x00, 4), Some(vec![0xDE, 0xAD, 0xBE, 0xEF]));
+    /// assert_eq!(ih.read_range(0x10, 4), Some(vec![0x00, 0xC0, 0xFF, 0xEE]));
+    /// ```
+    pub fn parse_mem_init(
+        raw_bytes: &[u8],
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<Self, IntelHexError> {
+        let mut ih = Self::new();
+        ih.size = raw_bytes.len();
+        ih.parse_mem_init_data(raw_bytes, format, options)?;
+        Ok(ih)
+    }
+
+    /// Parse the memory initialization file contents into the (cleared) instance.
+    fn parse_mem_init_data(
+        &mut self,
+        raw_bytes: &[u8],
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<(), IntelHexError> {
+        // Invalid UTF-8 sequences end up in word tokens and are reported as invalid values
+        let text = String::from_utf8_lossy(raw_bytes);
+        let tokens = tokenize(&text, format);
+
+        // The word width of .mif files is set by their header
+        let word_width = match (format, word_bytes(options.word_width)) {
+            (MemInitFormat::Mif, _) => 8,
+            (_, Some(_)) => options.word_width,
+            (_, None) => {
+                return Err(IntelHexError::ParseRecordError(
+                    IntelHexErrorKind::InvalidWordWidth(options.word_width),
+                    0,
+                ));
+            }
+        };
+
+        let mut sink = WordSink::new(self, options, word_width);
+        match format {
+            MemInitFormat::ReadMemH => parse_readmem(&mut sink, tokens, 16)?,
+            MemInitFormat::ReadMemB => parse_readmem(&mut sink, tokens, 2)?,
+            MemInitFormat::Coe => parse_coe(&mut sink, tokens)?,
+            MemInitFormat::Mif => parse_mif(&mut sink, tokens)?,
+        }
+        sink.flush()
+    }
+
+    /// Generates an FPGA memory initialization file at the specified path.
+    /// See [`IntelHex::write_mem_init_to()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written or the data cannot be
+    /// represented with the options.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, MemInitFormat, MemInitOptions};
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.write_mem_init("build/ex1/ih.coe", MemInitFormat::Coe, MemInitOptions::new(16))
+    ///     .unwrap();
+    ///
+    /// let ih_coe = IntelHex::from_mem_init("build/ex1/ih.coe", MemInitFormat::Coe, MemInitOptions::new(16))
+    ///     .unwrap();
+    /// assert_eq!(ih_coe.read_range(0x100, 2), ih.read_range(0x100, 2));
+    /// ```
+    pub fn write_mem_init<P: AsRef<Path>>(
+        &self,
+        filepath: P,
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_mem_init_to(create_file_writer(filepath)?, format, options)
+    }
+
+    /// Writes the data as an FPGA memory initialization file into the provided writer.
+    ///
+    /// Every word holding any data is written; its bytes without data are set to
+    /// `options.gap_fill`. Words between the data are handled per format:
+    /// - `$readmemh` / `$readmemb`: skipped via `@addr` markers
+    /// - .coe: written with the gap fill value, as the vector starts at word address 0
+    /// - .mif: written with the gap fill value in `[first..last]` ranges, so that no word
+    ///   up to `DEPTH` (the word after the last data) stays undefined
+    ///
+    /// # Errors
+    /// Returns `CreateRecordError` if:
+    /// - the word width is 0 (`InvalidWordWidth`)
+    /// - a word holds a value which does not fit into the word width (`InvalidWordValue`)
+    /// - the data starts below `options.base_addr` (`InvalidAddress`)
+    /// - a .coe or .mif file is requested for an empty instance (`IntelHexInstanceEmpty`)
+    ///
+    /// Returns an error if the writer fails.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, MemInitFormat, MemInitOptions};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x0, &[0x13, 0x00, 0x00, 0x00, 0x6F, 0x00, 0x00, 0x00]).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_mem_init_to(&mut out, MemInitFormat::Mif, MemInitOptions::new(32)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "-- Generated by intelhexlib\n\
+    ///      WIDTH=32;\nDEPTH=2;\n\n\
+    ///      ADDRESS_RADIX=HEX;\nDATA_RADIX=HEX;\n\n\
+    ///      CONTENT BEGIN\n    0 : 00000013;\n    1 : 0000006F;\nEND;\n"
+    /// );
+    /// ```
+    pub fn write_mem_init_to<W: Write>(
+        &self,
+        mut writer: W,
+        format: MemInitFormat,
+        options: MemInitOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let word_bytes = word_bytes(options.word_width).ok_or(IntelHexError::CreateRecordError(
+            IntelHexErrorKind::InvalidWordWidth(options.word_width),
+        ))?;
+        let words = self.collect_words(&options, word_bytes)?;
+        let width = options.word_width;
+
+        // Gap fill value of a whole word, without the padding bits
+        let mut gap_word = vec![options.gap_fill; word_bytes];
+        gap_word[0] &= top_byte_mask(width);
+        let gap_word = format_word(&gap_word, 16, width);
+
+        match format {
+            MemInitFormat::ReadMemH | MemInitFormat::ReadMemB => {
+                let radix = if format == MemInitFormat::ReadMemH {
+                    16
+                } else {
+                    2
+                };
+                writeln!(writer, "// Generated by intelhexlib")?;
+                let mut next_addr = None;
+                for (word_addr, value) in &words {
+                    if next_addr != Some(*word_addr) {
+                        writeln!(writer, "@{word_addr:08X}")?;
+                    }
+                    writeln!(writer, "{}", format_word(value, radix, width))?;
+                    next_addr = Some(word_addr + 1);
+                }
+            }
+            MemInitFormat::Coe => {
+                let depth = words.last().map(|(word_addr, _)| word_addr + 1).ok_or(
+                    IntelHexError::CreateRecordError(IntelHexErrorKind::IntelHexInstanceEmpty),
+                )?;
+                writeln!(writer, "; Generated by intelhexlib")?;
+                writeln!(writer, "memory_initialization_radix=16;")?;
+                writeln!(writer, "memory_initialization_vector=")?;
+
+                let mut words = words.iter().peekable();
+                for word_addr in 0..depth {
+                    let value = words.next_if(|(addr, _)| *addr == word_addr).map_or_else(
+                        || gap_word.clone(),
+                        |(_, value)| format_word(value, 16, width),
+                    );
+                    let end = if word_addr + 1 < depth { "," } else { ";" };
+                    writeln!(writer, "{value}{end}")?;
+                }
+            }
+            MemInitFormat::Mif => {
+                let depth = words.last().map(|(word_addr, _)| word_addr + 1).ok_or(
+                    IntelHexError::CreateRecordError(IntelHexErrorKind::IntelHexInstanceEmpty),
+                )?;
+                writeln!(writer, "-- Generated by intelhexlib")?;
+                writeln!(writer, "WIDTH={};\nDEPTH={depth};\n", options.word_width)?;
+                writeln!(writer, "ADDRESS_RADIX=HEX;\nDATA_RADIX=HEX;\n")?;
+                writeln!(writer, "CONTENT BEGIN")?;
+
+                let mut next_addr = 0;
+                for (word_addr, value) in &words {
+                    match word_addr - next_addr {
+                        0 => {}
+                        1 => writeln!(writer, "    {next_addr:X} : {gap_word};")?,
+                        _ => writeln!(
+                            writer,
+                            "    [{next_addr:X}..{:X}] : {gap_word};",
+                            word_addr - 1
+                        )?,
+                    }
+                    writeln!(
+                        writer,
+                        "    {word_addr:X} : {};",
+                        format_word(value, 16, width)
+                    )?;
+                    next_addr = word_addr + 1;
+                }
+                writeln!(writer, "END;")?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Words holding any data as (word address, bytes with the most significant first).
+    /// Bytes of the words without data are set to the gap fill byte (without the padding bits).
+    ///
+    /// # Errors
+    /// - Returns `InvalidAddress` if the data starts below the base address
+    /// - Returns `InvalidWordValue` if the data of a word does not fit into the word width
+    ///
+    fn collect_words(
+        &self,
+        options: &MemInitOptions,
+        word_bytes: usize,
+    ) -> Result<Vec<(usize, Vec<u8>)>, IntelHexError> {
+        if let Some(min_addr) = self.get_min_addr()
+            && min_addr < options.base_addr
+        {
+            return Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::InvalidAddress(min_addr),
+            ));
+        }
+
+        let mut words: Vec<(usize, Vec<u8>)> = Vec::new();
+        for (&chunk_start_addr, data) in self {
+            let first = (chunk_start_addr - options.base_addr) / word_bytes;
+            let last = (chunk_start_addr + data.len() - 1 - options.base_addr) / word_bytes;
+
+            for word_addr in first..=last {
+                // Neighbouring chunks may share a word
+                if words.last().is_some_and(|(addr, _)| *addr == word_addr) {
+                    continue;
+                }
+                let mut bytes =
+                    self.read_range_safe(options.base_addr + word_addr * word_bytes, word_bytes);
+                if options.endian == Endian::Little {
+                    bytes.reverse();
+                }
+
+                // The padding bits of the most significant byte have to be clear
+                let mask = top_byte_mask(options.word_width);
+                let top = match bytes[0] {
+                    Some(top) if top & !mask != 0 => {
+                        return Err(IntelHexError::CreateRecordError(
+                            IntelHexErrorKind::InvalidWordValue,
+                        ));
+                    }
+                    Some(top) => top,
+                    None => options.gap_fill & mask,
+                };
+                let value = std::iter::once(top)
+                    .chain(
+                        bytes[1..]
+                            .iter()
+                            .map(|byte| byte.unwrap_or(options.gap_fill)),
+                    )
+                    .collect();
+                words.push((word_addr, value));
+            }
+        }
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_word() {
+        assert_eq!(parse_word("1_0F", 16, 16), Some(vec![0x01, 0x0F]));
+        assert_eq!(parse_word("65535", 10, 16), Some(vec![0xFF, 0xFF]));
+        assert_eq!(parse_word("101", 2, 8), Some(vec![0x05]));
+        assert_eq!(parse_word("1FF", 16, 9), Some(vec![0x01, 0xFF]));
+        assert_eq!(parse_word("65536", 10, 16), None);
+        assert_eq!(parse_word("200", 16, 9), None);
+        assert_eq!(parse_word("1x", 16, 16), None);
+        assert_eq!(parse_word("_", 16, 16), None);
+    }
+
+    #[test]
+    fn test_format_word() {
+        assert_eq!(format_word(&[0x01, 0x0F], 16, 16), "010F");
+        assert_eq!(format_word(&[0x01, 0xFF], 16, 9), "1FF");
+        assert_eq!(
+            format_word(&[0x03, 0x00, 0x01], 2, 18),
+            "110000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_comments() {
+        // Arrange
+        let text = "; comment\nradix=16;\nvector=A,\n B; // not a comment";
+
+        // Act
+        let tokens = tokenize(text, MemInitFormat::Coe);
+
+        // Assert: ';' only starts a comment at the beginning of a line
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(
+            texts,
+            [
+                "radix", "=", "16", ";", "vector", "=", "A", ",", "B", ";", "//", "not", "a",
+                "comment"
+            ]
+        );
+        assert_eq!(tokens[8].line, 4);
+    }
+
+    #[test]
+    fn test_parse_readmemb_little_endian() {
+        // Arrange
+        let raw = b"/* two\n words */ 00000001_00000010\n@2 // gap\n1111111100000000";
+
+        // Act
+        let res = IntelHex::parse_mem_init(raw, MemInitFormat::ReadMemB, MemInitOptions::new(16));
+
+        // Assert
+        assert!(res.is_ok_and(
+            |ih| ih.bytes().collect::<Vec<_>>() == [(0, 0x02), (1, 0x01), (4, 0x00), (5, 0xFF)]
+        ));
+    }
+
+    #[test]
+    fn test_parse_coe() {
+        // Arrange
+        let raw = b"; FIR core keys are ignored\nradix=10;\nmemory_initialization_radix=16;\n\
+                    memory_initialization_vector=\nDEAD, BEEF,\n00C0;";
+        let options = MemInitOptions::new(16)
+            .with_endian(Endian::Big)
+            .with_base_addr(0x1000);
+
+        // Act
+        let res = IntelHex::parse_mem_init(raw, MemInitFormat::Coe, options);
+
+        // Assert
+        assert!(res.is_ok_and(
+            |ih| ih.read_range(0x1000, 6) == Some(vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0xC0])
+        ));
+    }
+
+    #[test]
+    fn test_parse_mif_ranges() {
+        // Arrange
+        let raw = b"WIDTH=8; DEPTH=16; ADDRESS_RADIX=UNS; DATA_RADIX=HEX;\n\
+                    CONTENT BEGIN\n\
+                    % block\ncomment %\n\
+                    [0..4] : 01 02; -- repeated pattern\n\
+                    10 : AA BB;\n\
+                    END;";
+
+        // Act
+        let res = IntelHex::parse_mem_init(raw, MemInitFormat::Mif, MemInitOptions::default());
+
+        // Assert
+        assert!(res.is_ok_and(|ih| ih.bytes().collect::<Vec<_>>()
+            == [
+                (0, 0x01),
+                (1, 0x02),
+                (2, 0x01),
+                (3, 0x02),
+                (4, 0x01),
+                (10, 0xAA),
+                (11, 0xBB)
+            ]));
+    }
+
+    #[test]
+    fn test_parse_mem_init_invalid() {
+        // Arrange
+        let cases: [(&[u8], MemInitFormat, IntelHexErrorKind, usize); 8] = [
+            (
+                b"00\n100",
+                MemInitFormat::ReadMemH,
+                IntelHexErrorKind::InvalidWordValue,
+                2,
+            ),
+            (
+                b"00\n@0 11",
+                MemInitFormat::ReadMemH,
+                IntelHexErrorKind::RecordAddressOverlap(0),
+                2,
+            ),
+            (
+                b"@G 11",
+                MemInitFormat::ReadMemH,
+                IntelHexErrorKind::InvalidMemInitSyntax,
+                1,
+            ),
+            (
+                b"WIDTH=0;\nDEPTH=4;\nCONTENT BEGIN\nEND;",
+                MemInitFormat::Mif,
+                IntelHexErrorKind::InvalidWordWidth(0),
+                1,
+            ),
+            (
+                b"WIDTH=9;\nDEPTH=4;\nCONTENT BEGIN\n0 : 1FF;\n1 : 200;\nEND;",
+                MemInitFormat::Mif,
+                IntelHexErrorKind::InvalidWordValue,
+                5,
+            ),
+            (
+                b"WIDTH=8;\nDEPTH=4;\nCONTENT BEGIN\n3 : 01 02;\nEND;",
+                MemInitFormat::Mif,
+                IntelHexErrorKind::InvalidAddress(4),
+                4,
+            ),
+            (
+                b"@FFFFFFFFFFFFFFFF 01 02",
+                MemInitFormat::ReadMemH,
+                IntelHexErrorKind::InvalidAddress(usize::MAX),
+                1,
+            ),
+            (
+                b"WIDTH=8;\nDEPTH=4;\nCONTENT BEGIN\nFFFFFFFFFFFFFFFF : 01 02;\nEND;",
+                MemInitFormat::Mif,
+                IntelHexErrorKind::InvalidAddress(usize::MAX),
+                4,
+            ),
+        ];
+
+        for (raw, format, kind, line) in cases {
+            // Act
+            let res = IntelHex::parse_mem_init(raw, format, MemInitOptions::default());
+
+            // Assert
+            assert_eq!(res.err(), Some(IntelHexError::ParseRecordError(kind, line)));
+        }
+    }
+
+    #[test]
+    fn test_write_mem_init_round_trip() {
+        // Arrange: two chunks sharing a 32-bit word and a gap of whole words
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x2000_0000, &[0x01, 0x02, 0x03]).is_ok());
+        assert!(ih.write_range(0x2000_0005, &[0x06, 0x07, 0x08]).is_ok());
+        assert!(
+            ih.write_range(0x2000_0014, &[0x15, 0x16, 0x17, 0x18])
+                .is_ok()
+        );
+        let options = MemInitOptions::new(32)
+            .with_base_addr(0x2000_0000)
+            .with_gap_fill(0xFF);
+
+        for format in [
+            MemInitFormat::ReadMemH,
+            MemInitFormat::ReadMemB,
+            MemInitFormat::Coe,
+            MemInitFormat::Mif,
+        ] {
+            // Act
+            let mut out = Vec::new();
+            assert!(ih.write_mem_init_to(&mut out, format, options).is_ok());
+            let res = IntelHex::parse_mem_init(&out, format, options);
+
+            // Assert: data is kept, the gaps inside the written words are filled
+            assert!(res.is_ok_and(|ih_read| {
+                ih_read.read_range(0x2000_0000, 8)
+                    == Some(vec![0x01, 0x02, 0x03, 0xFF, 0xFF, 0x06, 0x07, 0x08])
+                    && ih_read.read_range(0x2000_0014, 4) == Some(vec![0x15, 0x16, 0x17, 0x18])
+            }));
+        }
+    }
+
+    #[test]
+    fn test_mem_init_non_byte_width() {
+        // Arrange: 18-bit words padded to 3 bytes, the second one half filled
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x0, &[0x01, 0x02, 0x03, 0xFF]).is_ok());
+        let options = MemInitOptions::new(18).with_gap_fill(0xFF);
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_mem_init_to(&mut out, MemInitFormat::ReadMemH, options);
+        let res_read = IntelHex::parse_mem_init(&out, MemInitFormat::ReadMemH, options);
+
+        // Assert: the gap fill of the padding bits is dropped
+        assert!(res.is_ok());
+        assert_eq!(
+            String::from_utf8(out).unwrap_or_default(),
+            "// Generated by intelhexlib\n@00000000\n30201\n3FFFF\n"
+        );
+        assert!(
+            res_read.is_ok_and(|ih_read| ih_read.bytes().collect::<Vec<_>>()
+                == [
+                    (0, 0x01),
+                    (1, 0x02),
+                    (2, 0x03),
+                    (3, 0xFF),
+                    (4, 0xFF),
+                    (5, 0x03)
+                ])
+        );
+    }
+
+    #[test]
+    fn test_write_readmemh_gaps() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x0, &[0x01, 0x02]).is_ok());
+        assert!(ih.write_range(0x8, &[0x09, 0x0A]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_mem_init_to(
+            &mut out,
+            MemInitFormat::ReadMemH,
+            MemInitOptions::new(16).with_endian(Endian::Big),
+        );
+
+        // Assert
+        assert!(res.is_ok());
+        assert_eq!(
+            String::from_utf8(out).unwrap_or_default(),
+            "// Generated by intelhexlib\n@00000000\n0102\n@00000004\n090A\n"
+        );
+    }
+
+    #[test]
+    fn test_write_mem_init_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x1F]).is_ok());
+        let empty = IntelHex::new();
+
+        // Act
+        let mut out = Vec::new();
+        let res_width = ih.write_mem_init_to(&mut out, MemInitFormat::Mif, MemInitOptions::new(0));
+        let res_value = ih.write_mem_init_to(&mut out, MemInitFormat::Mif, MemInitOptions::new(4));
+        let res_base = ih.write_mem_init_to(
+            &mut out,
+            MemInitFormat::Mif,
+            MemInitOptions::new(8).with_base_addr(0x20),
+        );
+        let res_empty =
+            empty.write_mem_init_to(&mut out, MemInitFormat::Coe, MemInitOptions::new(8));
+
+        // Assert
+        let expected = [
+            IntelHexErrorKind::InvalidWordWidth(0),
+            IntelHexErrorKind::InvalidWordValue,
+            IntelHexErrorKind::InvalidAddress(0x10),
+            IntelHexErrorKind::IntelHexInstanceEmpty,
+        ];
+        let results = [res_width, res_value, res_base, res_empty];
+        for (res, kind) in results.into_iter().zip(expected) {
+            let expected = IntelHexError::CreateRecordError(kind);
+            assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! The `options` module defines the option types which control how
+//! [`IntelHex`](crate::IntelHex) reads and writes hex files, combines data and stores
+//! multi-byte values.
+
+/// Options for parsing of Intel HEX files.
+///
+/// By default parsing is strict, i.e., it stops at the first invalid record and returns
+/// an [`IntelHexError`](crate::IntelHexError). In lenient mode the parser instead:
+/// - repairs records with a wrong checksum (their data is kept),
+/// - skips records that are corrupted, overlap already loaded data or repeat the start address,
+/// - ignores everything after the End Of File record,
+///
+/// and reports every such problem as a [`Diagnostic`](crate::Diagnostic).
+///
+/// # Example
+/// ```
+/// use intelhexlib::{IntelHex, ParseOptions};
+///
+/// let raw = b":0400100000010203E5\n:00000001FF\ngarbage";
+/// let (ih, diagnostics) = IntelHex::parse_bytes_with_options(raw, ParseOptions::lenient()).unwrap();
+///
+/// assert_eq!(ih.read_byte(0x13), Some(0x03));
+/// assert_eq!(diagnostics.len(), 2);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// Skip or repair bad records instead of aborting on the first error
+    pub lenient: bool,
+    /// Remember the original record layout (record boundaries, record order, extended address
+    /// records, line endings and letter case), so that writing the hex file back reproduces it
+    /// and only the records with changed data are regenerated.
+    /// New data outside of the original records is written before the End Of File record.
+    /// Only supported for byte-addressed files. The records are generated from scratch if the
+    /// selected [`HexFormat`] does not allow the original extended or start address records
+    /// (e.g. I8HEX), and the layout is discarded when the data is relocated.
+    pub preserve_layout: bool,
+    /// Unit of the addresses in the file (also used when writing the loaded file back)
+    pub address_unit: AddressUnit,
+}
+
+impl ParseOptions {
+    /// Creates options for strict parsing (same as default).
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            lenient: false,
+            preserve_layout: false,
+            address_unit: AddressUnit::Byte,
+        }
+    }
+
+    /// Creates options for lenient parsing.
+    #[must_use]
+    pub const fn lenient() -> Self {
+        Self {
+            lenient: true,
+            preserve_layout: false,
+            address_unit: AddressUnit::Byte,
+        }
+    }
+
+    /// Enables preservation of the original record layout.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, ParseOptions};
+    ///
+    /// let raw = b":0300300002337A1E\r\n:0400100000010203e6\r\n:00000001ff\r\n";
+    /// let options = ParseOptions::strict().with_preserved_layout();
+    /// let (mut ih, _) = IntelHex::parse_bytes_with_options(raw, options).unwrap();
+    ///
+    /// ih.update_byte(0x13, 0x0A).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_hex_to(&mut out).unwrap();
+    ///
+    /// // Record order, line endings and case are kept, only the patched record changes
+    /// assert_eq!(out, b":0300300002337A1E\r\n:040010000001020adf\r\n:00000001ff\r\n");
+    /// ```
+    #[must_use]
+    pub const fn with_preserved_layout(mut self) -> Self {
+        self.preserve_layout = true;
+        self
+    }
+
+    /// Sets the unit of the addresses in the file. See [`AddressUnit`].
+    #[must_use]
+    pub const fn with_address_unit(mut self, unit: AddressUnit) -> Self {
+        self.address_unit = unit;
+        self
+    }
+}
+
+/// Unit of the addresses in a hex file, set via
+/// [`ParseOptions::with_address_unit()`] when loading and
+/// [`IntelHex::set_address_unit()`](crate::IntelHex::set_address_unit) when writing.
+///
+/// The `IntelHex` buffer is always byte-addressed; the unit describes how the addresses
+/// of the file map to it. Word-addressed files of 16-bit and 24-bit word architectures
+/// are thereby loaded without doubled addresses or phantom bytes.
+///
+/// # Example
+/// ```
+/// use intelhexlib::{AddressUnit, IntelHex, ParseOptions};
+///
+/// // Record at word address 0x0008 holding two 16-bit words
+/// let raw = b":04000800112233444A\n:00000001FF";
+/// let options = ParseOptions::strict().with_address_unit(AddressUnit::Word(2));
+/// let (ih, _) = IntelHex::parse_bytes_with_options(raw, options).unwrap();
+///
+/// assert_eq!(ih.read_slice(0x10, 4), Some([0x11, 0x22, 0x33, 0x44].as_slice()));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AddressUnit {
+    /// Each address holds a single byte
+    #[default]
+    Byte,
+    /// Each address holds a word of the given number of bytes, e.g. 2 for 16-bit DSPs
+    /// (`Word(0)` and `Word(1)` are the same as `Byte`)
+    Word(u8),
+    /// PIC24 / dsPIC program memory: the file addresses are twice the word addresses and each
+    /// 24-bit instruction word is stored in 4 bytes, the last of them being a phantom (zero) byte.
+    /// Only the 3 real bytes of each word are kept in the buffer.
+    Pic24,
+}
+
+impl AddressUnit {
+    /// Number of bytes covered by a single address of the hex records.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::AddressUnit;
+    ///
+    /// assert_eq!(AddressUnit::Word(2).bytes_per_address(), 2);
+    /// assert_eq!(AddressUnit::Pic24.bytes_per_address(), 1);
+    /// ```
+    #[must_use]
+    pub const fn bytes_per_address(self) -> usize {
+        match self {
+            Self::Word(size) if size > 1 => size as usize,
+            Self::Byte | Self::Word(_) | Self::Pic24 => 1,
+        }
+    }
+
+    /// Returns `true` if each address holds a single byte.
+    #[must_use]
+    pub const fn is_byte(self) -> bool {
+        self.bytes_per_address() == 1 && !matches!(self, Self::Pic24)
+    }
+}
+
+/// Intel HEX flavour used when writing hex files, set via
+/// [`IntelHex::set_hex_format()`](crate::IntelHex::set_hex_format).
+///
+/// The flavours differ in the extended address records they use and hence
+/// in the address range they can represent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HexFormat {
+    /// 16-bit addresses without any extended address records (up to 64 KiB).
+    /// Has no start address records, so writing an image with a start address fails.
+    I8Hex,
+    /// 20-bit addresses via Extended Segment Address records (up to 1 MiB)
+    I16Hex,
+    /// 32-bit addresses via Extended Linear Address records (up to 4 GiB)
+    #[default]
+    I32Hex,
+}
+
+impl HexFormat {
+    /// Highest address which can be represented in the format.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::HexFormat;
+    ///
+    /// assert_eq!(HexFormat::I8Hex.max_address(), 0xFFFF);
+    /// assert_eq!(HexFormat::I16Hex.max_address(), 0xF_FFFF);
+    /// ```
+    #[must_use]
+    pub const fn max_address(self) -> usize {
+        match self {
+            Self::I8Hex => 0xFFFF,
+            Self::I16Hex => 0xF_FFFF,
+            Self::I32Hex => 0xFFFF_FFFF,
+        }
+    }
+}
+
+/// Line ending used when writing hex files.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Unix style `\n`
+    #[default]
+    Lf,
+    /// Windows style `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line ending characters.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Options for the textual layout of written Intel HEX files, set via
+/// [`IntelHex::set_hex_write_options()`](crate::IntelHex::set_hex_write_options).
+///
+/// The defaults reproduce the classic output: uppercase hex digits, LF line endings,
+/// no newline after the End Of File record and records starting at arbitrary addresses.
+/// Tuning the options allows writing files byte-identical to those of vendor tools.
+///
+/// # Example
+/// ```
+/// use intelhexlib::{HexWriteOptions, IntelHex, LineEnding};
+///
+/// let mut ih = IntelHex::new();
+/// ih.write_range(0x0E, &[0xAA, 0xBB, 0xCC]).unwrap();
+/// ih.set_hex_write_options(HexWriteOptions {
+///     line_ending: LineEnding::CrLf,
+///     trailing_newline: true,
+///     lowercase: true,
+///     align_records: true,
+///     extended_address_at_start: true,
+/// });
+///
+/// let mut out = Vec::new();
+/// ih.write_hex_to(&mut out).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     ":020000040000fa\r\n:02000e00aabb8b\r\n:01001000cc23\r\n:00000001ff\r\n"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HexWriteOptions {
+    /// Line ending written after each record
+    pub line_ending: LineEnding,
+    /// Terminate the End Of File record with a line ending as well
+    pub trailing_newline: bool,
+    /// Write hex digits in lowercase
+    pub lowercase: bool,
+    /// Split data records at multiples of the max payload size, so that each record
+    /// covers an aligned address window
+    pub align_records: bool,
+    /// Write an extended address record at the start of the file even if the data
+    /// starts in the first 64 KiB (ignored for I8HEX)
+    pub extended_address_at_start: bool,
+}
+
+/// Policy for resolving overlaps when merging two `IntelHex` instances
+/// via [`IntelHex::merge()`](crate::IntelHex::merge).
+///
+/// The policy applies both to the data and to the start address.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OverlapPolicy {
+    /// Any overlap is an error
+    #[default]
+    Error,
+    /// Overlapping data of the merged instance is dropped
+    KeepExisting,
+    /// Overlapping data of the merged instance replaces the existing data
+    Overwrite,
+    /// Overlap is allowed as long as the data is identical
+    ErrorIfDifferent,
+}
+
+/// Byte order of multi-byte values written into (or read from) the image.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Least significant byte at the lowest address
+    #[default]
+    Little,
+    /// Most significant byte at the lowest address
+    Big,
+}
+
+/// Options for reading and writing FPGA memory initialization files
+/// (see [`MemInitFormat`](crate::MemInitFormat)).
+///
+/// The files hold words of `word_width` bits at word addresses, each padded to whole bytes
+/// (`word_bytes`) in the image. The word at word address `n` occupies the bytes
+/// `base_addr + n * word_bytes` onwards, ordered by `endian`.
+///
+/// # Example
+/// ```
+/// use intelhexlib::{Endian, MemInitOptions};
+///
+/// // 32-bit block RAM of a soft-core mapped at 0x8000_0000
+/// let options = MemInitOptions::new(32)
+///     .with_endian(Endian::Little)
+///     .with_base_addr(0x8000_0000);
+///
+/// assert_eq!(options.word_width, 32);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MemInitOptions {
+    /// Width of a memory word in bits (e.g. 8, 9, 18, 32 or 36)
+    pub word_width: usize,
+    /// Byte order of the words in the image
+    pub endian: Endian,
+    /// Image address of word address 0
+    pub base_addr: usize,
+    /// Byte used in place of the gaps when whole words have to be written
+    pub gap_fill: u8,
+}
+
+impl MemInitOptions {
+    /// Creates options for words of the given width in bits, little endian, based at
+    /// address 0 and with gaps filled with 0x00.
+    #[must_use]
+    pub const fn new(word_width: usize) -> Self {
+        Self {
+            word_width,
+            endian: Endian::Little,
+            base_addr: 0,
+            gap_fill: 0x00,
+        }
+    }
+
+    /// Sets the byte order of the words.
+    #[must_use]
+    pub const fn with_endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Sets the image address of word address 0.
+    #[must_use]
+    pub const fn with_base_addr(mut self, base_addr: usize) -> Self {
+        self.base_addr = base_addr;
+        self
+    }
+
+    /// Sets the byte used in place of the gaps.
+    #[must_use]
+    pub const fn with_gap_fill(mut self, gap_fill: u8) -> Self {
+        self.gap_fill = gap_fill;
+        self
+    }
+}
+
+impl Default for MemInitOptions {
+    /// Byte-wide memory (8-bit words)
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
@@ -31,7 +31,7 @@ const HEX_TABLE: [u8; 256] = {
 
 #[allow(clippy::inline_always)]
 #[inline(always)]
-const fn fast_decode(high: u8, low: u8) -> u8 {
+pub const fn fast_decode(high: u8, low: u8) -> u8 {
     (HEX_TABLE[high as usize] << 4) | HEX_TABLE[low as usize]
 }
 
@@ -60,6 +60,72 @@ impl RecordType {
     }
 }
 
+/// Start address (entry point) of the image, stored in a Start Segment Address (03)
+/// or a Start Linear Address (05) record of the hex file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StartAddress {
+    /// Legacy 8086 real mode start address (CS:IP register values)
+    Segment { cs: u16, ip: u16 },
+    /// 32-bit linear start address (EIP register value)
+    Linear(u32),
+}
+
+impl StartAddress {
+    /// Returns the linear address the start address points to (`CS * 16 + IP` for segment one).
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::StartAddress;
+    ///
+    /// assert_eq!(StartAddress::Segment { cs: 0x1000, ip: 0x0123 }.linear(), 0x0001_0123);
+    /// assert_eq!(StartAddress::Linear(0x0800_0101).linear(), 0x0800_0101);
+    /// ```
+    #[must_use]
+    pub const fn linear(self) -> u32 {
+        match self {
+            Self::Segment { cs, ip } => (cs as u32) * 16 + ip as u32,
+            Self::Linear(address) => address,
+        }
+    }
+
+    /// Returns the record type used to store the start address in a hex file.
+    ///
+    pub(crate) const fn record_type(self) -> RecordType {
+        match self {
+            Self::Segment { .. } => RecordType::StartSegmentAddress,
+            Self::Linear(_) => RecordType::StartLinearAddress,
+        }
+    }
+
+    /// Returns the payload of the start address record (big endian).
+    ///
+    pub(crate) const fn record_data(self) -> [u8; 4] {
+        match self {
+            Self::Segment { cs, ip } => {
+                let [cs_hi, cs_lo] = cs.to_be_bytes();
+                let [ip_hi, ip_lo] = ip.to_be_bytes();
+                [cs_hi, cs_lo, ip_hi, ip_lo]
+            }
+            Self::Linear(address) => address.to_be_bytes(),
+        }
+    }
+
+    /// Creates the start address from a parsed start address record.
+    /// Returns `None` for other record types.
+    ///
+    pub(crate) fn from_record(record: &Record) -> Option<Self> {
+        let data: [u8; 4] = record.data.as_slice().try_into().ok()?;
+        match record.rtype {
+            RecordType::StartSegmentAddress => Some(Self::Segment {
+                cs: u16::from_be_bytes([data[0], data[1]]),
+                ip: u16::from_be_bytes([data[2], data[3]]),
+            }),
+            RecordType::StartLinearAddress => Some(Self::Linear(u32::from_be_bytes(data))),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Record {
     pub(crate) length: u8,
@@ -70,6 +136,37 @@ pub struct Record {
 }
 
 impl Record {
+    /// Apply an address record to the address state of a hex file: the offset of the
+    /// extended address records and the start address. Data and End Of File records
+    /// leave the state unchanged.
+    ///
+    /// # Errors
+    /// Returns `DuplicateStartAddress` if the start address was already set.
+    ///
+    pub(crate) fn apply_address(
+        &self,
+        offset: &mut usize,
+        start_addr: &mut Option<StartAddress>,
+    ) -> Result<(), IntelHexErrorKind> {
+        match self.rtype {
+            RecordType::Data | RecordType::EndOfFile => {}
+            RecordType::ExtendedSegmentAddress => {
+                *offset = (self.data[0] as usize * 256 + self.data[1] as usize) * 16;
+            }
+            RecordType::ExtendedLinearAddress => {
+                *offset = (self.data[0] as usize * 256 + self.data[1] as usize) * 65536;
+            }
+            RecordType::StartSegmentAddress | RecordType::StartLinearAddress => {
+                if start_addr.is_some() {
+                    return Err(IntelHexErrorKind::DuplicateStartAddress);
+                }
+                // Payload length was checked during record parsing
+                *start_addr = StartAddress::from_record(self);
+            }
+        }
+        Ok(())
+    }
+
     /// Calculate checksum from u8 array.
     ///
     pub(crate) fn calculate_checksum(data: &[u8]) -> u8 {
@@ -128,8 +225,8 @@ impl Record {
                 Ok(record)
             }
             RecordType::EndOfFile => Ok(String::from(":00000001FF")),
-            RecordType::ExtendedLinearAddress => {
-                // Check for data length (has to be 1 byte)
+            RecordType::ExtendedSegmentAddress | RecordType::ExtendedLinearAddress => {
+                // Check for data length (has to be 2 bytes)
                 if length != 2 {
                     return Err(IntelHexError::CreateRecordError(
                         IntelHexErrorKind::RecordLengthInvalidForType(rtype, 2, length),
@@ -190,16 +287,25 @@ impl Record {
 
                 Ok(record)
             }
-            // TODO: support legacy record?
-            RecordType::ExtendedSegmentAddress => Err(IntelHexError::CreateRecordError(
-                IntelHexErrorKind::RecordNotSupported,
-            )),
         }
     }
 
     /// Parse the record string into Record.
     ///
     pub(crate) fn parse(line: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        Self::decode(line, true)
+    }
+
+    /// Parse the record string into Record without validating its checksum.
+    /// The checksum of the returned record is recalculated from its contents.
+    ///
+    pub(crate) fn parse_unchecked(line: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        Self::decode(line, false)
+    }
+
+    /// Decode the record string into Record, optionally validating its checksum.
+    ///
+    fn decode(line: &[u8], verify_checksum: bool) -> Result<Self, IntelHexErrorKind> {
         // Check for start record
         if line[0] != b':' {
             return Err(IntelHexErrorKind::MissingStartCode);
@@ -295,15 +401,18 @@ impl Record {
         let data = decoded_hex_buf[4..data_end].to_vec();
 
         // Get checksum
-        let checksum = decoded_hex_buf[record_end - 1];
+        let mut checksum = decoded_hex_buf[record_end - 1];
 
-        // Validate checksum
+        // Validate checksum (or repair it)
         let calc_checksum = Self::calculate_checksum(&v);
         if calc_checksum != checksum {
-            return Err(IntelHexErrorKind::RecordChecksumMismatch(
-                calc_checksum,
-                checksum,
-            ));
+            if verify_checksum {
+                return Err(IntelHexErrorKind::RecordChecksumMismatch(
+                    calc_checksum,
+                    checksum,
+                ));
+            }
+            checksum = calc_checksum;
         }
 
         // Construct and return record instance
@@ -505,6 +614,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_unchecked_repairs_checksum() {
+        // Arrange
+        let record = ":0400100000010203E5";
+
+        // Act
+        let res = Record::parse_unchecked(record.as_bytes());
+
+        // Assert
+        assert_eq!(
+            res,
+            Ok(Record {
+                length: 4,
+                address: 0x10,
+                rtype: RecordType::Data,
+                data: vec![0x00, 0x01, 0x02, 0x03],
+                checksum: 0xE6,
+            })
+        );
+        assert_eq!(
+            Record::parse_unchecked(b"0400100000010203E6"),
+            Err(IntelHexErrorKind::MissingStartCode)
+        );
+    }
+
+    #[test]
+    fn test_start_address_from_record() {
+        // Arrange
+        let segment = Record::parse(b":040000031043FFAAFD");
+        let linear = Record::parse(b":040000051043FFAAFB");
+        let data = Record::parse(b":0400100000010203E6");
+
+        // Act + Assert
+        assert_eq!(
+            segment.map(|r| StartAddress::from_record(&r)),
+            Ok(Some(StartAddress::Segment {
+                cs: 0x1043,
+                ip: 0xFFAA
+            }))
+        );
+        assert_eq!(
+            linear.map(|r| StartAddress::from_record(&r)),
+            Ok(Some(StartAddress::Linear(0x1043_FFAA)))
+        );
+        assert_eq!(data.map(|r| StartAddress::from_record(&r)), Ok(None));
+    }
+
+    #[test]
+    fn test_start_address_record_data() {
+        let segment = StartAddress::Segment {
+            cs: 0x1043,
+            ip: 0xFFAA,
+        };
+        assert_eq!(segment.record_type(), RecordType::StartSegmentAddress);
+        assert_eq!(segment.record_data(), [0x10, 0x43, 0xFF, 0xAA]);
+
+        let linear = StartAddress::Linear(0x1043_FFAA);
+        assert_eq!(linear.record_type(), RecordType::StartLinearAddress);
+        assert_eq!(linear.record_data(), [0x10, 0x43, 0xFF, 0xAA]);
+    }
+
     #[test]
     fn test_create_valid_records() {
         // Data record
@@ -519,6 +689,12 @@ mod tests {
         let expected_rec_str = ":020000041122C7".to_string();
         assert_eq!(res, Ok(expected_rec_str));
 
+        // Extended Segment Address record
+        let data: [u8; 2] = [0x12, 0x00];
+        let res = Record::create(0, RecordType::ExtendedSegmentAddress, &data);
+        let expected_rec_str = ":020000021200EA".to_string();
+        assert_eq!(res, Ok(expected_rec_str));
+
         // End Of File record
         let res = Record::create(0, RecordType::EndOfFile, &[]);
         let expected_rec_str = ":00000001FF".to_string();
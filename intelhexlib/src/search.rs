@@ -0,0 +1,345 @@
+//! The `search` module defines the [`Pattern`] searched for in the data of an `IntelHex`
+//! image via [`IntelHex::find()`](crate::IntelHex::find) and the [`FindIter`] iterator over
+//! its matches. Patterns support per-bit masks (e.g. nibble wildcards such as `DE ?? BE EF`).
+//!
+//! Matching uses the Boyer-Moore-Horspool algorithm directly on the data chunks,
+//! so the image is never copied.
+
+use crate::error::IntelHexErrorKind;
+use std::collections::{VecDeque, btree_map};
+use std::str::FromStr;
+
+/// Byte pattern with a mask: a data byte matches if all its bits selected by the mask
+/// equal the pattern bits (i.e. `data & mask == pattern & mask`).
+///
+/// # Example
+/// ```
+/// use intelhexlib::Pattern;
+///
+/// // Nibble wildcards in text form
+/// let pattern: Pattern = "DE ?? B? EF".parse().unwrap();
+///
+/// // Same pattern with an explicit mask
+/// let masked = Pattern::with_mask(&[0xDE, 0x00, 0xB0, 0xEF], &[0xFF, 0x00, 0xF0, 0xFF]).unwrap();
+///
+/// assert_eq!(pattern, masked);
+/// assert_eq!(pattern.len(), 4);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    /// Pattern bytes (bits outside of the mask are zero)
+    bytes: Vec<u8>,
+    /// Bits of each byte that have to match
+    mask: Vec<u8>,
+    /// Matches may span the gaps between segments
+    across_gaps: bool,
+}
+
+impl Pattern {
+    /// Creates the pattern matching the bytes exactly.
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+            mask: vec![0xFF; bytes.len()],
+            across_gaps: false,
+        }
+    }
+
+    /// Creates the pattern matching the bits of the bytes selected by the mask.
+    ///
+    /// # Errors
+    /// Returns `SearchMaskLengthMismatch` if the mask and the bytes differ in length.
+    pub fn with_mask(bytes: &[u8], mask: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        if bytes.len() != mask.len() {
+            return Err(IntelHexErrorKind::SearchMaskLengthMismatch(
+                bytes.len(),
+                mask.len(),
+            ));
+        }
+        Ok(Self {
+            bytes: bytes.iter().zip(mask).map(|(b, m)| b & m).collect(),
+            mask: mask.to_vec(),
+            across_gaps: false,
+        })
+    }
+
+    /// Allows matches to span the gaps between segments, i.e. the data is searched
+    /// as if all segments were concatenated.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, Pattern};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x10, &[0xDE, 0xAD]).unwrap();
+    /// ih.write_range(0x20, &[0xBE, 0xEF]).unwrap();
+    ///
+    /// let pattern = Pattern::new(&[0xAD, 0xBE]);
+    ///
+    /// assert_eq!(ih.find(&pattern), None);
+    /// assert_eq!(ih.find(&pattern.across_gaps()), Some(0x11));
+    /// ```
+    #[must_use]
+    pub const fn across_gaps(mut self) -> Self {
+        self.across_gaps = true;
+        self
+    }
+
+    /// Length of the pattern in bytes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the pattern has no bytes (it matches nothing).
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Does the data byte match the pattern byte at `index`.
+    ///
+    fn matches(&self, index: usize, byte: u8) -> bool {
+        (byte ^ self.bytes[index]) & self.mask[index] == 0
+    }
+
+    /// Build the bad match table: for each value of the last byte of the window,
+    /// the shift to the next position where it can match the pattern.
+    ///
+    fn bad_match_table(&self) -> [usize; 256] {
+        let m = self.len();
+        let mut table = [m; 256];
+        for i in 0..m.saturating_sub(1) {
+            for (b, shift) in (0..=u8::MAX).zip(table.iter_mut()) {
+                if self.matches(i, b) {
+                    *shift = m - 1 - i;
+                }
+            }
+        }
+        table
+    }
+
+    /// Find the first match in the haystack starting at or after `from`.
+    ///
+    fn find_in(&self, haystack: &[u8], from: usize, table: &[usize; 256]) -> Option<usize> {
+        let m = self.len();
+        if m == 0 {
+            return None;
+        }
+
+        let mut i = from;
+        while i + m <= haystack.len() {
+            // Compare pattern from right to left
+            if (0..m).rev().all(|j| self.matches(j, haystack[i + j])) {
+                return Some(i);
+            }
+            // Mismatch -> skip using last byte of window
+            i += table[haystack[i + m - 1] as usize];
+        }
+        None
+    }
+}
+
+/// Parses the pattern from hex digits, where `?` is a wildcard for a single nibble.
+/// Whitespace is ignored, e.g. `"DE ?? BE EF"`, `"de??beef"` or `"D? ?E"`.
+impl FromStr for Pattern {
+    type Err = IntelHexErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let nibbles: Vec<(u8, u8)> = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                '?' => Some((0x0, 0x0)),
+                #[allow(clippy::cast_possible_truncation)]
+                _ => c.to_digit(16).map(|d| (d as u8, 0xF)),
+            })
+            .collect::<Option<_>>()
+            .ok_or(IntelHexErrorKind::InvalidSearchPattern)?;
+
+        if !nibbles.len().is_multiple_of(2) {
+            return Err(IntelHexErrorKind::InvalidSearchPattern);
+        }
+
+        let (bytes, mask) = nibbles
+            .chunks_exact(2)
+            .map(|pair| ((pair[0].0 << 4) | pair[1].0, (pair[0].1 << 4) | pair[1].1))
+            .unzip();
+
+        Ok(Self {
+            bytes,
+            mask,
+            across_gaps: false,
+        })
+    }
+}
+
+/// Iterator over the start addresses of all (also overlapping) matches of a [`Pattern`],
+/// in ascending order. Created by [`IntelHex::find_iter()`](crate::IntelHex::find_iter).
+#[derive(Debug)]
+pub struct FindIter<'a> {
+    /// Pattern being searched for
+    pattern: &'a Pattern,
+    /// Bad match table of the pattern
+    table: [usize; 256],
+    /// Chunks not searched yet
+    chunks: btree_map::Iter<'a, usize, Vec<u8>>,
+    /// Chunk being searched: (start address, data)
+    current: Option<(usize, &'a [u8])>,
+    /// Offset in the current chunk to continue the search from
+    pos: usize,
+    /// Matches spanning from the finished chunk into the following ones
+    pending: VecDeque<usize>,
+}
+
+impl<'a> FindIter<'a> {
+    pub(crate) fn new(
+        mut chunks: btree_map::Iter<'a, usize, Vec<u8>>,
+        pattern: &'a Pattern,
+    ) -> Self {
+        let current = chunks.next().map(|(&addr, data)| (addr, data.as_slice()));
+        Self {
+            pattern,
+            table: pattern.bad_match_table(),
+            chunks,
+            current,
+            pos: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Find the matches which start in the tail of the chunk and continue
+    /// in the following chunks (as if the chunks were concatenated).
+    ///
+    fn find_across_gap(&self, addr: usize, data: &[u8]) -> VecDeque<usize> {
+        let reach = self.pattern.len().saturating_sub(1);
+        let tail_start = data.len().saturating_sub(reach);
+
+        // Data around the gap with the address of each byte
+        let mut bytes: Vec<u8> = data[tail_start..].to_vec();
+        let mut addrs: Vec<usize> = (addr + tail_start..addr + data.len()).collect();
+        let tail_len = bytes.len();
+
+        for (&next_addr, next_data) in self.chunks.clone() {
+            let take = next_data.len().min(reach - (bytes.len() - tail_len));
+            bytes.extend_from_slice(&next_data[..take]);
+            addrs.extend(next_addr..next_addr + take);
+            if bytes.len() - tail_len == reach {
+                break;
+            }
+        }
+
+        let mut matches = VecDeque::new();
+        let mut from = 0;
+        while let Some(i) = self.pattern.find_in(&bytes, from, &self.table) {
+            if i >= tail_len {
+                break;
+            }
+            matches.push_back(addrs[i]);
+            from = i + 1;
+        }
+        matches
+    }
+}
+
+impl Iterator for FindIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(addr) = self.pending.pop_front() {
+                return Some(addr);
+            }
+
+            let (addr, data) = self.current?;
+            if let Some(offset) = self.pattern.find_in(data, self.pos, &self.table) {
+                self.pos = offset + 1;
+                return Some(addr + offset);
+            }
+
+            // Chunk finished -> continue with the next one
+            if self.pattern.across_gaps {
+                self.pending = self.find_across_gap(addr, data);
+            }
+            self.current = self.chunks.next().map(|(&a, d)| (a, d.as_slice()));
+            self.pos = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_parse_pattern() {
+        // Act
+        let res = "d? ?E\t01".parse::<Pattern>();
+
+        // Assert
+        assert_eq!(
+            res,
+            Ok(Pattern {
+                bytes: vec![0xD0, 0x0E, 0x01],
+                mask: vec![0xF0, 0x0F, 0xFF],
+                across_gaps: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern_invalid() {
+        assert_eq!(
+            "DE A".parse::<Pattern>(),
+            Err(IntelHexErrorKind::InvalidSearchPattern)
+        );
+        assert_eq!(
+            "DE AG".parse::<Pattern>(),
+            Err(IntelHexErrorKind::InvalidSearchPattern)
+        );
+        assert_eq!(
+            Pattern::with_mask(&[0x00, 0x01], &[0xFF]),
+            Err(IntelHexErrorKind::SearchMaskLengthMismatch(2, 1))
+        );
+    }
+
+    #[test]
+    fn test_find_in_with_wildcards() {
+        // Arrange
+        let pattern: Pattern = "AA ?B AA".parse().unwrap_or_else(|_| Pattern::new(&[]));
+        let table = pattern.bad_match_table();
+        let haystack = [0xAA, 0x1B, 0xAA, 0x2B, 0xAA, 0x2C, 0xAA];
+
+        // Act
+        let first = pattern.find_in(&haystack, 0, &table);
+        let second = pattern.find_in(&haystack, 1, &table);
+        let third = pattern.find_in(&haystack, 3, &table);
+
+        // Assert: overlapping matches are found
+        assert_eq!(first, Some(0));
+        assert_eq!(second, Some(2));
+        assert_eq!(third, None);
+    }
+
+    #[test]
+    fn test_find_iter_across_gaps() {
+        // Arrange: pattern spans three chunks
+        let buffer = BTreeMap::from([
+            (0x10, vec![0x01, 0x02]),
+            (0x20, vec![0x03]),
+            (0x30, vec![0x04, 0x01, 0x02, 0x03, 0x04]),
+        ]);
+        let pattern = Pattern::new(&[0x02, 0x03, 0x04]);
+        let pattern_across = pattern.clone().across_gaps();
+
+        // Act
+        let matches: Vec<usize> = FindIter::new(buffer.iter(), &pattern).collect();
+        let matches_across: Vec<usize> = FindIter::new(buffer.iter(), &pattern_across).collect();
+
+        // Assert
+        assert_eq!(matches, vec![0x32]);
+        assert_eq!(matches_across, vec![0x11, 0x32]);
+    }
+}
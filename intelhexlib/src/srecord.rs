@@ -0,0 +1,397 @@
+//! The `srecord` module defines the `SRecord` and `SRecordType` which are used for parsing
+//! (and generating) Motorola S-records (S19 / S28 / S37).
+
+use crate::IntelHexError;
+use crate::error::IntelHexErrorKind;
+use crate::record::fast_decode;
+use std::fmt::Write;
+
+mod sizes {
+    pub const SMALLEST_RECORD: usize = (1 + 2 + 1) * 2; // count + addr + checksum
+    pub const LARGEST_RECORD: usize = (1 + 255) * 2; // count + up to 255 counted bytes
+    pub const LARGEST_RECORD_HEX: usize = LARGEST_RECORD / 2;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SRecordType {
+    Header = 0x0,
+    Data16 = 0x1,
+    Data24 = 0x2,
+    Data32 = 0x3,
+    Count16 = 0x5,
+    Count24 = 0x6,
+    Start32 = 0x7,
+    Start24 = 0x8,
+    Start16 = 0x9,
+}
+
+impl SRecordType {
+    const fn parse(c: u8) -> Result<Self, IntelHexErrorKind> {
+        match c {
+            b'0' => Ok(Self::Header),
+            b'1' => Ok(Self::Data16),
+            b'2' => Ok(Self::Data24),
+            b'3' => Ok(Self::Data32),
+            b'5' => Ok(Self::Count16),
+            b'6' => Ok(Self::Count24),
+            b'7' => Ok(Self::Start32),
+            b'8' => Ok(Self::Start24),
+            b'9' => Ok(Self::Start16),
+            _ => Err(IntelHexErrorKind::InvalidRecordType),
+        }
+    }
+
+    /// Number of address bytes used by the record type.
+    pub(crate) const fn address_len(self) -> usize {
+        match self {
+            Self::Header | Self::Data16 | Self::Count16 | Self::Start16 => 2,
+            Self::Data24 | Self::Count24 | Self::Start24 => 3,
+            Self::Data32 | Self::Start32 => 4,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SRecord {
+    pub(crate) rtype: SRecordType,
+    pub(crate) address: u32,
+    pub(crate) data: Vec<u8>,
+    pub(crate) checksum: u8,
+}
+
+impl SRecord {
+    /// Calculate checksum (one's complement of the sum) from u8 array.
+    ///
+    pub(crate) fn calculate_checksum(data: &[u8]) -> u8 {
+        let mut sum: u8 = 0;
+        for b in data {
+            sum = sum.wrapping_add(*b);
+        }
+        !sum // one's complement
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    /// Create the record string from type, address and data vector.
+    ///
+    pub(crate) fn create(
+        rtype: SRecordType,
+        address: u32,
+        data: &[u8],
+    ) -> Result<String, IntelHexError> {
+        let addr_len = rtype.address_len();
+
+        // Byte count covers address, data and checksum
+        let count = addr_len + data.len() + 1;
+        if count > u8::MAX as usize {
+            return Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::RecordTooLong,
+            ));
+        }
+
+        // Check that the address fits into the address field of the record type
+        if addr_len < 4 && address >> (addr_len * 8) != 0 {
+            return Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::InvalidAddress(address as usize),
+            ));
+        }
+
+        // Only header and data records carry a payload
+        if !data.is_empty()
+            && !matches!(
+                rtype,
+                SRecordType::Header
+                    | SRecordType::Data16
+                    | SRecordType::Data24
+                    | SRecordType::Data32
+            )
+        {
+            return Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::RecordInvalidPayloadLength,
+            ));
+        }
+
+        // Create a vector of data for checksum calculation
+        let mut v = Vec::with_capacity(count);
+        v.push(count as u8);
+        v.extend_from_slice(&address.to_be_bytes()[4 - addr_len..]);
+        v.extend_from_slice(data);
+
+        // Checksum
+        let checksum = Self::calculate_checksum(&v);
+
+        // Create record string
+        let record = format!(
+            "S{}{}{:02X}",
+            rtype as u8,
+            v.iter()
+                .fold(String::with_capacity(v.len() * 2), |mut buffer, b| {
+                    let _ = write!(buffer, "{b:02X}");
+                    buffer
+                }),
+            checksum
+        );
+
+        Ok(record)
+    }
+
+    /// Parse the record string into `SRecord`.
+    ///
+    pub(crate) fn parse(line: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        // Check for start code
+        if line[0] != b'S' {
+            return Err(IntelHexErrorKind::MissingStartCode);
+        }
+
+        // Get record type
+        let rtype = SRecordType::parse(*line.get(1).ok_or(IntelHexErrorKind::RecordTooShort)?)?;
+
+        let hex_len = line.len() - 2;
+
+        // Validate record's size
+        if hex_len < sizes::SMALLEST_RECORD {
+            return Err(IntelHexErrorKind::RecordTooShort);
+        }
+        if hex_len > sizes::LARGEST_RECORD {
+            return Err(IntelHexErrorKind::RecordTooLong);
+        }
+        if !hex_len.is_multiple_of(2) {
+            return Err(IntelHexErrorKind::RecordNotEvenLength);
+        }
+
+        // Validate all characters are hexadecimal
+        if !&line[2..].iter().all(u8::is_ascii_hexdigit) {
+            return Err(IntelHexErrorKind::ContainsInvalidCharacters);
+        }
+
+        // Decode hex digits into the stack buffer (avoids heap allocations of Vec)
+        let mut decoded_hex_buf = [0u8; sizes::LARGEST_RECORD_HEX];
+        for (count, i) in (2..line.len()).step_by(2).enumerate() {
+            decoded_hex_buf[count] = fast_decode(line[i], line[i + 1]);
+        }
+        let decoded = &decoded_hex_buf[..hex_len / 2];
+
+        // Byte count has to match the number of bytes that follow it
+        let count = decoded[0] as usize;
+        if count + 1 != decoded.len() {
+            return Err(IntelHexErrorKind::RecordInvalidPayloadLength);
+        }
+
+        // Address field has to fit in
+        let addr_len = rtype.address_len();
+        if count < addr_len + 1 {
+            return Err(IntelHexErrorKind::RecordTooShort);
+        }
+
+        // Get record address (big endian)
+        let address = decoded[1..=addr_len]
+            .iter()
+            .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+
+        // Get record data payload
+        let data = decoded[1 + addr_len..count].to_vec();
+
+        // Count and start address records do not carry a payload
+        if !data.is_empty()
+            && !matches!(
+                rtype,
+                SRecordType::Header
+                    | SRecordType::Data16
+                    | SRecordType::Data24
+                    | SRecordType::Data32
+            )
+        {
+            return Err(IntelHexErrorKind::RecordInvalidPayloadLength);
+        }
+
+        // Validate checksum
+        let checksum = decoded[count];
+        let calc_checksum = Self::calculate_checksum(&decoded[..count]);
+        if calc_checksum != checksum {
+            return Err(IntelHexErrorKind::RecordChecksumMismatch(
+                calc_checksum,
+                checksum,
+            ));
+        }
+
+        // Construct and return record instance
+        Ok(Self {
+            rtype,
+            address,
+            data,
+            checksum,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns valid instances of `SRecord` and corresponding str representation
+    ///
+    fn get_valid_records() -> [(SRecord, &'static str); 6] {
+        [
+            (
+                SRecord {
+                    rtype: SRecordType::Header,
+                    address: 0x0000,
+                    data: vec![0x48, 0x44, 0x52],
+                    checksum: 0x1B,
+                },
+                "S00600004844521B",
+            ),
+            (
+                SRecord {
+                    rtype: SRecordType::Data16,
+                    address: 0x0038,
+                    data: vec![
+                        0x48, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x77, 0x6F, 0x72, 0x6C, 0x64, 0x2E,
+                        0x0A, 0x00,
+                    ],
+                    checksum: 0x42,
+                },
+                "S111003848656C6C6F20776F726C642E0A0042",
+            ),
+            (
+                SRecord {
+                    rtype: SRecordType::Data32,
+                    address: 0x0800_0000,
+                    data: vec![0x01, 0x02, 0x03, 0x04],
+                    checksum: 0xE4,
+                },
+                "S3090800000001020304E4",
+            ),
+            (
+                SRecord {
+                    rtype: SRecordType::Count16,
+                    address: 0x0003,
+                    data: vec![],
+                    checksum: 0xF9,
+                },
+                "S5030003F9",
+            ),
+            (
+                SRecord {
+                    rtype: SRecordType::Start16,
+                    address: 0x0000,
+                    data: vec![],
+                    checksum: 0xFC,
+                },
+                "S9030000FC",
+            ),
+            (
+                SRecord {
+                    rtype: SRecordType::Start32,
+                    address: 0x0800_0000,
+                    data: vec![],
+                    checksum: 0xF2,
+                },
+                "S70508000000F2",
+            ),
+        ]
+    }
+
+    /// Returns invalid record strings and corresponding errors
+    ///
+    fn get_invalid_str_records() -> [(&'static str, IntelHexErrorKind); 8] {
+        [
+            // Removed 'S' from record str
+            ("9030000FC", IntelHexErrorKind::MissingStartCode),
+            // S4 is reserved
+            ("S4030000FC", IntelHexErrorKind::InvalidRecordType),
+            // Byte count larger than the rest of the record
+            ("S9040000FC", IntelHexErrorKind::RecordInvalidPayloadLength),
+            // Too few characters
+            ("S90300FC", IntelHexErrorKind::RecordTooShort),
+            // Extra '0' added
+            ("S9030000FC0", IntelHexErrorKind::RecordNotEvenLength),
+            // Char 'Z' is not a hex digit
+            ("S903000ZFC", IntelHexErrorKind::ContainsInvalidCharacters),
+            // Checksum wrong - should be 0xE4
+            (
+                "S3090800000001020304AA",
+                IntelHexErrorKind::RecordChecksumMismatch(0xE4, 0xAA),
+            ),
+            // Start address record with payload
+            (
+                "S9040000AA51",
+                IntelHexErrorKind::RecordInvalidPayloadLength,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_valid_record_types() {
+        assert_eq!(SRecordType::parse(b'0'), Ok(SRecordType::Header));
+        assert_eq!(SRecordType::parse(b'1'), Ok(SRecordType::Data16));
+        assert_eq!(SRecordType::parse(b'3'), Ok(SRecordType::Data32));
+        assert_eq!(SRecordType::parse(b'6'), Ok(SRecordType::Count24));
+        assert_eq!(SRecordType::parse(b'9'), Ok(SRecordType::Start16));
+    }
+
+    #[test]
+    fn test_parse_invalid_record_type() {
+        assert_eq!(
+            SRecordType::parse(b'4'),
+            Err(IntelHexErrorKind::InvalidRecordType)
+        );
+        assert_eq!(
+            SRecordType::parse(b'A'),
+            Err(IntelHexErrorKind::InvalidRecordType)
+        );
+    }
+
+    #[test]
+    fn test_parse_valid_records() {
+        for (rec, rec_str) in get_valid_records() {
+            assert_eq!(SRecord::parse(rec_str.as_bytes()), Ok(rec));
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_records() {
+        for (record, expected_error) in get_invalid_str_records() {
+            assert_eq!(SRecord::parse(record.as_bytes()), Err(expected_error));
+        }
+    }
+
+    #[test]
+    fn test_create_valid_records() {
+        for (rec, rec_str) in get_valid_records() {
+            let res = SRecord::create(rec.rtype, rec.address, &rec.data);
+            assert_eq!(res, Ok(rec_str.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_create_invalid_records() {
+        // Data record too long
+        let data = [0; 253];
+        let res = SRecord::create(SRecordType::Data16, 0, &data);
+        assert_eq!(
+            res,
+            Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::RecordTooLong
+            ))
+        );
+
+        // Address does not fit into 16 bits
+        let res = SRecord::create(SRecordType::Data16, 0x1_0000, &[0x00]);
+        assert_eq!(
+            res,
+            Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::InvalidAddress(0x1_0000)
+            ))
+        );
+
+        // Start address record with payload
+        let res = SRecord::create(SRecordType::Start32, 0, &[0x00]);
+        assert_eq!(
+            res,
+            Err(IntelHexError::CreateRecordError(
+                IntelHexErrorKind::RecordInvalidPayloadLength
+            ))
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! The `stream` module extends [`IntelHex`] with streaming processing of hex files: the records
+//! are parsed one line at a time from a [`BufRead`] and passed on directly, without filling
+//! the internal buffer. Memory use therefore does not depend on the size of the file.
+
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::{IntelHex, write_gap};
+use crate::record::{Record, RecordType, StartAddress};
+use std::error::Error;
+use std::io::{BufRead, Read, Write};
+use std::ops::Range;
+
+/// Longest line read at once: start code, largest record (255 data bytes) and CRLF line ending
+const MAX_LINE_LEN: usize = 1 + (1 + 2 + 1 + 255 + 1) * 2 + 2;
+
+/// Data record: absolute address and data
+type DataRecord = (usize, Vec<u8>);
+
+/// Reader of the records of a hex file, one line at a time.
+struct RecordReader<R> {
+    reader: R,
+    /// Line buffer reused for every record
+    line: Vec<u8>,
+    /// Number of the last read (non-empty) line
+    count: usize,
+    /// Address offset of the extended address records
+    offset: usize,
+    start_addr: Option<StartAddress>,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::with_capacity(MAX_LINE_LEN),
+            count: 0,
+            offset: 0,
+            start_addr: None,
+        }
+    }
+
+    /// Read records up to the next data record and return its absolute address and data.
+    /// Returns `None` at the end of the reader. As with [`IntelHex::load_hex()`], records
+    /// following the End Of File record are processed as well.
+    ///
+    /// # Errors
+    /// - Returns an error if the reader fails
+    /// - Returns an error if the record is corrupted or the start address was already set
+    ///
+    fn next_data(&mut self) -> Result<Option<DataRecord>, Box<dyn Error>> {
+        loop {
+            // Read one line, but never more than the longest record (bounded memory)
+            self.line.clear();
+            let n = (&mut self.reader)
+                .take(MAX_LINE_LEN as u64)
+                .read_until(b'\n', &mut self.line)?;
+            if n == 0 {
+                return Ok(None);
+            }
+
+            let line = self.line.strip_suffix(b"\n").unwrap_or(&self.line);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            self.count += 1;
+
+            let record = Record::parse(line)
+                .map_err(|err| IntelHexError::ParseRecordError(err, self.count))?;
+
+            if record.rtype == RecordType::Data {
+                return Ok(Some((record.address as usize + self.offset, record.data)));
+            }
+            record
+                .apply_address(&mut self.offset, &mut self.start_addr)
+                .map_err(|err| IntelHexError::ParseRecordError(err, self.count))?;
+        }
+    }
+}
+
+impl IntelHex {
+    /// Parses the hex file from the reader one record at a time and passes the data of each
+    /// data record with its absolute address to the `on_data` callback, without storing it.
+    /// As with [`IntelHex::load_hex()`], records following the End Of File record are
+    /// processed as well.
+    ///
+    /// Returns the start address of the file (if any).
+    ///
+    /// # Errors
+    /// - Returns `ParseRecordError` if a record is corrupted or the start address is set twice
+    /// - Returns the error of the reader or of the callback
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    /// use std::io::BufReader;
+    ///
+    /// let file = std::fs::File::open("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// let mut total = 0;
+    /// IntelHex::stream_hex(BufReader::new(file), |_address, data| {
+    ///     total += data.len();
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(total, 64);
+    /// ```
+    pub fn stream_hex<R, F>(
+        reader: R,
+        mut on_data: F,
+    ) -> Result<Option<StartAddress>, Box<dyn Error>>
+    where
+        R: BufRead,
+        F: FnMut(usize, &[u8]) -> Result<(), Box<dyn Error>>,
+    {
+        let mut records = RecordReader::new(reader);
+        while let Some((address, data)) = records.next_data()? {
+            on_data(address, &data)?;
+        }
+        Ok(records.start_addr)
+    }
+
+    /// Converts the hex file from the reader into binary data written into the writer, one
+    /// record at a time. The output is the same as of [`IntelHex::write_bin_to()`]: it starts
+    /// at the lowest address and the gaps are filled with the `gap_fill` byte.
+    ///
+    /// Returns the address range of the written data (`None` if the file has no data).
+    ///
+    /// # Limitations
+    /// The data records have to be in ascending address order (as written by most tools),
+    /// so that no more than one record is held in memory and the output is written in one pass.
+    /// Out-of-order records (e.g. of linkers emitting the sections out of order) are not
+    /// reordered: the conversion stops with `RecordAddressNotAscending` and the output written
+    /// so far is incomplete. Such files can be converted via [`IntelHex::load_hex()`] and
+    /// [`IntelHex::write_bin()`] instead, e.g. as a fallback on this error.
+    ///
+    /// # Errors
+    /// - Returns `ParseRecordError` if a record is corrupted or the start address is set twice
+    /// - Returns `ParseRecordError` with `RecordAddressNotAscending` if a data record lies
+    ///   below the end of the previous one
+    /// - Returns an error if the reader or the writer fails
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    /// use std::io::BufReader;
+    ///
+    /// let file = std::fs::File::open("tests/fixtures/ih_valid_2.hex").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// let range = IntelHex::hex_to_bin(BufReader::new(file), &mut out, 0xFF).unwrap();
+    ///
+    /// assert_eq!(range, Some(0x100..0x140));
+    /// assert_eq!(out.len(), 64);
+    /// ```
+    pub fn hex_to_bin<R: BufRead, W: Write>(
+        reader: R,
+        mut writer: W,
+        gap_fill: u8,
+    ) -> Result<Option<Range<usize>>, Box<dyn Error>> {
+        let mut records = RecordReader::new(reader);
+        let mut written: Option<Range<usize>> = None;
+
+        while let Some((address, data)) = records.next_data()? {
+            match written.as_mut() {
+                // First record -> the image starts here
+                None => written = Some(address..address + data.len()),
+                // Fill the gap between the last written byte and the start of this record
+                Some(range) if address >= range.end => {
+                    write_gap(&mut writer, address - range.end, gap_fill)?;
+                    range.end = address + data.len();
+                }
+                Some(_) => {
+                    return Err(IntelHexError::ParseRecordError(
+                        IntelHexErrorKind::RecordAddressNotAscending(address),
+                        records.count,
+                    )
+                    .into());
+                }
+            }
+
+            writer.write_all(&data)?;
+        }
+
+        writer.flush()?;
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_to_bin_matches_write_bin() {
+        // Arrange: segments with gaps, extended linear address, start address
+        // and a record after the End Of File record
+        let raw = ":0400100000010203E6\n\
+                   :02000004000AF0\n\
+                   :02000000AABB99\n\
+                   :04000005000000CD2A\n\
+                   :00000001FF\n\
+                   :02001400CCDD41\n";
+        let ih = IntelHex::parse_bytes(raw.as_bytes()).unwrap_or_default();
+        let mut expected = Vec::new();
+        assert!(ih.write_bin_to(&mut expected, 0xFF).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = IntelHex::hex_to_bin(raw.as_bytes(), &mut out, 0xFF);
+
+        // Assert
+        assert_eq!(res.ok(), Some(Some(0x10..0x000A_0016)));
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_hex_to_bin_not_ascending() {
+        // Arrange: second record lies below the end of the first one
+        let raw = b":0400100000010203E6\n\n:02000E00AABB8B\n:00000001FF\n";
+
+        // Act
+        let mut out = Vec::new();
+        let res = IntelHex::hex_to_bin(&raw[..], &mut out, 0xFF);
+
+        // Assert
+        let err = res.err();
+        assert_eq!(
+            err.as_ref()
+                .and_then(|err| err.downcast_ref::<IntelHexError>()),
+            Some(&IntelHexError::ParseRecordError(
+                IntelHexErrorKind::RecordAddressNotAscending(0x0E),
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_stream_hex_records() {
+        // Arrange: data after the End Of File record is processed (same as load_hex)
+        let raw = b":02000002100AE2\r\n:02000000AABB99\r\n:0400000300001000E9\r\n\
+                    :00000001FF\r\n:02000000CCDD55\r\n";
+
+        // Act
+        let mut records = Vec::new();
+        let res = IntelHex::stream_hex(&raw[..], |address, data| {
+            records.push((address, data.to_vec()));
+            Ok(())
+        });
+
+        // Assert
+        assert_eq!(
+            res.ok(),
+            Some(Some(StartAddress::Segment {
+                cs: 0x0000,
+                ip: 0x1000
+            }))
+        );
+        assert_eq!(
+            records,
+            [(0x100A0, vec![0xAA, 0xBB]), (0x100A0, vec![0xCC, 0xDD])]
+        );
+    }
+
+    #[test]
+    fn test_stream_hex_invalid() {
+        // Arrange
+        let long_line = format!(":{}\n", "0".repeat(2 * MAX_LINE_LEN));
+        let cases: [(&[u8], IntelHexErrorKind, usize); 3] = [
+            (
+                b":02000000AABB99\n:02000000AABB98\n",
+                IntelHexErrorKind::RecordChecksumMismatch(0x99, 0x98),
+                2,
+            ),
+            (
+                b":0400000500000000F7\n:0400000500000000F7\n",
+                IntelHexErrorKind::DuplicateStartAddress,
+                2,
+            ),
+            (long_line.as_bytes(), IntelHexErrorKind::RecordTooLong, 1),
+        ];
+
+        for (raw, kind, line) in cases {
+            // Act
+            let res = IntelHex::stream_hex(raw, |_, _| Ok(()));
+
+            // Assert
+            let err = res.err();
+            assert_eq!(
+                err.as_ref()
+                    .and_then(|err| err.downcast_ref::<IntelHexError>()),
+                Some(&IntelHexError::ParseRecordError(kind, line))
+            );
+        }
+    }
+}
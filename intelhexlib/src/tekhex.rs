@@ -0,0 +1,567 @@
+//! The `tekhex` module defines the `TekRecord` used for parsing (and generating) Tektronix hex
+//! files in the standard format (`/` records with 16-bit addresses) and the extended format
+//! (`%` records with up to 64-bit addresses), and extends [`IntelHex`] with their reader and writer.
+
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::{IntelHex, create_file_writer};
+use crate::record::{StartAddress, fast_decode};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+mod sizes {
+    pub const STANDARD_HEADER: usize = 4 + 2 + 2; // address + count + header checksum
+    pub const STANDARD_MAX_PAYLOAD: usize = 0xFF;
+    pub const EXTENDED_HEADER: usize = 2 + 1 + 2; // length + type + checksum
+    pub const EXTENDED_ADDRESS_DIGITS: usize = 8;
+    pub const EXTENDED_MAX_PAYLOAD: usize =
+        (0xFF - EXTENDED_HEADER - 1 - EXTENDED_ADDRESS_DIGITS) / 2;
+}
+
+/// Flavour of Tektronix hex written by [`IntelHex::write_tekhex()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TekHexFormat {
+    /// Standard Tektronix hex: `/AAAANNHH<data>DD` records with 16-bit addresses
+    Standard,
+    /// Extended Tektronix hex: `%LLTCC<address><data>` records with 32-bit addresses
+    #[default]
+    Extended,
+}
+
+/// Record of a Tektronix hex file.
+#[derive(Debug, PartialEq, Eq)]
+enum TekRecord {
+    /// Data bytes at the address
+    Data(usize, Vec<u8>),
+    /// End of the file carrying the start address
+    Termination(usize),
+    /// Symbol record of the extended format (not loaded)
+    Symbol,
+}
+
+/// Sum of the values of the hex digits (checksum of the standard format).
+fn nibble_sum(digits: &[u8]) -> u8 {
+    digits.iter().fold(0u8, |acc, &digit| {
+        acc.wrapping_add(fast_decode(b'0', digit))
+    })
+}
+
+/// Value of a character in the checksum of the extended format.
+const fn extended_char_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'Z' => Some(c - b'A' + 10),
+        b'$' => Some(36),
+        b'%' => Some(37),
+        b'.' => Some(38),
+        b'_' => Some(39),
+        b'a'..=b'z' => Some(c - b'a' + 40),
+        _ => None,
+    }
+}
+
+/// Sum of the character values (checksum of the extended format).
+fn extended_sum(chars: &[u8]) -> Option<u8> {
+    chars.iter().try_fold(0u8, |acc, &c| {
+        extended_char_value(c).map(|value| acc.wrapping_add(value))
+    })
+}
+
+/// Decode the (validated) hex digits into a number.
+fn decode_number(digits: &[u8]) -> Result<usize, IntelHexErrorKind> {
+    digits
+        .iter()
+        .try_fold(0usize, |acc, &digit| {
+            acc.checked_mul(16)
+                .map(|acc| acc + usize::from(fast_decode(b'0', digit)))
+        })
+        .ok_or(IntelHexErrorKind::InvalidAddress(usize::MAX))
+}
+
+/// Decode the (validated) pairs of hex digits into bytes.
+fn decode_bytes(digits: &[u8]) -> Vec<u8> {
+    digits
+        .chunks_exact(2)
+        .map(|pair| fast_decode(pair[0], pair[1]))
+        .collect()
+}
+
+/// Encode the bytes as uppercase hex digits.
+fn encode_bytes(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut digits, b| {
+        let _ = write!(digits, "{b:02X}");
+        digits
+    })
+}
+
+impl TekRecord {
+    /// Parse a record of the standard format (without the leading '/').
+    fn parse_standard(line: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        // Abort record ("//...") of the programmer protocol
+        if line.starts_with(b"/") {
+            return Err(IntelHexErrorKind::RecordNotSupported);
+        }
+        if !line.iter().all(u8::is_ascii_hexdigit) {
+            return Err(IntelHexErrorKind::ContainsInvalidCharacters);
+        }
+        if line.len() < sizes::STANDARD_HEADER {
+            return Err(IntelHexErrorKind::RecordTooShort);
+        }
+
+        // Header checksum covers the address and byte count digits
+        let (header, rest) = line.split_at(sizes::STANDARD_HEADER);
+        let expected = nibble_sum(&header[..6]);
+        let actual = fast_decode(header[6], header[7]);
+        if expected != actual {
+            return Err(IntelHexErrorKind::RecordChecksumMismatch(expected, actual));
+        }
+
+        let address = decode_number(&header[..4])?;
+        let count = usize::from(fast_decode(header[4], header[5]));
+
+        // Byte count 0 marks the termination record
+        if count == 0 {
+            return if rest.is_empty() {
+                Ok(Self::Termination(address))
+            } else {
+                Err(IntelHexErrorKind::RecordInvalidPayloadLength)
+            };
+        }
+        if rest.len() != count * 2 + 2 {
+            return Err(IntelHexErrorKind::RecordInvalidPayloadLength);
+        }
+
+        // Data checksum covers the data digits
+        let (data, checksum) = rest.split_at(count * 2);
+        let expected = nibble_sum(data);
+        let actual = fast_decode(checksum[0], checksum[1]);
+        if expected != actual {
+            return Err(IntelHexErrorKind::RecordChecksumMismatch(expected, actual));
+        }
+
+        Ok(Self::Data(address, decode_bytes(data)))
+    }
+
+    /// Parse a record of the extended format (without the leading '%').
+    fn parse_extended(line: &[u8]) -> Result<Self, IntelHexErrorKind> {
+        if line.len() < sizes::EXTENDED_HEADER {
+            return Err(IntelHexErrorKind::RecordTooShort);
+        }
+        if !line[..2]
+            .iter()
+            .chain(&line[3..5])
+            .all(u8::is_ascii_hexdigit)
+        {
+            return Err(IntelHexErrorKind::ContainsInvalidCharacters);
+        }
+
+        // Length counts all characters but the leading '%'
+        if usize::from(fast_decode(line[0], line[1])) != line.len() {
+            return Err(IntelHexErrorKind::RecordInvalidPayloadLength);
+        }
+
+        // Checksum covers all characters but itself
+        let mut chars = line[..3].to_vec();
+        chars.extend_from_slice(&line[5..]);
+        let expected = extended_sum(&chars).ok_or(IntelHexErrorKind::ContainsInvalidCharacters)?;
+        let actual = fast_decode(line[3], line[4]);
+        if expected != actual {
+            return Err(IntelHexErrorKind::RecordChecksumMismatch(expected, actual));
+        }
+
+        let rtype = line[2];
+        let fields = &line[5..];
+        match rtype {
+            b'3' => return Ok(Self::Symbol),
+            b'6' | b'8' => {}
+            _ => return Err(IntelHexErrorKind::InvalidRecordType),
+        }
+        if !fields.iter().all(u8::is_ascii_hexdigit) {
+            return Err(IntelHexErrorKind::ContainsInvalidCharacters);
+        }
+
+        // Address field: number of digits (0 means 16) followed by the digits
+        let address_len = match fields.first().map(|&digit| fast_decode(b'0', digit)) {
+            Some(0) => 16,
+            Some(len) => usize::from(len),
+            None => return Err(IntelHexErrorKind::RecordTooShort),
+        };
+        if fields.len() < 1 + address_len {
+            return Err(IntelHexErrorKind::RecordTooShort);
+        }
+        let (address, data) = fields[1..].split_at(address_len);
+        let address = decode_number(address)?;
+
+        if rtype == b'8' {
+            return if data.is_empty() {
+                Ok(Self::Termination(address))
+            } else {
+                Err(IntelHexErrorKind::RecordInvalidPayloadLength)
+            };
+        }
+        if !data.len().is_multiple_of(2) {
+            return Err(IntelHexErrorKind::RecordNotEvenLength);
+        }
+        Ok(Self::Data(address, decode_bytes(data)))
+    }
+
+    /// Create a data record (or the termination record for empty data) of the standard format.
+    /// The address has to fit into 16 bits and the data into 255 bytes.
+    fn create_standard(address: usize, data: &[u8]) -> String {
+        let header = format!("{address:04X}{:02X}", data.len());
+        let mut record = format!("/{header}{:02X}", nibble_sum(header.as_bytes()));
+        if !data.is_empty() {
+            let digits = encode_bytes(data);
+            let _ = write!(record, "{digits}{:02X}", nibble_sum(digits.as_bytes()));
+        }
+        record
+    }
+
+    /// Create a data (`6`) or termination (`8`) record of the extended format.
+    /// The address has to fit into 32 bits and the data into `EXTENDED_MAX_PAYLOAD` bytes.
+    fn create_extended(rtype: char, address: usize, data: &[u8]) -> String {
+        let fields = format!(
+            "{:X}{address:0width$X}{}",
+            sizes::EXTENDED_ADDRESS_DIGITS,
+            encode_bytes(data),
+            width = sizes::EXTENDED_ADDRESS_DIGITS
+        );
+        let head = format!("{:02X}{rtype}", sizes::EXTENDED_HEADER + fields.len());
+        let mut chars = head.clone().into_bytes();
+        chars.extend_from_slice(fields.as_bytes());
+        let checksum = extended_sum(&chars).unwrap_or_default();
+        format!("%{head}{checksum:02X}{fields}")
+    }
+}
+
+impl IntelHex {
+    /// Creates an `IntelHex` instance and fills it with data from the provided
+    /// Tektronix hex file (standard or extended format).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, StartAddress};
+    ///
+    /// let ih = IntelHex::from_tekhex("tests/fixtures/tek_valid_1.tek").unwrap();
+    ///
+    /// assert_eq!(ih.read_byte(0x100), Some(0x21));
+    /// assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x100)));
+    /// ```
+    pub fn from_tekhex<P: AsRef<Path>>(filepath: P) -> Result<Self, Box<dyn Error>> {
+        let mut ih = Self::new();
+        ih.load_tekhex(filepath)?;
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with data from the provided Tektronix hex file.
+    /// Each record may be of the standard (`/`) or extended (`%`) format; symbol records
+    /// of the extended format are skipped. The termination record sets the start address.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_tekhex("tests/fixtures/tek_valid_1.tek").unwrap();
+    ///
+    /// assert_eq!(ih.get_min_addr(), Some(0x100));
+    /// ```
+    pub fn load_tekhex<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        // Parse contents
+        self.parse_tekhex(&raw_bytes)?;
+
+        Ok(())
+    }
+
+    /// Parse the raw contents of the Tektronix hex file and fill internal buffer.
+    fn parse_tekhex(&mut self, raw_bytes: &[u8]) -> Result<(), IntelHexError> {
+        let mut count: usize = 0;
+
+        // Iterate over lines of records
+        for line in raw_bytes.split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            count += 1;
+
+            let record = match line {
+                [b'/', rest @ ..] => TekRecord::parse_standard(rest),
+                [b'%', rest @ ..] => TekRecord::parse_extended(rest),
+                _ => Err(IntelHexErrorKind::MissingStartCode),
+            }
+            .map_err(|err| IntelHexError::ParseRecordError(err, count))?;
+
+            // Fill in self
+            match record {
+                TekRecord::Data(address, data) => {
+                    self.insert_bytes(address, &data).map_err(|err| match err {
+                        IntelHexError::UpdateError(kind) => {
+                            IntelHexError::ParseRecordError(kind, count)
+                        }
+                        other => other,
+                    })?;
+                }
+                TekRecord::Termination(address) => {
+                    if self.start_address().is_some() {
+                        return Err(IntelHexError::ParseRecordError(
+                            IntelHexErrorKind::DuplicateStartAddress,
+                            count,
+                        ));
+                    }
+                    let address = u32::try_from(address).map_err(|_| {
+                        IntelHexError::ParseRecordError(
+                            IntelHexErrorKind::InvalidAddress(address),
+                            count,
+                        )
+                    })?;
+                    self.set_start_address(Some(StartAddress::Linear(address)));
+                }
+                TekRecord::Symbol => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates a Tektronix hex file at the specified path.
+    /// See [`IntelHex::write_tekhex_to()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written or the data does not fit
+    /// into the address space of the format.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, TekHexFormat};
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.write_tekhex("build/ex6/ih.tek", TekHexFormat::Extended).unwrap();
+    ///
+    /// let ih_tek = IntelHex::from_tekhex("build/ex6/ih.tek").unwrap();
+    /// assert_eq!(ih.bytes().collect::<Vec<_>>(), ih_tek.bytes().collect::<Vec<_>>());
+    /// ```
+    pub fn write_tekhex<P: AsRef<Path>>(
+        &self,
+        filepath: P,
+        format: TekHexFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_tekhex_to(create_file_writer(filepath)?, format)
+    }
+
+    /// Writes Tektronix hex records into the provided writer: data records of up to
+    /// `max_payload_size` bytes, followed by the termination record with the start address
+    /// (0 if there is none).
+    ///
+    /// # Errors
+    /// Returns `CreateRecordError` with `InvalidAddress` if the data or the start address
+    /// does not fit into 16 bits (standard format). Returns an error if the writer fails.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::{IntelHex, TekHexFormat};
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x1000, &[0x01, 0x02]).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_tekhex_to(&mut out, TekHexFormat::Standard).unwrap();
+    ///
+    /// assert_eq!(out, b"/10000203010203\n/00000000\n");
+    /// ```
+    pub fn write_tekhex_to<W: Write>(
+        &self,
+        mut writer: W,
+        format: TekHexFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let start_addr = self.start_address().map_or(0, StartAddress::linear) as usize;
+
+        match format {
+            TekHexFormat::Standard => {
+                let max_addr = self.get_max_addr().unwrap_or(0).max(start_addr);
+                if max_addr > 0xFFFF {
+                    return Err(IntelHexError::CreateRecordError(
+                        IntelHexErrorKind::InvalidAddress(max_addr),
+                    )
+                    .into());
+                }
+
+                let payload_size = self.max_payload_size().min(sizes::STANDARD_MAX_PAYLOAD);
+                for (&chunk_start_addr, data) in self {
+                    for (i, payload) in data.chunks(payload_size).enumerate() {
+                        let address = chunk_start_addr + i * payload_size;
+                        writeln!(writer, "{}", TekRecord::create_standard(address, payload))?;
+                    }
+                }
+                writeln!(writer, "{}", TekRecord::create_standard(start_addr, &[]))?;
+            }
+            TekHexFormat::Extended => {
+                let payload_size = self.max_payload_size().min(sizes::EXTENDED_MAX_PAYLOAD);
+                for (&chunk_start_addr, data) in self {
+                    for (i, payload) in data.chunks(payload_size).enumerate() {
+                        let address = chunk_start_addr + i * payload_size;
+                        writeln!(
+                            writer,
+                            "{}",
+                            TekRecord::create_extended('6', address, payload)
+                        )?;
+                    }
+                }
+                writeln!(
+                    writer,
+                    "{}",
+                    TekRecord::create_extended('8', start_addr, &[])
+                )?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_standard() {
+        // Act + Assert
+        assert_eq!(
+            TekRecord::parse_standard(b"10000203010203"),
+            Ok(TekRecord::Data(0x1000, vec![0x01, 0x02]))
+        );
+        assert_eq!(
+            TekRecord::parse_standard(b"01000001"),
+            Ok(TekRecord::Termination(0x0100))
+        );
+        assert_eq!(
+            TekRecord::parse_standard(b"10000204010203"),
+            Err(IntelHexErrorKind::RecordChecksumMismatch(0x03, 0x04))
+        );
+        assert_eq!(
+            TekRecord::parse_standard(b"10000203010204"),
+            Err(IntelHexErrorKind::RecordChecksumMismatch(0x03, 0x04))
+        );
+        assert_eq!(
+            TekRecord::parse_standard(b"10000304010203"),
+            Err(IntelHexErrorKind::RecordInvalidPayloadLength)
+        );
+        assert_eq!(
+            TekRecord::parse_standard(b"/abort"),
+            Err(IntelHexErrorKind::RecordNotSupported)
+        );
+    }
+
+    #[test]
+    fn test_parse_extended() {
+        // Arrange: data record and its variants
+        let record = TekRecord::create_extended('6', 0x0800_0000, &[0xAB, 0xCD]);
+
+        // Act + Assert
+        assert_eq!(record, "%12647808000000ABCD");
+        assert_eq!(
+            TekRecord::parse_extended(&record.as_bytes()[1..]),
+            Ok(TekRecord::Data(0x0800_0000, vec![0xAB, 0xCD]))
+        );
+        assert_eq!(
+            TekRecord::parse_extended(b"098153100"),
+            Ok(TekRecord::Termination(0x100))
+        );
+        assert_eq!(
+            TekRecord::parse_extended(b"098163100"),
+            Err(IntelHexErrorKind::RecordChecksumMismatch(0x15, 0x16))
+        );
+        assert_eq!(
+            TekRecord::parse_extended(b"0A8153100"),
+            Err(IntelHexErrorKind::RecordInvalidPayloadLength)
+        );
+        assert_eq!(
+            TekRecord::parse_extended(b"094113100"),
+            Err(IntelHexErrorKind::InvalidRecordType)
+        );
+    }
+
+    #[test]
+    fn test_parse_tekhex_skips_symbols() {
+        // Arrange: symbol record, data record and termination record
+        let raw = format!(
+            "%183A24main_sec5start3100\r\n{}\r\n{}\r\n",
+            TekRecord::create_extended('6', 0x100, &[0x21]),
+            TekRecord::create_extended('8', 0x100, &[])
+        );
+
+        // Act
+        let mut ih = IntelHex::new();
+        let res = ih.parse_tekhex(raw.as_bytes());
+
+        // Assert
+        assert_eq!(res, Ok(()));
+        assert_eq!(ih.bytes().collect::<Vec<_>>(), [(0x100, 0x21)]);
+        assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x100)));
+    }
+
+    #[test]
+    fn test_parse_tekhex_invalid() {
+        // Arrange
+        let cases: [(&[u8], IntelHexErrorKind, usize); 3] = [
+            (b":10000203010203", IntelHexErrorKind::MissingStartCode, 1),
+            (
+                b"/10000203010203\n/10000203010203",
+                IntelHexErrorKind::RecordAddressOverlap(0x1000),
+                2,
+            ),
+            (
+                b"/01000001\n/01000001",
+                IntelHexErrorKind::DuplicateStartAddress,
+                2,
+            ),
+        ];
+
+        for (raw, kind, line) in cases {
+            // Act
+            let mut ih = IntelHex::new();
+            let res = ih.parse_tekhex(raw);
+
+            // Assert
+            assert_eq!(res, Err(IntelHexError::ParseRecordError(kind, line)));
+        }
+    }
+
+    #[test]
+    fn test_write_tekhex_standard_address_range() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0xFFFF, &[0x01, 0x02]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_tekhex_to(&mut out, TekHexFormat::Standard);
+
+        // Assert
+        let expected =
+            IntelHexError::CreateRecordError(IntelHexErrorKind::InvalidAddress(0x1_0000));
+        assert!(res.is_err_and(|e| e.downcast_ref::<IntelHexError>() == Some(&expected)));
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! The `titxt` module extends [`IntelHex`] with the reader and writer of TI-TXT files, as
+//! produced by MSP430 tooling: `@ADDR` lines followed by lines of hex bytes, terminated by `q`.
+
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::{IntelHex, create_file_writer};
+use crate::record::fast_decode;
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+/// Number of data bytes per line of the written file
+const BYTES_PER_LINE: usize = 16;
+
+/// Parse a hexadecimal address of an `@ADDR` line.
+fn parse_address(digits: &[u8]) -> Option<usize> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    usize::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+impl IntelHex {
+    /// Creates an `IntelHex` instance and fills it with data from the provided TI-TXT file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_titxt("tests/fixtures/ti_valid_1.txt").unwrap();
+    /// assert_eq!(ih.read_range(0xFFFE, 2), Some(vec![0x00, 0xF0]));
+    /// ```
+    pub fn from_titxt<P: AsRef<Path>>(filepath: P) -> Result<Self, Box<dyn Error>> {
+        let mut ih = Self::new();
+        ih.load_titxt(filepath)?;
+        Ok(ih)
+    }
+
+    /// Fills an `IntelHex` instance with data from the provided TI-TXT file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed:
+    /// - `ContainsInvalidCharacters` if an address or a data byte is not hexadecimal
+    /// - `MissingAddress` if data bytes are not preceded by an `@ADDR` line
+    /// - `RecordAddressOverlap` if an address is defined twice
+    /// - `DataAfterEndOfFile` if anything but whitespace follows the `q` line
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.load_titxt("tests/fixtures/ti_valid_1.txt").unwrap();
+    ///
+    /// assert_eq!(ih.get_min_addr(), Some(0xF000));
+    /// ```
+    pub fn load_titxt<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), Box<dyn Error>> {
+        // Read the contents of the file
+        let raw_bytes = std::fs::read(&filepath)?;
+
+        // Clear provided IntelHex instance
+        self.clear();
+
+        // Compute the size (in bytes)
+        self.size = raw_bytes.len();
+
+        // Load filepath
+        self.filepath = filepath.as_ref().to_path_buf();
+
+        // Parse contents
+        self.parse_titxt(&raw_bytes)?;
+
+        Ok(())
+    }
+
+    /// Parse the raw contents of the TI-TXT file and fill internal buffer.
+    fn parse_titxt(&mut self, raw_bytes: &[u8]) -> Result<(), IntelHexError> {
+        let mut count: usize = 0;
+        let mut address = None;
+        let mut eof_found = false;
+
+        // Iterate over lines
+        for line in raw_bytes.split(|&b| b == b'\n') {
+            let line = line.trim_ascii();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            count += 1;
+
+            if eof_found {
+                return Err(IntelHexError::ParseRecordError(
+                    IntelHexErrorKind::DataAfterEndOfFile,
+                    count,
+                ));
+            }
+
+            match line {
+                b"q" | b"Q" => eof_found = true,
+                [b'@', digits @ ..] => {
+                    address =
+                        Some(parse_address(digits).ok_or(IntelHexError::ParseRecordError(
+                            IntelHexErrorKind::ContainsInvalidCharacters,
+                            count,
+                        ))?);
+                }
+                _ => {
+                    let start_addr = address.ok_or(IntelHexError::ParseRecordError(
+                        IntelHexErrorKind::MissingAddress,
+                        count,
+                    ))?;
+
+                    let data = line
+                        .split(u8::is_ascii_whitespace)
+                        .filter(|byte| !byte.is_empty())
+                        .map(|byte| match byte {
+                            [high, low] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                                Ok(fast_decode(*high, *low))
+                            }
+                            _ => Err(IntelHexError::ParseRecordError(
+                                IntelHexErrorKind::ContainsInvalidCharacters,
+                                count,
+                            )),
+                        })
+                        .collect::<Result<Vec<u8>, IntelHexError>>()?;
+
+                    self.insert_bytes(start_addr, &data)
+                        .map_err(|err| match err {
+                            IntelHexError::UpdateError(kind) => {
+                                IntelHexError::ParseRecordError(kind, count)
+                            }
+                            other => other,
+                        })?;
+
+                    // Next line continues right after this one
+                    address = Some(start_addr + data.len());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates a TI-TXT file at the specified path.
+    /// See [`IntelHex::write_titxt_to()`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let ih = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex").unwrap();
+    /// ih.write_titxt("build/ex5/ih.txt").unwrap();
+    ///
+    /// let ih_txt = IntelHex::from_titxt("build/ex5/ih.txt").unwrap();
+    /// assert!(ih.diff(&ih_txt).is_empty());
+    /// ```
+    pub fn write_titxt<P: AsRef<Path>>(&self, filepath: P) -> Result<(), Box<dyn Error>> {
+        self.write_titxt_to(create_file_writer(filepath)?)
+    }
+
+    /// Writes the data as TI-TXT into the provided writer: an `@ADDR` line at the start of
+    /// each data chunk, followed by lines of up to 16 space-separated bytes, and the final `q`.
+    ///
+    /// # Errors
+    /// Returns an error if the writer fails.
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0xF000, &[0x31, 0x40, 0x00, 0x03]).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ih.write_titxt_to(&mut out).unwrap();
+    ///
+    /// assert_eq!(out, b"@F000\n31 40 00 03\nq\n");
+    /// ```
+    pub fn write_titxt_to<W: Write>(&self, mut writer: W) -> Result<(), Box<dyn Error>> {
+        for (&chunk_start_addr, data) in self {
+            writeln!(writer, "@{chunk_start_addr:04X}")?;
+            for line in data.chunks(BYTES_PER_LINE) {
+                let bytes: Vec<String> = line.iter().map(|b| format!("{b:02X}")).collect();
+                writeln!(writer, "{}", bytes.join(" "))?;
+            }
+        }
+        writeln!(writer, "q")?;
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_titxt_continues_address() {
+        // Arrange
+        let raw = b"@0100\r\n01 02\r\n\r\n03\r\n@0200\r\naa\r\nq\r\n";
+
+        // Act
+        let mut ih = IntelHex::new();
+        let res = ih.parse_titxt(raw);
+
+        // Assert
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            ih.bytes().collect::<Vec<_>>(),
+            [(0x100, 0x01), (0x101, 0x02), (0x102, 0x03), (0x200, 0xAA)]
+        );
+    }
+
+    #[test]
+    fn test_parse_titxt_invalid() {
+        // Arrange
+        let cases: [(&[u8], IntelHexErrorKind, usize); 5] = [
+            (b"01 02\nq", IntelHexErrorKind::MissingAddress, 1),
+            (
+                b"@01G0\n01\nq",
+                IntelHexErrorKind::ContainsInvalidCharacters,
+                1,
+            ),
+            (
+                b"@0100\n01 2\nq",
+                IntelHexErrorKind::ContainsInvalidCharacters,
+                2,
+            ),
+            (
+                b"@0100\n01\n@0100\n02\nq",
+                IntelHexErrorKind::RecordAddressOverlap(0x100),
+                4,
+            ),
+            (
+                b"@0100\n01\nq\n02",
+                IntelHexErrorKind::DataAfterEndOfFile,
+                4,
+            ),
+        ];
+
+        for (raw, kind, line) in cases {
+            // Act
+            let mut ih = IntelHex::new();
+            let res = ih.parse_titxt(raw);
+
+            // Assert
+            assert_eq!(res, Err(IntelHexError::ParseRecordError(kind, line)));
+        }
+    }
+
+    #[test]
+    fn test_write_titxt_line_length() {
+        // Arrange: 17 bytes -> two lines
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x1_0000, &[0x11; 17]).is_ok());
+
+        // Act
+        let mut out = Vec::new();
+        let res = ih.write_titxt_to(&mut out);
+
+        // Assert
+        assert!(res.is_ok());
+        let text = String::from_utf8(out).unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "@10000");
+        assert_eq!(lines[1], vec!["11"; 16].join(" "));
+        assert_eq!(lines[2], "11");
+        assert_eq!(lines[3], "q");
+    }
+}
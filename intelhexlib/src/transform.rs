@@ -0,0 +1,196 @@
+//! The `transform` module extends [`IntelHex`] with in-place byte order transforms over
+//! address ranges, e.g. for flash dumps read over 16-bit buses with swapped bytes or
+//! 32-bit words stored in the wrong order. The range may span across chunks, but has to
+//! be fully covered by data.
+
+use crate::chunks::{Chunk, ChunkMut};
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::IntelHex;
+use std::ops::Range;
+
+impl IntelHex {
+    /// Reverse the byte order of every `word_size` bytes in the range (2, 4 or 8),
+    /// i.e. convert between little and big endian words.
+    ///
+    /// # Errors
+    /// - Returns `InvalidWordSize` if the word size is not 2, 4 or 8
+    /// - Returns `RangeLengthNotMultiple` if the range does not consist of whole words
+    /// - Returns `InvalidAddress` with the first address of the range which holds no data
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x100, &[0x01, 0x02, 0x03, 0x04]).unwrap();
+    ///
+    /// ih.swap_bytes(0x100..0x104, 2).unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x100, 4), Some(vec![0x02, 0x01, 0x04, 0x03]));
+    /// ```
+    pub fn swap_bytes(
+        &mut self,
+        range: Range<usize>,
+        word_size: usize,
+    ) -> Result<(), IntelHexError> {
+        if !matches!(word_size, 2 | 4 | 8) {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidWordSize(word_size),
+            ));
+        }
+
+        let permutation: Vec<usize> = (0..word_size).rev().collect();
+        self.reorder(range, &permutation)
+    }
+
+    /// Reorder the bytes of every group of `permutation.len()` bytes in the range:
+    /// the byte at index `i` of a group is replaced by the byte at index `permutation[i]`.
+    ///
+    /// # Errors
+    /// - Returns `InvalidPermutation` if the permutation is not a reordering of `0..len`
+    /// - Returns `RangeLengthNotMultiple` if the range does not consist of whole groups
+    /// - Returns `InvalidAddress` with the first address of the range which holds no data
+    ///
+    /// # Example
+    /// ```
+    /// use intelhexlib::IntelHex;
+    ///
+    /// let mut ih = IntelHex::new();
+    /// ih.write_range(0x100, &[0x11, 0x22, 0x33, 0x44]).unwrap();
+    ///
+    /// // Swap the 16-bit halves of a 32-bit word
+    /// ih.reorder(0x100..0x104, &[2, 3, 0, 1]).unwrap();
+    ///
+    /// assert_eq!(ih.read_range(0x100, 4), Some(vec![0x33, 0x44, 0x11, 0x22]));
+    /// ```
+    pub fn reorder(
+        &mut self,
+        range: Range<usize>,
+        permutation: &[usize],
+    ) -> Result<(), IntelHexError> {
+        let group = permutation.len();
+        let mut seen = vec![false; group];
+        for &index in permutation {
+            match seen.get_mut(index) {
+                Some(slot) if !*slot => *slot = true,
+                _ => {
+                    return Err(IntelHexError::UpdateError(
+                        IntelHexErrorKind::InvalidPermutation,
+                    ));
+                }
+            }
+        }
+        if group == 0 {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidPermutation,
+            ));
+        }
+        if !range.len().is_multiple_of(group) {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RangeLengthNotMultiple(range.len(), group),
+            ));
+        }
+
+        // Collect the data of the whole range first, so that nothing changes on error
+        let mut data = Vec::with_capacity(range.len());
+        for chunk in self.chunks_in(range.clone()) {
+            match chunk {
+                Chunk::Data(_, bytes) => data.extend_from_slice(bytes),
+                Chunk::Gap(gap) => {
+                    return Err(IntelHexError::UpdateError(
+                        IntelHexErrorKind::InvalidAddress(gap.start),
+                    ));
+                }
+            }
+        }
+
+        let reordered: Vec<u8> = data
+            .chunks_exact(group)
+            .flat_map(|word| permutation.iter().map(|&index| word[index]))
+            .collect();
+
+        // Write back chunk by chunk
+        let mut offset = 0;
+        for chunk in self.chunks_in_mut(range) {
+            if let ChunkMut::Data(_, bytes) = chunk {
+                bytes.copy_from_slice(&reordered[offset..offset + bytes.len()]);
+                offset += bytes.len();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_bytes_across_chunks() {
+        // Arrange: 32-bit words split over two chunks
+        let mut ih = IntelHex::new();
+        assert!(
+            ih.write_range(0x10, &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06])
+                .is_ok()
+        );
+        assert!(ih.write_range(0x16, &[0x07, 0x08]).is_ok());
+
+        // Act
+        let res = ih.swap_bytes(0x10..0x18, 4);
+
+        // Assert
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            ih.read_range(0x10, 8),
+            Some(vec![0x04, 0x03, 0x02, 0x01, 0x08, 0x07, 0x06, 0x05])
+        );
+    }
+
+    #[test]
+    fn test_swap_bytes_invalid() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x01, 0x02, 0x03, 0x04]).is_ok());
+        assert!(ih.write_range(0x16, &[0x07, 0x08]).is_ok());
+
+        // Act + Assert
+        assert_eq!(
+            ih.swap_bytes(0x10..0x14, 3),
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidWordSize(3)
+            ))
+        );
+        assert_eq!(
+            ih.swap_bytes(0x10..0x13, 2),
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::RangeLengthNotMultiple(3, 2)
+            ))
+        );
+        assert_eq!(
+            ih.swap_bytes(0x10..0x18, 8),
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0x14)
+            ))
+        );
+
+        // Nothing has changed
+        assert_eq!(ih.read_range(0x10, 4), Some(vec![0x01, 0x02, 0x03, 0x04]));
+    }
+
+    #[test]
+    fn test_reorder_invalid_permutation() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x01, 0x02, 0x03, 0x04]).is_ok());
+
+        // Act + Assert
+        for permutation in [&[][..], &[0, 0], &[0, 2], &[1, 2, 3]] {
+            assert_eq!(
+                ih.reorder(0x10..0x14, permutation),
+                Err(IntelHexError::UpdateError(
+                    IntelHexErrorKind::InvalidPermutation
+                ))
+            );
+        }
+    }
+}
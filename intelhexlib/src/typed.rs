@@ -0,0 +1,256 @@
+//! The `typed` module extends [`IntelHex`] with accessors for multi-byte integer and float
+//! values (e.g. version fields, lengths and pointers) stored in the image with a given
+//! [`Endian`] byte order. Values may span across chunk boundaries.
+
+use crate::chunks::{Chunk, ChunkMut};
+use crate::error::{IntelHexError, IntelHexErrorKind};
+use crate::intelhex::IntelHex;
+use crate::options::Endian;
+
+/// Generate the `read_*` and `write_*` methods for the numeric types.
+macro_rules! typed_accessors {
+    ($($t:ty => $read:ident, $write:ident, $example:literal;)*) => {
+        $(
+            #[doc = concat!("Read a `", stringify!($t), "` value at the address with the given byte order.")]
+            ///
+            /// # Errors
+            /// Returns `InvalidAddress` with the first address of the value which holds no data.
+            ///
+            /// # Example
+            /// ```
+            /// use intelhexlib::{Endian, IntelHex};
+            ///
+            /// let mut ih = IntelHex::new();
+            /// ih.write_range(0x100, &[0; 8]).unwrap();
+            #[doc = concat!("ih.", stringify!($write), "(0x100, ", stringify!($example), ", Endian::Big).unwrap();")]
+            ///
+            #[doc = concat!("assert_eq!(ih.", stringify!($read), "(0x100, Endian::Big), Ok(", stringify!($example), "));")]
+            /// ```
+            pub fn $read(&self, address: usize, endian: Endian) -> Result<$t, IntelHexError> {
+                let bytes = self.read_array(address)?;
+                Ok(match endian {
+                    Endian::Little => <$t>::from_le_bytes(bytes),
+                    Endian::Big => <$t>::from_be_bytes(bytes),
+                })
+            }
+
+            #[doc = concat!("Write a `", stringify!($t), "` value at the address with the given byte order.")]
+            /// Only existing data is overwritten, nothing is written if any byte of the value
+            /// lies in a gap. To create new data, see [`IntelHex::write_range()`].
+            ///
+            /// # Errors
+            /// Returns `InvalidAddress` with the first address of the value which holds no data.
+            ///
+            /// # Example
+            /// ```
+            /// use intelhexlib::{Endian, IntelHex};
+            ///
+            /// let mut ih = IntelHex::new();
+            /// ih.write_range(0x100, &[0; 8]).unwrap();
+            #[doc = concat!("ih.", stringify!($write), "(0x100, ", stringify!($example), ", Endian::Little).unwrap();")]
+            ///
+            #[doc = concat!("assert_eq!(ih.", stringify!($read), "(0x100, Endian::Little), Ok(", stringify!($example), "));")]
+            /// ```
+            pub fn $write(
+                &mut self,
+                address: usize,
+                value: $t,
+                endian: Endian,
+            ) -> Result<(), IntelHexError> {
+                let bytes = match endian {
+                    Endian::Little => value.to_le_bytes(),
+                    Endian::Big => value.to_be_bytes(),
+                };
+                self.update_bytes(address, &bytes)
+            }
+        )*
+    };
+}
+
+impl IntelHex {
+    /// Read `N` bytes starting at the address, which may span across chunks.
+    ///
+    /// # Errors
+    /// Returns `InvalidAddress` with the first address which holds no data.
+    ///
+    fn read_array<const N: usize>(&self, address: usize) -> Result<[u8; N], IntelHexError> {
+        let end = address.checked_add(N).ok_or(IntelHexError::ReadError(
+            IntelHexErrorKind::InvalidAddress(address),
+        ))?;
+
+        let mut bytes = [0u8; N];
+        for chunk in self.chunks_in(address..end) {
+            match chunk {
+                Chunk::Data(addr, data) => {
+                    let offset = addr - address;
+                    bytes[offset..offset + data.len()].copy_from_slice(data);
+                }
+                Chunk::Gap(gap) => {
+                    return Err(IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(
+                        gap.start,
+                    )));
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Overwrite the bytes starting at the address, which may span across chunks.
+    /// Nothing is written if any of the bytes holds no data.
+    ///
+    /// # Errors
+    /// Returns `InvalidAddress` with the first address which holds no data.
+    ///
+    fn update_bytes(&mut self, address: usize, bytes: &[u8]) -> Result<(), IntelHexError> {
+        let end = address
+            .checked_add(bytes.len())
+            .ok_or(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(address),
+            ))?;
+
+        // Check the whole range up front, so that nothing is written on error
+        if let Some(Chunk::Gap(gap)) = self
+            .chunks_in(address..end)
+            .find(|chunk| matches!(chunk, Chunk::Gap(_)))
+        {
+            return Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(gap.start),
+            ));
+        }
+
+        for chunk in self.chunks_in_mut(address..end) {
+            if let ChunkMut::Data(addr, data) = chunk {
+                let offset = addr - address;
+                data.copy_from_slice(&bytes[offset..offset + data.len()]);
+            }
+        }
+        Ok(())
+    }
+
+    typed_accessors! {
+        u16 => read_u16, write_u16, 0x1234;
+        u32 => read_u32, write_u32, 0x1234_5678;
+        u64 => read_u64, write_u64, 0x1234_5678_9ABC_DEF0;
+        i16 => read_i16, write_i16, -2;
+        i32 => read_i32, write_i32, -2;
+        i64 => read_i64, write_i64, -2;
+        f32 => read_f32, write_f32, 1.5;
+        f64 => read_f64, write_f64, -0.25;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_across_chunks() {
+        // Arrange: value split over two chunks
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x78, 0x56]).is_ok());
+        assert!(ih.write_range(0x12, &[0x34, 0x12]).is_ok());
+
+        // Act + Assert
+        assert_eq!(ih.read_u32(0x10, Endian::Little), Ok(0x1234_5678));
+        assert_eq!(ih.read_u32(0x10, Endian::Big), Ok(0x7856_3412));
+        assert_eq!(ih.read_u16(0x11, Endian::Little), Ok(0x3456));
+        assert_eq!(ih.read_i16(0x12, Endian::Little), Ok(0x1234));
+    }
+
+    #[test]
+    fn test_read_missing_byte() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x00, 0x01]).is_ok());
+        assert!(ih.write_range(0x13, &[0x03]).is_ok());
+
+        // Act + Assert
+        assert_eq!(
+            ih.read_u32(0x10, Endian::Little),
+            Err(IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(
+                0x12
+            )))
+        );
+        assert_eq!(
+            ih.read_u16(0x0F, Endian::Little),
+            Err(IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(
+                0x0F
+            )))
+        );
+        assert_eq!(
+            ih.read_u64(usize::MAX - 3, Endian::Little),
+            Err(IntelHexError::ReadError(IntelHexErrorKind::InvalidAddress(
+                usize::MAX - 3
+            )))
+        );
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        // Arrange: data to be overwritten
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x00, &[0xFF; 16]).is_ok());
+
+        // Act
+        let res_big = ih.write_u64(0x00, 0x0102_0304_0506_0708, Endian::Big);
+        let res_float = ih.write_f32(0x08, -1.25, Endian::Little);
+        let res_signed = ih.write_i32(0x0C, i32::MIN, Endian::Big);
+
+        // Assert
+        assert!(res_big.is_ok() && res_float.is_ok() && res_signed.is_ok());
+        assert_eq!(ih.read_range(0x00, 4), Some(vec![0x01, 0x02, 0x03, 0x04]));
+        assert_eq!(ih.read_u64(0x00, Endian::Big), Ok(0x0102_0304_0506_0708));
+        assert_eq!(ih.read_f32(0x08, Endian::Little), Ok(-1.25));
+        assert_eq!(ih.read_i32(0x0C, Endian::Big), Ok(i32::MIN));
+        assert_eq!(ih.read_range(0x0C, 2), Some(vec![0x80, 0x00]));
+    }
+
+    #[test]
+    fn test_write_out_of_address_space() {
+        // Arrange
+        let mut ih = IntelHex::new();
+
+        // Act
+        let res = ih.write_u32(usize::MAX - 1, 0, Endian::Little);
+
+        // Assert
+        assert_eq!(
+            res,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(usize::MAX - 1)
+            ))
+        );
+        assert_eq!(ih.get_min_addr(), None);
+    }
+
+    #[test]
+    fn test_write_into_gap() {
+        // Arrange
+        let mut ih = IntelHex::new();
+        assert!(ih.write_range(0x10, &[0x00, 0x01]).is_ok());
+        assert!(ih.write_range(0x13, &[0x03]).is_ok());
+
+        // Act
+        let res_gap = ih.write_u32(0x10, 0xAABB_CCDD, Endian::Little);
+        let res_empty = ih.write_u16(0x20, 0xAABB, Endian::Little);
+
+        // Assert: nothing is written
+        assert_eq!(
+            res_gap,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0x12)
+            ))
+        );
+        assert_eq!(
+            res_empty,
+            Err(IntelHexError::UpdateError(
+                IntelHexErrorKind::InvalidAddress(0x20)
+            ))
+        );
+        assert_eq!(ih.read_range(0x10, 2), Some(vec![0x00, 0x01]));
+        assert_eq!(
+            ih.segments().collect::<Vec<_>>(),
+            vec![0x10..0x12, 0x13..0x14]
+        );
+    }
+}
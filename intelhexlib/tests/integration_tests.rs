@@ -1,5 +1,10 @@
-use intelhexlib::{IntelHex, IntelHexError, IntelHexErrorKind};
+use intelhexlib::{
+    Checksum, Crc, Diagnostic, DiffRange, ElfAddress, Endian, HexWriteOptions, IntelHex,
+    IntelHexError, IntelHexErrorKind, LineEnding, MemInitFormat, MemInitOptions, OverlapPolicy,
+    ParseOptions, StartAddress, TekHexFormat,
+};
 use std::fs;
+use std::io::{BufReader, BufWriter};
 
 fn compare_files(path1: &str, path2: &str) -> bool {
     // Load them in memory (small files -> OK)
@@ -115,6 +120,45 @@ fn test_load_hex_and_write_bin() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn test_from_reader_and_write_hex_to() {
+    // Define in path
+    let input_path = "tests/fixtures/ih_valid_2.hex";
+
+    // Load hex from an opened file
+    let res = fs::File::open(input_path).map(IntelHex::from_reader);
+    assert!(matches!(res, Ok(Ok(_))));
+
+    // If loaded Ok -> write it into memory
+    if let Ok(Ok(ih)) = res {
+        let mut out = Vec::new();
+        let res = ih.write_hex_to(&mut out);
+        assert!(res.is_ok());
+
+        assert_eq!(fs::read(input_path).ok(), Some(out));
+    }
+}
+
+#[test]
+fn test_parse_bytes_and_write_bin_to() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_1.hex";
+    let output_path = "build/t9/ih.bin";
+
+    // Parse hex from memory
+    let res = fs::read(input_path).map(|raw| IntelHex::parse_bytes(&raw));
+    assert!(matches!(res, Ok(Ok(_))));
+
+    // Writing into memory and into a file has to give the same result
+    if let Ok(Ok(mut ih)) = res {
+        let mut out = Vec::new();
+        assert!(ih.write_bin_to(&mut out, 0xFF).is_ok());
+        assert!(ih.write_bin(output_path, 0xFF).is_ok());
+
+        assert_eq!(fs::read(output_path).ok(), Some(out));
+    }
+}
+
 #[test]
 #[allow(clippy::panic)]
 fn test_hex_parsing_returns_error() {
@@ -142,3 +186,505 @@ fn test_hex_parsing_returns_error() {
         Ok(_) => panic!("Expected an error, but got Ok"),
     }
 }
+
+#[test]
+fn test_hex_lenient_parsing_returns_diagnostics() {
+    // Define in path
+    let input_path = "tests/fixtures/ih_damaged.hex";
+
+    // Strict parsing stops at the first problem
+    let res = IntelHex::from_hex(input_path);
+    assert!(res.is_err());
+
+    // Lenient parsing loads the data and reports all problems
+    let res = IntelHex::from_hex_with_options(input_path, ParseOptions::lenient());
+    assert!(res.is_ok());
+
+    if let Ok((ih, diagnostics)) = res {
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic {
+                    line: 2,
+                    column: 42,
+                    kind: IntelHexErrorKind::RecordChecksumMismatch(0x28, 0x29)
+                },
+                Diagnostic {
+                    line: 3,
+                    column: 13,
+                    kind: IntelHexErrorKind::ContainsInvalidCharacters
+                },
+                Diagnostic {
+                    line: 6,
+                    column: 1,
+                    kind: IntelHexErrorKind::DataAfterEndOfFile
+                },
+            ]
+        );
+
+        // Repaired record is kept, corrupted one is skipped
+        assert_eq!(ih.read_range(0x100, 0x20).map(|v| v.len()), Some(0x20));
+        assert_eq!(ih.read_byte(0x120), None);
+        assert_eq!(ih.read_byte(0x130), Some(0x3F));
+    }
+}
+
+#[test]
+fn test_from_srec_matches_hex() {
+    // Define in/out paths
+    let hex_path = "tests/fixtures/ih_valid_2.hex";
+    let srec_path = "tests/fixtures/ih_valid_2.s19";
+
+    // Load both representations of the same data
+    let res_hex = IntelHex::from_hex(hex_path);
+    let res_srec = IntelHex::from_srec(srec_path);
+    assert!(res_hex.is_ok());
+    assert!(res_srec.is_ok());
+
+    // Data has to be the same
+    if let (Ok(ih_hex), Ok(ih_srec)) = (res_hex, res_srec) {
+        assert!(ih_hex.bytes().eq(ih_srec.bytes()));
+    }
+}
+
+#[test]
+fn test_load_and_write_srec() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_2.s19";
+    let output_path = "build/t7/ih.s19";
+
+    // Load srec and write it back to the disk
+    let mut ih = IntelHex::new();
+    let res = ih.load_srec(input_path);
+    assert!(res.is_ok());
+
+    let res = ih.write_srec(output_path);
+    assert!(res.is_ok());
+
+    // Reload the written file and compare the data
+    let res = IntelHex::from_srec(output_path);
+    assert!(res.is_ok());
+    if let Ok(ih_out) = res {
+        assert!(ih.bytes().eq(ih_out.bytes()));
+    }
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_srec_parsing_returns_error() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/srec_bad_count.s19";
+
+    // Parse srec file
+    let res = IntelHex::from_srec(input_path);
+
+    // Check the error
+    match res {
+        Err(e) => {
+            if let Some(ih_err) = e.downcast_ref::<IntelHexError>() {
+                assert_eq!(
+                    ih_err,
+                    &IntelHexError::ParseRecordError(
+                        IntelHexErrorKind::RecordCountMismatch(3, 4),
+                        6
+                    )
+                );
+            } else {
+                panic!("Error was not an IntelHexError");
+            }
+        }
+        Ok(_) => panic!("Expected an error, but got Ok"),
+    }
+}
+
+#[test]
+fn test_from_elf_matches_hex() {
+    // Define in/out paths
+    let hex_path = "tests/fixtures/ih_valid_2.hex";
+    let elf32_le_path = "tests/fixtures/elf_valid_1.elf";
+    let elf64_be_path = "tests/fixtures/elf_valid_2.elf";
+
+    // Load hex and both ELF files (same segments, different class and endianness)
+    let res_hex = IntelHex::from_hex(hex_path);
+    let res_elf32 = IntelHex::from_elf(elf32_le_path, ElfAddress::Physical);
+    let res_elf64 = IntelHex::from_elf(elf64_be_path, ElfAddress::Physical);
+    assert!(res_hex.is_ok());
+    assert!(res_elf32.is_ok());
+    assert!(res_elf64.is_ok());
+
+    if let (Ok(ih_hex), Ok(ih_elf32), Ok(ih_elf64)) = (res_hex, res_elf32, res_elf64) {
+        // First segment holds the same data as the hex file
+        let hex_len = ih_hex.bytes().count();
+        assert!(ih_hex.bytes().eq(ih_elf32.bytes().take(hex_len)));
+
+        // Second segment is placed at its physical address, segment without data is skipped
+        assert_eq!(
+            ih_elf32.read_range(0x200, 4),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(ih_elf32.get_max_addr(), Some(0x203));
+
+        assert!(ih_elf32.bytes().eq(ih_elf64.bytes()));
+    }
+}
+
+#[test]
+fn test_load_elf_virtual_and_write_hex() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/elf_valid_1.elf";
+    let output_path = "build/t8/ih.hex";
+
+    // Load ELF using virtual addresses
+    let mut ih = IntelHex::new();
+    let res = ih.load_elf(input_path, ElfAddress::Virtual);
+    assert!(res.is_ok());
+    assert_eq!(ih.get_min_addr(), Some(0x2000_0100));
+    assert_eq!(ih.get_max_addr(), Some(0x2000_0203));
+
+    let res = ih.write_hex(output_path);
+    assert!(res.is_ok());
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_elf_parsing_returns_error() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/elf_truncated.elf";
+
+    // Parse ELF file
+    let res = IntelHex::from_elf(input_path, ElfAddress::Physical);
+
+    // Check the error
+    match res {
+        Err(e) => {
+            if let Some(ih_err) = e.downcast_ref::<IntelHexError>() {
+                assert_eq!(
+                    ih_err,
+                    &IntelHexError::ParseElfError(IntelHexErrorKind::ElfSegmentOutOfBounds(0))
+                );
+            } else {
+                panic!("Error was not an IntelHexError");
+            }
+        }
+        Ok(_) => panic!("Expected an error, but got Ok"),
+    }
+}
+
+#[test]
+fn test_merge_hex_and_srec() {
+    // Define in/out paths
+    let boot_path = "tests/fixtures/ih_valid_1.hex";
+    let app_path = "tests/fixtures/ih_valid_2.s19";
+    let output_path = "build/t10/ih.hex";
+
+    // Load both images
+    let res_boot = IntelHex::from_hex(boot_path);
+    let res_app = IntelHex::from_srec(app_path);
+    assert!(res_boot.is_ok());
+    assert!(res_app.is_ok());
+
+    if let (Ok(mut ih), Ok(app)) = (res_boot, res_app) {
+        let boot_bytes = ih.bytes().count();
+
+        // Merge them and merge the app again (identical data is allowed)
+        assert!(ih.merge(&app, OverlapPolicy::Error).is_ok());
+        assert!(ih.merge(&app, OverlapPolicy::ErrorIfDifferent).is_ok());
+        assert_eq!(ih.bytes().count(), boot_bytes + app.bytes().count());
+
+        // Merged image can be written and read back
+        assert!(ih.write_hex(output_path).is_ok());
+        let res = IntelHex::from_hex(output_path);
+        assert!(res.is_ok_and(|ih_out| ih_out.bytes().eq(ih.bytes())));
+    }
+}
+
+#[test]
+fn test_diff_hex_and_elf() {
+    // Define in paths
+    let hex_path = "tests/fixtures/ih_valid_2.hex";
+    let elf_path = "tests/fixtures/elf_valid_1.elf";
+
+    // Load both images
+    let res_hex = IntelHex::from_hex(hex_path);
+    let res_elf = IntelHex::from_elf(elf_path, ElfAddress::Physical);
+    assert!(res_hex.is_ok());
+    assert!(res_elf.is_ok());
+
+    if let (Ok(mut ih_hex), Ok(ih_elf)) = (res_hex, res_elf) {
+        // ELF holds an extra segment and an entry point
+        assert_eq!(
+            ih_hex.diff(&ih_elf),
+            vec![
+                DiffRange::OnlyInOther(0x200..0x204),
+                DiffRange::StartAddress
+            ]
+        );
+
+        // Changed bytes are reported as well
+        assert!(ih_hex.update_range(0x13E, &[0x00, 0x00]).is_ok());
+        assert_eq!(
+            ih_elf.diff(&ih_hex),
+            vec![
+                DiffRange::Changed(0x13E..0x140),
+                DiffRange::OnlyInSelf(0x200..0x204),
+                DiffRange::StartAddress
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_slice_and_erase_split_image() {
+    // Define in path
+    let input_path = "tests/fixtures/ih_valid_1.hex";
+
+    // Load hex and check the result
+    let res = IntelHex::from_hex(input_path);
+    assert!(res.is_ok());
+
+    if let Ok(ih) = res {
+        // Split the image into the bootloader region and the rest
+        let boot_region = 0x0000..0x0100;
+        let boot = ih.slice(boot_region.clone());
+        let mut app = ih.clone();
+        app.erase_range(boot_region.clone());
+
+        let mut app_cropped = ih.clone();
+        app_cropped.crop(0x0100..usize::MAX);
+
+        assert!(boot.bytes().all(|(addr, _)| boot_region.contains(&addr)));
+        assert!(app.bytes().all(|(addr, _)| !boot_region.contains(&addr)));
+        assert!(app.diff(&app_cropped).is_empty());
+
+        // Both parts together give back the original image
+        let mut joined = boot;
+        assert!(joined.merge(&app, OverlapPolicy::Error).is_ok());
+        assert!(joined.diff(&ih).is_empty());
+    }
+}
+
+#[test]
+fn test_embed_crc_and_write_hex() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_2.hex";
+    let output_path = "build/t11/ih.hex";
+
+    // Load hex and check the result
+    let res = IntelHex::from_hex(input_path);
+    assert!(res.is_ok());
+
+    if let Ok(mut ih) = res {
+        // CRC over the data and over the data followed by an erased (0xFF) area
+        let crc32 = Checksum::Crc(Crc::CRC_32);
+        assert_eq!(ih.checksum(0x100..0x140, crc32, 0xFF), 0x506E_38F1);
+        assert_eq!(ih.checksum(0x100..0x180, crc32, 0xFF), 0xB11C_2D98);
+
+        // Embed the CRC right after the data and write the image
+        let res = ih.embed_checksum(0x100..0x140, crc32, 0xFF, 0x140, Endian::Little);
+        assert_eq!(res, Ok(0x506E_38F1));
+        assert!(ih.write_hex(output_path).is_ok());
+
+        // CRC is present in the written image
+        let res = IntelHex::from_hex(output_path);
+        assert!(
+            res.is_ok_and(
+                |ih_out| ih_out.read_range(0x140, 4) == Some(vec![0xF1, 0x38, 0x6E, 0x50])
+            )
+        );
+    }
+}
+
+#[test]
+fn test_start_address_survives_conversion() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/elf_valid_1.elf";
+    let output_path = "build/t12/ih.hex";
+
+    // Load ELF and check the entry point
+    let res = IntelHex::from_elf(input_path, ElfAddress::Physical);
+    assert!(res.is_ok());
+
+    if let Ok(mut ih) = res {
+        assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x100)));
+
+        // Move the image and its entry point, then write it as hex
+        assert!(ih.relocate_with_start_address(0x0800_0000).is_ok());
+        assert!(ih.write_hex(output_path).is_ok());
+
+        // Start Linear Address record is read back
+        let res = IntelHex::from_hex(output_path);
+        assert!(res.is_ok_and(|ih_out| ih_out.start_address()
+            == Some(StartAddress::Linear(0x0800_0000))
+            && ih_out.diff(&ih).is_empty()));
+    }
+}
+
+#[test]
+fn test_write_hex_with_options_reads_back() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_1.hex";
+    let output_path = "build/t13/ih.hex";
+
+    let res = IntelHex::from_hex(input_path);
+    assert!(res.is_ok());
+
+    if let Ok(mut ih) = res {
+        // Write in vendor style layout
+        ih.set_hex_write_options(HexWriteOptions {
+            line_ending: LineEnding::CrLf,
+            trailing_newline: true,
+            lowercase: true,
+            align_records: true,
+            extended_address_at_start: true,
+        });
+        assert!(ih.write_hex(output_path).is_ok());
+
+        let written = fs::read_to_string(output_path).unwrap_or_default();
+        assert!(written.starts_with(":02000004"));
+        assert!(written.ends_with(":00000001ff\r\n"));
+        assert!(!written.chars().any(|c| c.is_ascii_uppercase()));
+
+        // Data is identical regardless of the layout
+        let res = IntelHex::from_hex(output_path);
+        assert!(res.is_ok_and(|ih_out| ih_out.diff(&ih).is_empty()));
+    }
+}
+
+#[test]
+fn test_preserved_layout_only_patched_record_changes() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_1.hex";
+    let output_path = "build/t14/ih.hex";
+
+    let options = ParseOptions::strict().with_preserved_layout();
+    let res = IntelHex::from_hex_with_options(input_path, options);
+    assert!(res.is_ok());
+
+    if let Ok((mut ih, _)) = res {
+        // Unchanged image is written byte-identical (incl. its segment records)
+        ih.set_max_payload_size(32).unwrap_or_default();
+        assert!(ih.write_hex(output_path).is_ok());
+        assert!(compare_files(input_path, output_path));
+
+        // Patch a single byte
+        assert!(ih.update_byte(0x1_C215, 0x00).is_ok());
+        assert!(ih.write_hex(output_path).is_ok());
+
+        let original = fs::read_to_string(input_path).unwrap_or_default();
+        let written = fs::read_to_string(output_path).unwrap_or_default();
+        let changed: Vec<(&str, &str)> = original
+            .lines()
+            .zip(written.lines())
+            .filter(|(l1, l2)| l1 != l2)
+            .collect();
+
+        assert_eq!(original.lines().count(), written.lines().count());
+        assert_eq!(
+            changed,
+            vec![(
+                ":10C21000FFFFF6F50EFE4B66F2FA0CFEF2F40EFE90",
+                ":10C21000FFFFF6F50E004B66F2FA0CFEF2F40EFE8E"
+            )]
+        );
+    }
+}
+
+#[test]
+fn test_mif_converts_to_readmemh_and_hex() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/mem_valid_1.mif";
+    let mem_path = "build/t15/rom.mem";
+    let hex_path = "build/t15/rom.hex";
+
+    // Load the 32-bit .mif image of a soft-core mapped at 0x8000_0000
+    let options = MemInitOptions::new(32).with_base_addr(0x8000_0000);
+    let res = IntelHex::from_mem_init(input_path, MemInitFormat::Mif, options);
+    assert!(res.is_ok());
+
+    if let Ok(mut ih) = res {
+        assert_eq!(ih.total_data_len(), 40);
+        assert_eq!(
+            ih.read_range(0x8000_0024, 4),
+            Some(vec![0xEF, 0xBE, 0xAD, 0xDE])
+        );
+
+        // Write the same memory for $readmemh and as hex file
+        assert!(
+            ih.write_mem_init(mem_path, MemInitFormat::ReadMemH, options)
+                .is_ok()
+        );
+        assert!(ih.write_hex(hex_path).is_ok());
+
+        // Both read back to the same image
+        let res_mem = IntelHex::from_mem_init(mem_path, MemInitFormat::ReadMemH, options);
+        let res_hex = IntelHex::from_hex(hex_path);
+        assert!(res_mem.is_ok_and(|ih_mem| ih_mem.diff(&ih).is_empty()));
+        assert!(res_hex.is_ok_and(|ih_hex| ih_hex.diff(&ih).is_empty()));
+    }
+}
+
+#[test]
+fn test_tekhex_and_titxt_convert_to_hex() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/tek_valid_1.tek";
+    let std_path = "build/t16/ih.tek";
+    let txt_path = "build/t16/ih.txt";
+
+    // Load the extended Tektronix file with the same data as ih_valid_2.hex
+    let res = IntelHex::from_tekhex(input_path);
+    assert!(res.is_ok());
+
+    if let Ok(ih) = res {
+        let res_hex = IntelHex::from_hex("tests/fixtures/ih_valid_2.hex");
+        assert!(res_hex.is_ok_and(|ih_hex| ih_hex.bytes().eq(ih.bytes())));
+        assert_eq!(ih.start_address(), Some(StartAddress::Linear(0x100)));
+
+        // Write as standard Tektronix and as TI-TXT
+        assert!(ih.write_tekhex(std_path, TekHexFormat::Standard).is_ok());
+        assert!(ih.write_titxt(txt_path).is_ok());
+
+        // Both read back to the same image
+        let res_std = IntelHex::from_tekhex(std_path);
+        let res_txt = IntelHex::from_titxt(txt_path);
+        assert!(res_std.is_ok_and(|ih_std| ih_std.diff(&ih).is_empty()));
+        assert!(res_txt.is_ok_and(|ih_txt| ih_txt.bytes().eq(ih.bytes())));
+    }
+}
+
+#[test]
+fn test_hex_to_bin_streams_ordered_files() {
+    // Define in/out paths
+    let input_path = "tests/fixtures/ih_valid_3.hex";
+    let output_path = "build/t17/ih.bin";
+
+    // Stream the hex file (records in ascending order) into a binary file
+    let input = fs::File::open(input_path);
+    let _ = fs::create_dir_all("build/t17");
+    let output = fs::File::create(output_path);
+    assert!(input.is_ok() && output.is_ok());
+
+    if let (Ok(input), Ok(output)) = (input, output) {
+        let res = IntelHex::hex_to_bin(BufReader::new(input), BufWriter::new(output), 0xFF);
+        assert_eq!(res.ok(), Some(Some(0x100..0x134)));
+    }
+
+    // Same output as loading the whole file
+    let res = IntelHex::from_hex(input_path);
+    assert!(res.is_ok());
+    if let Ok(ih) = res {
+        let mut expected = Vec::new();
+        assert!(ih.write_bin_to(&mut expected, 0xFF).is_ok());
+        assert_eq!(fs::read(output_path).ok(), Some(expected));
+    }
+
+    // Records of ih_valid_1.hex are not in ascending order -> cannot be streamed
+    let file = fs::read("tests/fixtures/ih_valid_1.hex").unwrap_or_default();
+    let res = IntelHex::hex_to_bin(file.as_slice(), std::io::sink(), 0xFF);
+    assert!(res.is_err_and(|err| {
+        err.downcast_ref::<IntelHexError>()
+            == Some(&IntelHexError::ParseRecordError(
+                IntelHexErrorKind::RecordAddressNotAscending(0),
+                7,
+            ))
+    }));
+}